@@ -1,12 +1,17 @@
 use anyhow::{Context, Result};
 use bc_envelope::prelude::*;
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashSet};
+use std::ops::Range;
 
 use crate::{
-    envelope_indexed_objects_for_predicate, test_envelope_roundtrip, Indexed, NoQuotesDebugOption
+    decode_attachments, encode_attachments, envelope_indexed_objects_for_predicate,
+    test_envelope_roundtrip, Blob, Indexed, NoQuotesDebugOption, SetIndexes,
 };
 
-use super::{Address, OrchardSentOutput, TxId, sapling::SaplingSentOutput};
+use super::{
+    Address, OrchardSentOutput, ProtocolAddress, SeedFingerprint, TxId, sapling::SaplingSentOutput,
+    transparent::TransparentSpendAuthority,
+};
 
 /// A logical grouping of addresses and transaction history within a wallet.
 ///
@@ -65,6 +70,9 @@ pub struct Account {
     name: String,
 
     zip32_account_id: Option<u32>,
+
+    // The seed this account's keys are derived from, in wallets holding several seeds.
+    seed_fingerprint: Option<SeedFingerprint>,
     addresses: Vec<Address>,
 
     // Subset of the global transaction history.
@@ -84,6 +92,7 @@ impl std::fmt::Debug for Account {
             .field("index", &self.index)
             .field("name", &self.name)
             .field("zip32_account_id", &NoQuotesDebugOption(&self.zip32_account_id))
+            .field("seed_fingerprint", &NoQuotesDebugOption(&self.seed_fingerprint))
             .field("addresses", &self.addresses)
             .field("relevant_transactions", &self.relevant_transactions)
             .field("sapling_sent_outputs", &self.sapling_sent_outputs)
@@ -111,6 +120,7 @@ impl Account {
             index: 0,
             name: String::default(),
             zip32_account_id: None,
+            seed_fingerprint: None,
             addresses: Vec::new(),
             relevant_transactions: HashSet::new(),
             sapling_sent_outputs: Vec::new(),
@@ -135,10 +145,23 @@ impl Account {
         self.zip32_account_id = Some(id);
     }
 
+    /// The fingerprint of the wallet seed this account is derived from, if known.
+    pub fn seed_fingerprint(&self) -> Option<&SeedFingerprint> {
+        self.seed_fingerprint.as_ref()
+    }
+
+    pub fn set_seed_fingerprint(&mut self, fingerprint: SeedFingerprint) {
+        self.seed_fingerprint = Some(fingerprint);
+    }
+
     pub fn addresses(&self) -> &Vec<Address> {
         &self.addresses
     }
 
+    pub fn addresses_mut(&mut self) -> &mut [Address] {
+        &mut self.addresses
+    }
+
     pub fn addresses_len(&self) -> usize {
         self.addresses.len()
     }
@@ -148,6 +171,90 @@ impl Account {
         self.addresses.push(address);
     }
 
+    /// Reassigns contiguous indexes, in storage order, to the account's
+    /// addresses and sent outputs.
+    pub fn reindex_all(&mut self) {
+        self.addresses = std::mem::take(&mut self.addresses).set_indexes();
+        self.sapling_sent_outputs = std::mem::take(&mut self.sapling_sent_outputs).set_indexes();
+        self.orchard_sent_outputs = std::mem::take(&mut self.orchard_sent_outputs).set_indexes();
+    }
+
+    /// Calls `f` with every `Blob<N>` held by the account's addresses and sent
+    /// outputs, in storage order.
+    ///
+    /// See [`Zewif::visit_blobs`](crate::Zewif::visit_blobs) for the fields
+    /// that are visited.
+    pub fn visit_blobs<const N: usize, F: FnMut(&Blob<N>)>(&self, mut f: F) {
+        for address in &self.addresses {
+            match address.address() {
+                ProtocolAddress::Transparent(address) => {
+                    if let Some(TransparentSpendAuthority::SpendingKey(key)) =
+                        address.spend_authority()
+                    {
+                        key.as_blob().visit_sized(&mut f);
+                    }
+                }
+                ProtocolAddress::Tex(_) => {}
+                ProtocolAddress::Sapling(address) => {
+                    if let Some(key) = address.incoming_viewing_key() {
+                        key.as_blob().visit_sized(&mut f);
+                    }
+                    if let Some(key) = address.full_viewing_key() {
+                        key.as_blob().visit_sized(&mut f);
+                    }
+                    if let Some(key) = address.spending_key() {
+                        key.as_blob().visit_sized(&mut f);
+                    }
+                    if let Some(index) = address.diversifier_index() {
+                        index.visit_sized(&mut f);
+                    }
+                }
+                ProtocolAddress::Unified(address) => {
+                    if let Some(index) = address.diversifier_index() {
+                        index.visit_sized(&mut f);
+                    }
+                }
+            }
+        }
+        for output in &self.sapling_sent_outputs {
+            output.diversifier().visit_sized(&mut f);
+        }
+        for output in &self.orchard_sent_outputs {
+            output.diversifier().visit_sized(&mut f);
+        }
+    }
+
+    /// Returns the ranges of external-chain transparent address indices that are
+    /// missing below the highest stored index.
+    ///
+    /// HD wallets derive transparent addresses sequentially, so a missing index
+    /// means part of the chain has not been derived or scanned. Only transparent
+    /// and TEX addresses with derivation information on the external
+    /// (non-change) chain are considered. The ranges are half-open and in ascending order.
+    pub fn derivation_gaps(&self) -> Vec<Range<u32>> {
+        let indices: BTreeSet<u32> = self
+            .addresses
+            .iter()
+            .filter_map(|address| match address.address() {
+                ProtocolAddress::Transparent(address) => address.derivation_info(),
+                ProtocolAddress::Tex(address) => address.derivation_info(),
+                _ => None,
+            })
+            .filter(|info| u32::from(info.change()) == 0)
+            .map(|info| u32::from(info.address_index()))
+            .collect();
+
+        let mut gaps = Vec::new();
+        let mut next = 0;
+        for index in indices {
+            if index > next {
+                gaps.push(next..index);
+            }
+            next = index.saturating_add(1);
+        }
+        gaps
+    }
+
     pub fn relevant_transactions(&self) -> &HashSet<TxId> {
         &self.relevant_transactions
     }
@@ -200,13 +307,14 @@ impl From<Account> for Envelope {
             .add_type("Account")
             .add_assertion("name", value.name)
             .add_optional_assertion("zip32_account_id", value.zip32_account_id)
+            .add_optional_assertion("seed_fingerprint", value.seed_fingerprint)
             .add_assertion("relevant_transactions", value.relevant_transactions.sort_by_cbor_encoding()); // Deterministic ordering
 
         e = value.addresses.iter().fold(e, |e, address| e.add_assertion("address", address.clone()));
         e = value.sapling_sent_outputs.iter().fold(e, |e, output| e.add_assertion("sapling_sent_output", output.clone()));
         e = value.orchard_sent_outputs.iter().fold(e, |e, output| e.add_assertion("orchard_sent_output", output.clone()));
 
-        value.attachments.add_to_envelope(e)
+        encode_attachments(&value.attachments, e)
     }
 }
 
@@ -219,18 +327,20 @@ impl TryFrom<Envelope> for Account {
         let index = envelope.extract_subject().context("index")?;
         let name = envelope.extract_object_for_predicate("name").context("name")?;
         let zip32_account_id = envelope.extract_optional_object_for_predicate("zip32_account_id").context("zip32_account_id")?;
+        let seed_fingerprint = envelope.try_optional_object_for_predicate("seed_fingerprint").context("seed_fingerprint")?;
         let relevant_transactions = envelope.extract_object_for_predicate("relevant_transactions").context("relevant_transactions")?;
 
         let addresses = envelope_indexed_objects_for_predicate(&envelope, "address").context("addresses")?;
         let sapling_sent_outputs = envelope_indexed_objects_for_predicate(&envelope, "sapling_sent_output").context("sapling_sent_outputs")?;
         let orchard_sent_outputs = envelope_indexed_objects_for_predicate(&envelope, "orchard_sent_output").context("orchard_sent_outputs")?;
 
-        let attachments = Attachments::try_from_envelope(&envelope).context("attachments")?;
+        let attachments = decode_attachments::<Self>(&envelope).context("attachments")?;
 
         Ok(Self {
             index,
             name,
             zip32_account_id,
+            seed_fingerprint,
             addresses,
             relevant_transactions,
             sapling_sent_outputs,
@@ -240,15 +350,39 @@ impl TryFrom<Envelope> for Account {
     }
 }
 
+impl crate::EnvelopeSchema for Account {
+    const TYPE_NAME: &'static str = "Account";
+    const ATTACHMENTS: bool = true;
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["name", "relevant_transactions"]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &[
+            "zip32_account_id",
+            "seed_fingerprint",
+            "address",
+            "sapling_sent_output",
+            "orchard_sent_output",
+        ]
+    }
+
+    fn check_nested_strict(envelope: &Envelope) -> Result<()> {
+        crate::check_objects_strict::<Address>(envelope, "address")?;
+        crate::check_objects_strict::<SaplingSentOutput>(envelope, "sapling_sent_output")?;
+        crate::check_objects_strict::<OrchardSentOutput>(envelope, "orchard_sent_output")
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for Account {
     fn random() -> Self {
-        use crate::SetIndexes;
-
         Self {
             index: 0,
             name: String::random(),
             zip32_account_id: u32::opt_random(),
+            seed_fingerprint: SeedFingerprint::opt_random(),
             addresses: Vec::random().set_indexes(),
             relevant_transactions: HashSet::random(),
             sapling_sent_outputs: Vec::random().set_indexes(),
@@ -259,3 +393,37 @@ impl crate::RandomInstance for Account {
 }
 
 test_envelope_roundtrip!(Account);
+
+#[cfg(test)]
+mod tests {
+    use super::Account;
+    use crate::{Address, DerivationInfo, ProtocolAddress, transparent};
+
+    fn derived(change: u32, index: u32) -> Address {
+        let mut address = transparent::Address::new(format!("t1address{}_{}", change, index));
+        address.set_derivation_info(DerivationInfo::new(change.into(), index.into()));
+        Address::new(ProtocolAddress::Transparent(address))
+    }
+
+    #[test]
+    fn test_derivation_gaps() {
+        let mut account = Account::new();
+        for index in [0, 1, 3, 4] {
+            account.add_address(derived(0, index));
+        }
+        // Change addresses and addresses without derivation info are ignored.
+        account.add_address(derived(1, 9));
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1imported"),
+        )));
+        assert_eq!(account.derivation_gaps(), vec![2..3]);
+
+        let mut account = Account::new();
+        for index in [2, 3, 7] {
+            account.add_address(derived(0, index));
+        }
+        assert_eq!(account.derivation_gaps(), vec![0..2, 4..7]);
+
+        assert!(Account::new().derivation_gaps().is_empty());
+    }
+}
@@ -1,6 +1,7 @@
-use crate::{ DebugOption, Indexed, test_envelope_roundtrip };
+use crate::{ BlockHeight, DebugOption, Indexed, Network, decode_attachments, encode_attachments, test_envelope_roundtrip_strict };
 use anyhow::{ Result, Context };
 use bc_envelope::prelude::*;
+use dcbor::Date;
 
 use super::ProtocolAddress;
 
@@ -63,6 +64,12 @@ pub struct Address {
     /// Optional description of this address's purpose
     purpose: Option<String>,
 
+    /// The block height at which the source wallet created this address, if recorded
+    created_at_height: Option<BlockHeight>,
+
+    /// The time at which the source wallet created this address, if recorded
+    created_at_time: Option<Date>,
+
     /// Additional metadata attached to this address
     attachments: Attachments,
 }
@@ -83,6 +90,8 @@ impl std::fmt::Debug for Address {
             .field("address", &self.address)
             .field("name", &self.name)
             .field("purpose", &DebugOption(&self.purpose))
+            .field("created_at_height", &DebugOption(&self.created_at_height))
+            .field("created_at_time", &DebugOption(&self.created_at_time))
             .field("attachments", &self.attachments)
             .finish()
     }
@@ -113,10 +122,35 @@ impl Address {
             address,
             name: String::default(),
             purpose: None,
+            created_at_height: None,
+            created_at_time: None,
             attachments: Attachments::new(),
         }
     }
 
+    /// Returns the block height at which the source wallet created this address, if known.
+    ///
+    /// Creation heights bound how far back a rescan must start to find funds
+    /// received at this address.
+    pub fn created_at_height(&self) -> Option<BlockHeight> {
+        self.created_at_height
+    }
+
+    /// Sets the block height at which this address was created.
+    pub fn set_created_at_height(&mut self, height: BlockHeight) {
+        self.created_at_height = Some(height);
+    }
+
+    /// Returns the time at which the source wallet created this address, if known.
+    pub fn created_at_time(&self) -> Option<&Date> {
+        self.created_at_time.as_ref()
+    }
+
+    /// Sets the time at which this address was created.
+    pub fn set_created_at_time(&mut self, time: Date) {
+        self.created_at_time = Some(time);
+    }
+
     /// Returns the user-assigned name for this address.
     ///
     /// # Returns
@@ -220,6 +254,24 @@ impl Address {
         &self.address
     }
 
+    /// Returns the network the address belongs to, as identified by the
+    /// encoding of its protocol address.
+    ///
+    /// See [`ProtocolAddress::network`] for how each kind of address is
+    /// identified; `None` is returned if the address string cannot be decoded.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Address, Network, ProtocolAddress, transparent};
+    /// let address = Address::new(ProtocolAddress::Transparent(
+    ///     transparent::Address::from_pubkey_hash(Network::Main, &[0u8; 20]),
+    /// ));
+    /// assert_eq!(address.network(), Some(Network::Main));
+    /// ```
+    pub fn network(&self) -> Option<Network> {
+        self.address.network()
+    }
+
     /// Returns a mutable reference to the protocol-specific address.
     ///
     /// # Returns
@@ -296,8 +348,10 @@ impl From<Address> for Envelope {
             .add_type("Address")
             .add_assertion("address", value.address)
             .add_assertion("name", value.name)
-            .add_optional_assertion("purpose", value.purpose);
-        value.attachments.add_to_envelope(envelope)
+            .add_optional_assertion("purpose", value.purpose)
+            .add_optional_assertion("created_at_height", value.created_at_height)
+            .add_optional_assertion("created_at_time", value.created_at_time);
+        encode_attachments(&value.attachments, envelope)
     }
 }
 
@@ -314,17 +368,45 @@ impl TryFrom<Envelope> for Address {
         let purpose = envelope
             .try_optional_object_for_predicate("purpose")
             .context("purpose")?;
-        let attachments = Attachments::try_from_envelope(&envelope).context("attachments")?;
+        let created_at_height = envelope
+            .try_optional_object_for_predicate("created_at_height")
+            .context("created_at_height")?;
+        let created_at_time = envelope
+            .try_optional_object_for_predicate("created_at_time")
+            .context("created_at_time")?;
+        let attachments = decode_attachments::<Self>(&envelope).context("attachments")?;
         Ok(Address {
             index,
             address,
             name,
             purpose,
+            created_at_height,
+            created_at_time,
             attachments,
         })
     }
 }
 
+impl crate::EnvelopeSchema for Address {
+    const TYPE_NAME: &'static str = "Address";
+    const ATTACHMENTS: bool = true;
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["address", "name"]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["purpose", "created_at_height", "created_at_time"]
+    }
+
+    fn check_nested_strict(envelope: &Envelope) -> Result<()> {
+        for object in envelope.objects_for_predicate("address") {
+            ProtocolAddress::check_strict(&object).context("address")?;
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for Address {
     fn random() -> Self {
@@ -332,10 +414,70 @@ impl crate::RandomInstance for Address {
             index: 0,
             name: String::random(),
             purpose: String::opt_random(),
+            created_at_height: BlockHeight::opt_random(),
+            created_at_time: rand::random::<bool>()
+                .then(|| Date::from_timestamp(rand::random::<u32>() as f64)),
             address: ProtocolAddress::random(),
             attachments: Attachments::random(),
         }
     }
 }
 
-test_envelope_roundtrip!(Address);
+test_envelope_roundtrip_strict!(Address);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+    use dcbor::Date;
+
+    use super::Address;
+    use crate::{BlockHeight, Network, ProtocolAddress, UnifiedAddress, sapling, transparent};
+
+    #[test]
+    fn test_network() {
+        for network in [Network::Main, Network::Test] {
+            let transparent = Address::new(ProtocolAddress::Transparent(
+                transparent::Address::from_script_hash(network, &[1u8; 20]),
+            ));
+            assert_eq!(transparent.network(), Some(network));
+
+            let sapling = Address::new(ProtocolAddress::Sapling(Box::new(
+                sapling::Address::from_raw(network, &[2u8; 43]),
+            )));
+            assert_eq!(sapling.network(), Some(network));
+        }
+
+        // Regtest transparent addresses share testnet's version bytes.
+        let regtest = Address::new(ProtocolAddress::Transparent(
+            transparent::Address::from_pubkey_hash(Network::Regtest, &[3u8; 20]),
+        ));
+        assert_eq!(regtest.network(), Some(Network::Test));
+
+        let unified = Address::new(ProtocolAddress::Unified(Box::new(UnifiedAddress::new(
+            "u1feukxdlmv7ancxyz7afwza6tz0edrqjvte3kwy0mhjuzz2ggmexjwlsze3qy8vku75ngtf94la6ucu4vvk3h2z6c6d0zmeqahv4cpxteyns4mxx6yrctummd05qede2y8c63kuwraqz".to_string(),
+        ))));
+        assert_eq!(unified.network(), Some(Network::Main));
+
+        let unknown = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1example",
+        )));
+        assert_eq!(unknown.network(), None);
+    }
+
+    #[test]
+    fn test_creation_metadata_roundtrip() {
+        let mut address = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1example",
+        )));
+        address.set_created_at_height(BlockHeight::from_u32(2_000_000));
+        address.set_created_at_time(Date::from_ymd_hms(2023, 2, 1, 12, 30, 0));
+
+        let decoded = Address::try_from(Envelope::from(address.clone())).unwrap();
+        assert_eq!(decoded, address);
+        assert_eq!(decoded.created_at_height(), Some(BlockHeight::from_u32(2_000_000)));
+        assert_eq!(
+            decoded.created_at_time(),
+            Some(&Date::from_ymd_hms(2023, 2, 1, 12, 30, 0))
+        );
+    }
+}
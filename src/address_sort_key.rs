@@ -0,0 +1,81 @@
+use std::cmp::Ordering;
+
+use super::{Address, Indexed, ProtocolAddress};
+
+/// The key used to order addresses for display.
+///
+/// Addresses are stored within each [`Account`](crate::Account) in `Indexed`
+/// order, which reflects the order in which they were imported. For display,
+/// users often want a different ordering; `AddressSortKey` selects one without
+/// touching the stored order. Sorting is stable, so addresses that compare equal
+/// under the chosen key keep their storage order.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Account, Address, AddressSortKey, Network, ProtocolAddress, ZewifWallet, transparent};
+/// #
+/// let mut account = Account::new();
+/// for name in ["Savings", "Donations"] {
+///     let mut address = Address::new(ProtocolAddress::Transparent(transparent::Address::new("t1example")));
+///     address.set_name(name.to_string());
+///     account.add_address(address);
+/// }
+/// let mut wallet = ZewifWallet::new(Network::Main);
+/// wallet.add_account(account);
+///
+/// let names: Vec<&str> = wallet
+///     .addresses_sorted_by(AddressSortKey::Name)
+///     .into_iter()
+///     .map(|address| address.name())
+///     .collect();
+/// assert_eq!(names, vec!["Donations", "Savings"]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AddressSortKey {
+    /// Accounts in order, then addresses by ascending index within each
+    /// account.
+    ///
+    /// [`compare`](Self::compare) compares only the address indexes, which
+    /// each account numbers from zero, so callers keep accounts in order
+    /// themselves, as
+    /// [`ZewifWallet::addresses_sorted_by`](crate::ZewifWallet::addresses_sorted_by)
+    /// does.
+    Index,
+
+    /// Lexicographic order of the user-assigned name.
+    Name,
+
+    /// Grouped by protocol: transparent (including TEX), then Sapling, then
+    /// unified addresses.
+    Protocol,
+
+    /// Ascending creation height; addresses without a recorded height sort last.
+    CreatedAtHeight,
+}
+
+impl AddressSortKey {
+    /// Compares two addresses under this key.
+    pub fn compare(&self, a: &Address, b: &Address) -> Ordering {
+        match self {
+            AddressSortKey::Index => a.index().cmp(&b.index()),
+            AddressSortKey::Name => a.name().cmp(b.name()),
+            AddressSortKey::Protocol => protocol_rank(a.address()).cmp(&protocol_rank(b.address())),
+            AddressSortKey::CreatedAtHeight => {
+                match (a.created_at_height(), b.created_at_height()) {
+                    (Some(a), Some(b)) => a.cmp(&b),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                }
+            }
+        }
+    }
+}
+
+fn protocol_rank(address: &ProtocolAddress) -> u8 {
+    match address {
+        ProtocolAddress::Transparent(_) | ProtocolAddress::Tex(_) => 0,
+        ProtocolAddress::Sapling(_) => 1,
+        ProtocolAddress::Unified(_) => 2,
+    }
+}
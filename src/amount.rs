@@ -7,7 +7,10 @@ use anyhow::{Error, Result, anyhow, bail};
 use bc_envelope::prelude::*;
 
 use super::parser::prelude::*;
-use crate::{format_signed_zats_as_zec, parse, test_cbor_roundtrip, test_envelope_roundtrip};
+use crate::{
+    AmountUnit, Blob, DisplayOpts, format_signed_zats_as_zec, group_digits, parse,
+    test_cbor_roundtrip, test_envelope_roundtrip,
+};
 
 /// Number of zatoshis (zats) in 1 ZEC
 pub const COIN: u64 = 1_0000_0000;
@@ -39,6 +42,13 @@ pub const MAX_BALANCE: i64 = MAX_MONEY as i64;
 /// maintaining precise balances and transaction amounts during wallet migration.
 /// When displayed, values are formatted as ZEC with decimal places.
 ///
+/// # Conversions
+/// Integer conversions are in zatoshis:
+/// - `TryFrom<i64>` fails outside `-MAX_BALANCE..=MAX_BALANCE`
+/// - `TryFrom<u64>` fails above `MAX_MONEY`
+/// - `From<Amount> for i64` always succeeds
+/// - `TryFrom<Amount> for u64` fails for negative amounts
+///
 /// # Examples
 /// ```
 /// # use zewif::Amount;
@@ -156,6 +166,83 @@ impl Amount {
         Amount::from_u64(amount)
     }
 
+    /// Parses an Amount from a hex string encoding a signed 64-bit
+    /// little-endian zatoshi value, as some legacy wallet formats store it.
+    ///
+    /// The string must be exactly 16 hex digits. Returns an error if it is not
+    /// valid hex of that length, or if the amount is outside the range
+    /// `{-MAX_BALANCE..MAX_BALANCE}`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Amount;
+    /// let amount = Amount::from_hex_zatoshis("00e1f50500000000").unwrap();
+    /// assert_eq!(i64::from(amount), 100_000_000);
+    /// ```
+    pub fn from_hex_zatoshis(s: &str) -> Result<Self> {
+        let bytes = Blob::<8>::from_hex(s)
+            .map_err(|e| anyhow!("Invalid hex zatoshi amount \"{}\": {}", s, e))?;
+        Amount::from_i64_le_bytes(bytes.into())
+    }
+
+    /// Parses an Amount from a decimal ZEC string such as `"1.23456789"`.
+    ///
+    /// The string may start with `+` or `-`, and either the integer part or
+    /// the fractional part may be omitted, as in `".5"` or `"2"`, but not both.
+    /// Only ASCII digits and a single `.` are accepted otherwise.
+    ///
+    /// Returns an error if the string is malformed, if it has more than eight
+    /// decimal places, since a zatoshi is the smallest unit, or if its
+    /// magnitude exceeds `MAX_MONEY`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Amount;
+    /// let amount = Amount::from_zec_str("1.23456789").unwrap();
+    /// assert_eq!(i64::from(amount), 123_456_789);
+    /// assert_eq!(i64::from(Amount::from_zec_str("-.5").unwrap()), -50_000_000);
+    /// assert!(Amount::from_zec_str("0.123456789").is_err());
+    /// ```
+    pub fn from_zec_str(s: &str) -> Result<Self> {
+        let (negative, unsigned) = match s.as_bytes().first() {
+            Some(b'-') => (true, &s[1..]),
+            Some(b'+') => (false, &s[1..]),
+            _ => (false, s),
+        };
+        let (integer, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+        let is_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer)
+            || !is_digits(fraction)
+            || (integer.is_empty() && fraction.is_empty())
+            || (unsigned.ends_with('.') && fraction.is_empty())
+        {
+            bail!("Invalid ZEC amount \"{}\"", s);
+        }
+        if fraction.len() > 8 {
+            bail!("Invalid ZEC amount \"{}\": more than 8 decimal places", s);
+        }
+
+        let integer_zats = if integer.is_empty() {
+            Some(0)
+        } else {
+            integer
+                .parse::<u64>()
+                .ok()
+                .and_then(|zec| zec.checked_mul(COIN))
+        };
+        let fraction_zats = if fraction.is_empty() {
+            0
+        } else {
+            fraction.parse::<u64>()? * 10u64.pow(8 - fraction.len() as u32)
+        };
+        let zats = integer_zats
+            .and_then(|zats| zats.checked_add(fraction_zats))
+            .filter(|zats| *zats <= MAX_MONEY)
+            .ok_or_else(|| anyhow!("ZEC amount overflow: {}", s))?;
+        let amount = Amount(zats as i64);
+        Ok(if negative { -amount } else { amount })
+    }
+
     /// Returns the Amount encoded as a signed 64-bit little-endian integer.
     pub fn to_i64_le_bytes(self) -> [u8; 8] {
         self.0.to_le_bytes()
@@ -173,6 +260,97 @@ impl Amount {
         self.0.is_negative()
     }
 
+    /// Returns the magnitude of the Amount.
+    ///
+    /// The result is always in range, since valid amounts are bounded by
+    /// `MAX_MONEY` in both directions.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Amount;
+    /// let spend = Amount::from_i64(-150_000_000).unwrap();
+    /// assert!(spend.is_negative());
+    /// assert_eq!(spend.abs(), Amount::from_u64(150_000_000).unwrap());
+    /// ```
+    pub const fn abs(self) -> Self {
+        Amount(self.0.abs())
+    }
+
+    /// Formats the Amount for presentation according to `opts`.
+    ///
+    /// See [`DisplayOpts`] for the available options; the defaults render a
+    /// plain ZEC value with trailing zeros trimmed.
+    pub fn to_display_string(&self, opts: &DisplayOpts) -> String {
+        let sign = if self.is_negative() { "-" } else { "" };
+        let zats = self.0.unsigned_abs();
+        let group = |digits: String| match opts.thousands_separator {
+            Some(separator) => group_digits(&digits, separator),
+            None => digits,
+        };
+
+        let number = match opts.unit {
+            AmountUnit::Zatoshi => group(zats.to_string()),
+            AmountUnit::Zec => {
+                let integer = group((zats / COIN).to_string());
+                let fraction = format!("{:08}", zats % COIN);
+                let fraction = if opts.trim_trailing_zeros {
+                    fraction.trim_end_matches('0')
+                } else {
+                    &fraction
+                };
+                if fraction.is_empty() {
+                    integer
+                } else {
+                    format!("{}.{}", integer, fraction)
+                }
+            }
+        };
+
+        if opts.unit_label {
+            format!("{}{} {}", sign, number, opts.unit.label())
+        } else {
+            format!("{}{}", sign, number)
+        }
+    }
+
+    /// Adds two Amounts, returning `None` if the result falls outside
+    /// `-MAX_BALANCE..=MAX_BALANCE`.
+    ///
+    /// The bound is the Zcash `MAX_MONEY` supply limit, far below the range of
+    /// `i64`, so a sum is rejected once it could no longer be a real balance.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Amount, MAX_MONEY};
+    /// let max = Amount::const_from_u64(MAX_MONEY);
+    /// let one = Amount::const_from_u64(1);
+    /// assert_eq!(max.checked_add(Amount::zero()), Some(max));
+    /// assert_eq!(max.checked_add(one), None);
+    /// ```
+    pub fn checked_add(self, rhs: Amount) -> Option<Amount> {
+        Amount::from_i64(self.0.checked_add(rhs.0)?).ok()
+    }
+
+    /// Subtracts `rhs` from `self`, returning `None` if the result falls
+    /// outside `-MAX_BALANCE..=MAX_BALANCE`.
+    ///
+    /// A negative result within range is returned as a negative Amount, as
+    /// when a balance is reduced by more than it holds.
+    pub fn checked_sub(self, rhs: Amount) -> Option<Amount> {
+        Amount::from_i64(self.0.checked_sub(rhs.0)?).ok()
+    }
+
+    /// Formats the Amount as a decimal ZEC string, such as `"1.5"` or
+    /// `"-0.00000001"`.
+    ///
+    /// Trailing zeros in the fraction are omitted, and so is the decimal point
+    /// for whole amounts, but all eight decimal places are kept when needed,
+    /// so [`from_zec_str`](Self::from_zec_str) always recovers the exact
+    /// value.
+    pub fn to_zec_string(&self) -> String {
+        self.to_display_string(&DisplayOpts::default())
+    }
+
     /// Sums a collection of Amount values with overflow checking.
     ///
     /// This helper method safely adds a collection of Amounts, returning None if
@@ -205,11 +383,19 @@ impl Amount {
     /// # }
     /// ```
     pub fn sum<I: IntoIterator<Item = Amount>>(values: I) -> Option<Amount> {
-        let mut result = Amount::zero();
-        for value in values {
-            result = (result + value)?;
-        }
-        Some(result)
+        Self::checked_sum(values)
+    }
+
+    /// Sums a collection of Amount values with [`checked_add`](Self::checked_add),
+    /// returning `None` as soon as a partial sum leaves the valid range.
+    ///
+    /// Because every partial sum is checked, positive values that alone would
+    /// exceed `MAX_MONEY` are rejected even if later negative values would
+    /// bring the total back into range.
+    pub fn checked_sum<I: IntoIterator<Item = Amount>>(values: I) -> Option<Amount> {
+        values
+            .into_iter()
+            .try_fold(Amount::zero(), Amount::checked_add)
     }
 }
 
@@ -222,6 +408,15 @@ impl TryFrom<i64> for Amount {
     }
 }
 
+/// Converts a u64 into an Amount, failing if it exceeds `MAX_MONEY`
+impl TryFrom<u64> for Amount {
+    type Error = Error;
+
+    fn try_from(value: u64) -> Result<Self> {
+        Amount::from_u64(value)
+    }
+}
+
 /// Extracts the raw i64 zatoshi value from an Amount
 impl From<Amount> for i64 {
     fn from(amount: Amount) -> i64 {
@@ -248,12 +443,21 @@ impl TryFrom<Amount> for u64 {
     }
 }
 
+/// Converts an Amount reference to u64, ensuring the value is non-negative
+impl TryFrom<&Amount> for u64 {
+    type Error = Error;
+
+    fn try_from(value: &Amount) -> Result<Self, Self::Error> {
+        u64::try_from(*value)
+    }
+}
+
 /// Adds two Amounts, checking for overflow/underflow
 impl Add<Amount> for Amount {
     type Output = Option<Amount>;
 
     fn add(self, rhs: Amount) -> Option<Amount> {
-        Amount::from_i64(self.0 + rhs.0).ok()
+        self.checked_add(rhs)
     }
 }
 
@@ -271,7 +475,7 @@ impl Sub<Amount> for Amount {
     type Output = Option<Amount>;
 
     fn sub(self, rhs: Amount) -> Option<Amount> {
-        Amount::from_i64(self.0 - rhs.0).ok()
+        self.checked_sub(rhs)
     }
 }
 
@@ -365,3 +569,199 @@ impl crate::RandomInstance for Amount {
 
 test_cbor_roundtrip!(Amount);
 test_envelope_roundtrip!(Amount);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{AmountUnit, DisplayOpts};
+
+    use super::{Amount, MAX_BALANCE, MAX_MONEY};
+
+    #[test]
+    fn test_integer_conversions() {
+        assert_eq!(
+            Amount::try_from(MAX_MONEY).unwrap(),
+            Amount::const_from_u64(MAX_MONEY)
+        );
+        assert!(Amount::try_from(MAX_MONEY + 1).is_err());
+        assert!(Amount::try_from(u64::MAX).is_err());
+
+        assert_eq!(
+            Amount::try_from(-MAX_BALANCE).unwrap(),
+            -Amount::const_from_u64(MAX_MONEY)
+        );
+        assert!(Amount::try_from(MAX_BALANCE + 1).is_err());
+        assert!(Amount::try_from(-MAX_BALANCE - 1).is_err());
+        assert!(Amount::try_from(i64::MIN).is_err());
+
+        let positive = Amount::const_from_i64(150_000_000);
+        let negative = Amount::const_from_i64(-150_000_000);
+        assert_eq!(i64::from(positive), 150_000_000);
+        assert_eq!(i64::from(&negative), -150_000_000);
+        assert_eq!(u64::try_from(positive).unwrap(), 150_000_000);
+        assert_eq!(u64::try_from(&Amount::zero()).unwrap(), 0);
+        assert!(u64::try_from(negative).is_err());
+    }
+
+    #[test]
+    fn test_negative_amounts() {
+        let spend = Amount::from_i64(-150_000_000).unwrap();
+        assert!(spend.is_negative());
+        assert!(!spend.is_positive());
+        assert_eq!(spend.abs(), Amount::const_from_u64(150_000_000));
+        assert_eq!(spend.abs().abs(), spend.abs());
+        assert_eq!(Amount::zero().abs(), Amount::zero());
+        assert_eq!(
+            (-Amount::const_from_u64(MAX_MONEY)).abs(),
+            Amount::const_from_u64(MAX_MONEY)
+        );
+        assert!(Amount::from_nonnegative_i64(-1).is_err());
+
+        // Negative amounts encode as negative CBOR integers.
+        let cbor = CBOR::from(spend);
+        assert_eq!(
+            cbor.to_cbor_data(),
+            CBOR::from(-150_000_000i64).to_cbor_data()
+        );
+        assert_eq!(Amount::try_from(cbor).unwrap(), spend);
+        assert_eq!(Amount::try_from(Envelope::from(spend)).unwrap(), spend);
+        assert!(Amount::try_from(CBOR::from(-MAX_BALANCE - 1)).is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let max = Amount::const_from_u64(MAX_MONEY);
+        let one = Amount::const_from_i64(1);
+        let two = Amount::const_from_i64(2);
+
+        assert_eq!(one.checked_add(two), Some(Amount::const_from_i64(3)));
+        assert_eq!(max.checked_add(one), None);
+        assert_eq!((-max).checked_add(-one), None);
+        assert_eq!(max.checked_add(-max), Some(Amount::zero()));
+
+        // Results may be negative, down to -MAX_MONEY.
+        assert_eq!(one.checked_sub(two), Some(-one));
+        assert_eq!(Amount::zero().checked_sub(max), Some(-max));
+        assert_eq!((-max).checked_sub(one), None);
+        assert_eq!(max.checked_sub(-one), None);
+
+        assert_eq!(
+            Amount::checked_sum([one, two, -one]),
+            Some(Amount::const_from_i64(2))
+        );
+        assert_eq!(Amount::checked_sum([]), Some(Amount::zero()));
+        assert_eq!(Amount::checked_sum([max, Amount::zero()]), Some(max));
+        // An intermediate sum above MAX_MONEY fails, even if the total would not.
+        assert_eq!(Amount::checked_sum([max, one, -two]), None);
+        assert_eq!(Amount::checked_sum(vec![max; 3]), None);
+        assert_eq!(Amount::sum([max, one]), None);
+        assert_eq!([max, one].into_iter().sum::<Option<Amount>>(), None);
+    }
+
+    #[test]
+    fn test_zec_strings() {
+        for (s, zats, formatted) in [
+            ("1.23456789", 123_456_789, "1.23456789"),
+            ("+1.5", 150_000_000, "1.5"),
+            ("-0.00000001", -1, "-0.00000001"),
+            (".5", 50_000_000, "0.5"),
+            ("-.5", -50_000_000, "-0.5"),
+            ("2", 200_000_000, "2"),
+            ("2.10", 210_000_000, "2.1"),
+            ("0", 0, "0"),
+            ("-0", 0, "0"),
+            ("007.00000000", 700_000_000, "7"),
+            ("21000000", MAX_BALANCE, "21000000"),
+            ("-21000000.00000000", -MAX_BALANCE, "-21000000"),
+        ] {
+            let amount = Amount::from_zec_str(s).unwrap();
+            assert_eq!(i64::from(amount), zats, "{}", s);
+            assert_eq!(amount.to_zec_string(), formatted);
+            assert_eq!(Amount::from_zec_str(formatted).unwrap(), amount);
+        }
+
+        for zats in [1, 10, 99_999_999, 100_000_001, MAX_BALANCE - 1] {
+            for amount in [Amount::const_from_i64(zats), -Amount::const_from_i64(zats)] {
+                assert_eq!(
+                    Amount::from_zec_str(&amount.to_zec_string()).unwrap(),
+                    amount
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_zec_string_rejections() {
+        let error = Amount::from_zec_str("0.123456789").unwrap_err();
+        assert!(error.to_string().contains("more than 8 decimal places"));
+        assert!(Amount::from_zec_str("1.000000000").is_err());
+        assert!(Amount::from_zec_str("-.123456789").is_err());
+
+        let error = Amount::from_zec_str("21000000.00000001").unwrap_err();
+        assert!(error.to_string().contains("overflow"));
+        assert!(Amount::from_zec_str("-21000001").is_err());
+        assert!(Amount::from_zec_str("99999999999999999999").is_err());
+
+        for s in [
+            "", "+", "-", ".", "1.", "+-1", "--1", "1.2.3", "1,5", " 1", "1 ", "1e8", "0x10", "١",
+        ] {
+            assert!(Amount::from_zec_str(s).is_err(), "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_from_hex_zatoshis() {
+        let amount = Amount::const_from_u64(123_456_789);
+        let hex = hex::encode(amount.to_i64_le_bytes());
+        assert_eq!(hex, "15cd5b0700000000");
+        assert_eq!(Amount::from_hex_zatoshis(&hex).unwrap(), amount);
+        assert_eq!(
+            Amount::from_hex_zatoshis("C050F279B78FFFFF").unwrap(),
+            -Amount::const_from_u64(123_456_789_000_000)
+        );
+
+        let over_range = hex::encode((MAX_BALANCE + 1).to_le_bytes());
+        let error = Amount::from_hex_zatoshis(&over_range).unwrap_err();
+        assert!(error.to_string().contains("overflow"));
+
+        assert!(Amount::from_hex_zatoshis("15cd5b07000000zz").is_err());
+        assert!(Amount::from_hex_zatoshis("15cd5b07").is_err());
+        assert!(Amount::from_hex_zatoshis("15cd5b070000000").is_err());
+    }
+
+    #[test]
+    fn test_display_grouped_zec() {
+        let opts = DisplayOpts::default().with_thousands_separator(',');
+        let amount = Amount::const_from_u64(1_234_567_890_000_000);
+        assert_eq!(amount.to_display_string(&opts), "12,345,678.9");
+        assert_eq!(
+            (-amount).to_display_string(&opts.with_unit_label(true)),
+            "-12,345,678.9 ZEC"
+        );
+        assert_eq!(
+            amount.to_display_string(&opts.with_trim_trailing_zeros(false)),
+            "12,345,678.90000000"
+        );
+        assert_eq!(
+            Amount::const_from_u64(100_000_000).to_display_string(&opts),
+            "1"
+        );
+        assert_eq!(
+            Amount::const_from_i64(-1).to_display_string(&opts),
+            "-0.00000001"
+        );
+        assert_eq!(Amount::zero().to_display_string(&opts), "0");
+    }
+
+    #[test]
+    fn test_display_zatoshi() {
+        let opts = DisplayOpts::default().with_unit(AmountUnit::Zatoshi);
+        let amount = Amount::const_from_u64(1_234_567_890);
+        assert_eq!(amount.to_display_string(&opts), "1234567890");
+        assert_eq!(
+            (-amount).to_display_string(&opts.with_thousands_separator('_').with_unit_label(true)),
+            "-1_234_567_890 zat"
+        );
+    }
+}
@@ -1,4 +1,9 @@
-use super::u256;
+use std::fmt;
+
+use anyhow::Context;
+use bc_envelope::prelude::*;
+
+use crate::{parse, parser::prelude::*, test_cbor_roundtrip, test_envelope_roundtrip, u256};
 
 /// A commitment tree root used for proving membership of shielded notes.
 ///
@@ -16,14 +21,17 @@ use super::u256;
 /// 3. The note has not been previously spent
 ///
 /// The anchors are critical for maintaining privacy while preventing double-spending.
+/// A witness kept by a wallet authenticates its note against one such root, so the
+/// anchor recorded with a witness can be matched against the anchors a
+/// [`Transaction`](crate::Transaction) spent from.
 ///
 /// # Data Preservation
 /// `Anchor` preserves the exact 256-bit root hash values from wallet data, which are
 /// needed when reconstructing or validating shielded transactions during wallet migration.
 ///
 /// # Technical Implementation
-/// Technically, `Anchor` is an alias for the [`u256`](crate::u256) type, which represents
-/// a 256-bit unsigned integer used for cryptographic values.
+/// `Anchor` wraps a [`u256`](crate::u256) and encodes to CBOR and envelopes exactly as
+/// the underlying value does.
 ///
 /// # Examples
 /// ```
@@ -31,6 +39,116 @@ use super::u256;
 /// #
 /// // Create an anchor from a u256 value
 /// let anchor_value = u256::default(); // Usually this would be a real tree root
-/// let anchor: Anchor = anchor_value;
+/// let anchor = Anchor::from(anchor_value);
+/// assert_eq!(u256::from(anchor), anchor_value);
 /// ```
-pub type Anchor = u256;
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Anchor(u256);
+
+impl Anchor {
+    pub fn new(root: u256) -> Self {
+        Self(root)
+    }
+
+    /// Returns the tree root this anchor commits to.
+    pub fn root(&self) -> &u256 {
+        &self.0
+    }
+}
+
+impl fmt::Debug for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Anchor({})", self.0)
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl From<u256> for Anchor {
+    fn from(root: u256) -> Self {
+        Self(root)
+    }
+}
+
+impl From<Anchor> for u256 {
+    fn from(anchor: Anchor) -> Self {
+        anchor.0
+    }
+}
+
+impl AsRef<[u8]> for Anchor {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl Parse for Anchor {
+    fn parse(p: &mut Parser) -> anyhow::Result<Self> {
+        Ok(Self(parse!(p, "anchor")?))
+    }
+}
+
+impl From<Anchor> for CBOR {
+    fn from(value: Anchor) -> Self {
+        CBOR::from(value.0)
+    }
+}
+
+impl From<&Anchor> for CBOR {
+    fn from(value: &Anchor) -> Self {
+        CBOR::from(value.0)
+    }
+}
+
+impl TryFrom<CBOR> for Anchor {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        Ok(Self(u256::try_from(cbor)?))
+    }
+}
+
+impl From<Anchor> for Envelope {
+    fn from(value: Anchor) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for Anchor {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> anyhow::Result<Self> {
+        envelope.extract_subject().context("Anchor")
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for Anchor {
+    fn random() -> Self {
+        Self(u256::random())
+    }
+}
+
+test_cbor_roundtrip!(Anchor);
+test_envelope_roundtrip!(Anchor);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::Anchor;
+    use crate::{RandomInstance, u256};
+
+    #[test]
+    fn test_encodes_like_u256() {
+        let root = u256::random();
+        let anchor = Anchor::from(root);
+        assert_eq!(CBOR::from(anchor), CBOR::from(root));
+        assert_eq!(anchor.to_string(), root.to_string());
+        assert_eq!(Anchor::try_from(Envelope::from(root)).unwrap(), anchor);
+    }
+}
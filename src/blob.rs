@@ -2,6 +2,10 @@ use std::{
     array::TryFromSliceError,
     fmt,
     ops::{
+        BitAnd,
+        BitOr,
+        BitXor,
+        BitXorAssign,
         Index,
         IndexMut,
         Range,
@@ -25,10 +29,14 @@ use hex::FromHexError;
 /// Errors that can occur in decoding a blob from its hex-encoded representation.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum HexParseError {
+    /// The hex string decoded to the wrong number of bytes.
     SliceInvalid {
+        /// The number of bytes required.
         expected: usize,
+        /// The number of bytes decoded.
         actual: usize,
     },
+    /// The string is not valid hex, including when it has an odd length.
     HexInvalid(FromHexError),
 }
 
@@ -99,6 +107,13 @@ impl std::error::Error for HexParseError {}
 /// # Ok(())
 /// # }
 /// ```
+///
+/// # Comparing Secret Values
+/// The derived `PartialEq` stops at the first differing byte, so the time it
+/// takes reveals how long a common prefix the operands share. Use it for public
+/// values such as identifiers and hashes. When either operand is secret, such as
+/// key material or commitment randomness, compare with [`constant_time_eq`]
+/// instead, or with `Blob::ct_eq` when the `subtle` feature is enabled.
 #[derive(Clone, PartialEq, Eq, Hash)]
 pub struct Blob<const N: usize>([u8; N]);
 
@@ -226,12 +241,235 @@ impl<const N: usize> Blob<N> {
     /// assert_eq!(blob.as_slice(), &[1, 2, 3, 4]);
     /// ```
     pub fn from_hex(hex: &str) -> Result<Self, HexParseError> {
-        let data = hex::decode(hex).map_err(|e| crate::HexParseError::HexInvalid(e))?;
-        Self::from_vec(data).map_err(|_| crate::HexParseError::SliceInvalid {
-            expected: N * 2,
-            actual: hex.len(),
+        if !hex.len().is_multiple_of(2) {
+            return Err(HexParseError::HexInvalid(FromHexError::OddLength));
+        }
+        let data = hex::decode(hex).map_err(HexParseError::HexInvalid)?;
+        let actual = data.len();
+        Self::from_vec(data).map_err(|_| HexParseError::SliceInvalid {
+            expected: N,
+            actual,
         })
     }
+
+    /// Returns a copy of this blob with its bytes in reverse order.
+    ///
+    /// Zcash serializes hashes such as txids and block hashes in the opposite
+    /// byte order from the hex form shown by explorers and RPC interfaces.
+    /// `Blob` always stores and displays bytes in their stored order, so a
+    /// serialized txid held in a `Blob<32>` must be reversed to match its
+    /// display form; [`TxId`](crate::TxId) performs this reversal itself.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Blob, TxId};
+    /// let serialized = Blob::<32>::from_hex(
+    ///     "3ba3edfd7a7b12b27ac72c3e67768f617fc81bc3888a51323a9fb8aa4b1e5e4a",
+    /// )
+    /// .unwrap();
+    /// let txid = TxId::from_bytes(serialized.clone().into());
+    /// assert_eq!(serialized.reversed().to_string(), txid.to_string());
+    /// ```
+    pub fn reversed(&self) -> Self {
+        let mut blob = self.clone();
+        blob.reverse_in_place();
+        blob
+    }
+
+    /// Reverses the order of the bytes in this blob.
+    pub fn reverse_in_place(&mut self) {
+        self.0.reverse();
+    }
+
+    /// Returns an iterator over the bytes of this blob.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let blob = Blob::new([0, 3, 0, 7]);
+    /// assert_eq!(blob.iter().filter(|b| **b != 0).count(), 2);
+    /// ```
+    pub fn iter(&self) -> std::slice::Iter<'_, u8> {
+        self.0.iter()
+    }
+
+    /// Copies the `LEN` bytes starting at `OFF` into a new, owned `Blob<LEN>`.
+    ///
+    /// The range is checked at compile time: a sub-blob that would extend past
+    /// the end of this blob fails to build rather than panicking at runtime.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let blob = Blob::<8>::new([0, 1, 2, 3, 4, 5, 6, 7]);
+    /// let sub: Blob<4> = blob.sub::<2, 4>();
+    /// assert_eq!(sub.as_slice(), &[2, 3, 4, 5]);
+    /// ```
+    ///
+    /// A range exceeding the blob does not compile:
+    /// ```compile_fail
+    /// # use zewif::Blob;
+    /// let blob = Blob::<8>::default();
+    /// let sub = blob.sub::<6, 4>();
+    /// ```
+    pub fn sub<const OFF: usize, const LEN: usize>(&self) -> Blob<LEN> {
+        const {
+            assert!(OFF <= N && LEN <= N - OFF, "sub-blob range exceeds blob length");
+        }
+        let mut data = [0u8; LEN];
+        data.copy_from_slice(&self.0[OFF..OFF + LEN]);
+        Blob(data)
+    }
+
+    /// Joins this blob and `other` into a new `Blob<C>`, with this blob's bytes
+    /// first.
+    ///
+    /// The output size must be given, either explicitly or by inference, and
+    /// is checked at compile time: a `C` other than `N + B` fails to build.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let diversifier = Blob::<11>::new([1; 11]);
+    /// let pk_d = Blob::<32>::new([2; 32]);
+    /// let raw_address: Blob<43> = diversifier.concat(pk_d);
+    /// assert_eq!(&raw_address[..11], &[1; 11]);
+    /// ```
+    ///
+    /// A mismatched output size does not compile:
+    /// ```compile_fail
+    /// # use zewif::Blob;
+    /// let joined: Blob<42> = Blob::<11>::default().concat(Blob::<32>::default());
+    /// ```
+    pub fn concat<const B: usize, const C: usize>(self, other: Blob<B>) -> Blob<C> {
+        const {
+            assert!(N + B == C, "concatenated blob length must be N + B");
+        }
+        let mut data = [0u8; C];
+        data[..N].copy_from_slice(&self.0);
+        data[N..].copy_from_slice(&other.0);
+        Blob(data)
+    }
+
+    /// Returns this blob as a `Blob<M>` if `M` equals its size `N`, or `None`
+    /// otherwise.
+    ///
+    /// This lets code that is generic over a blob size select the blobs of one
+    /// particular size without copying them.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let blob = Blob::<4>::new([1, 2, 3, 4]);
+    /// assert_eq!(blob.as_sized::<4>(), Some(&blob));
+    /// assert_eq!(blob.as_sized::<8>(), None);
+    /// ```
+    pub fn as_sized<const M: usize>(&self) -> Option<&Blob<M>> {
+        (self as &dyn std::any::Any).downcast_ref()
+    }
+
+    /// Compares two blobs in time that does not depend on their contents.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let a = Blob::new([1u8; 32]);
+    /// assert!(bool::from(a.ct_eq(&Blob::new([1u8; 32]))));
+    /// assert!(!bool::from(a.ct_eq(&Blob::new([2u8; 32]))));
+    /// ```
+    #[cfg(feature = "subtle")]
+    pub fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+    }
+
+    /// Calls `f` with this blob if `M` equals its size `N`.
+    pub(crate) fn visit_sized<const M: usize>(&self, f: &mut impl FnMut(&Blob<M>)) {
+        if let Some(blob) = self.as_sized() {
+            f(blob);
+        }
+    }
+
+    /// XORs `other` into this blob, byte by byte.
+    ///
+    /// Both blobs have the same size `N`, so a length mismatch cannot occur.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Blob;
+    /// let mut blob = Blob::new([0b1100u8, 0xff]);
+    /// blob.xor_assign(&Blob::new([0b1010, 0x0f]));
+    /// assert_eq!(blob, Blob::new([0b0110, 0xf0]));
+    /// ```
+    ///
+    /// Blobs of different sizes cannot be combined:
+    /// ```compile_fail
+    /// # use zewif::Blob;
+    /// let mut blob = Blob::<4>::default();
+    /// blob.xor_assign(&Blob::<5>::default());
+    /// ```
+    pub fn xor_assign(&mut self, other: &Self) {
+        for (a, b) in self.0.iter_mut().zip(other.0) {
+            *a ^= b;
+        }
+    }
+
+    fn zip_with(&self, other: &Self, f: impl Fn(u8, u8) -> u8) -> Self {
+        Self(std::array::from_fn(|i| f(self.0[i], other.0[i])))
+    }
+}
+
+/// Implements a bytewise binary operator for owned and borrowed blobs of the
+/// same size.
+macro_rules! impl_blob_bitwise_op {
+    ($trait:ident, $method:ident, $op:tt) => {
+        impl<const N: usize> $trait for &Blob<N> {
+            type Output = Blob<N>;
+
+            fn $method(self, rhs: Self) -> Blob<N> {
+                self.zip_with(rhs, |a, b| a $op b)
+            }
+        }
+
+        impl<const N: usize> $trait for Blob<N> {
+            type Output = Blob<N>;
+
+            fn $method(self, rhs: Self) -> Blob<N> {
+                (&self).$method(&rhs)
+            }
+        }
+    };
+}
+
+impl_blob_bitwise_op!(BitXor, bitxor, ^);
+impl_blob_bitwise_op!(BitAnd, bitand, &);
+impl_blob_bitwise_op!(BitOr, bitor, |);
+
+impl<const N: usize> BitXorAssign<&Blob<N>> for Blob<N> {
+    fn bitxor_assign(&mut self, rhs: &Blob<N>) {
+        self.xor_assign(rhs);
+    }
+}
+
+/// Returns `true` if `a` and `b` are equal, taking time that depends only on
+/// their lengths and not on their contents.
+///
+/// Use this rather than `==` when comparing secret bytes, such as the `rcm` or
+/// recipient public key of a sent output during selective-disclosure proof
+/// construction.
+///
+/// # Examples
+/// ```
+/// # use zewif::constant_time_eq;
+/// assert!(constant_time_eq(&[1, 2, 3], &[1, 2, 3]));
+/// assert!(!constant_time_eq(&[1, 2, 3], &[1, 2, 4]));
+/// assert!(!constant_time_eq(&[1, 2, 3], &[1, 2]));
+/// ```
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    let difference = a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y));
+    std::hint::black_box(difference) == 0
 }
 
 impl<const N: usize> Default for Blob<N> {
@@ -302,6 +540,24 @@ impl<const N: usize> Index<RangeToInclusive<usize>> for Blob<N> {
     }
 }
 
+impl<const N: usize> IntoIterator for Blob<N> {
+    type Item = u8;
+    type IntoIter = std::array::IntoIter<u8, N>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a, const N: usize> IntoIterator for &'a Blob<N> {
+    type Item = &'a u8;
+    type IntoIter = std::slice::Iter<'a, u8>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
 impl<const N: usize> From<Blob<N>> for [u8; N] {
     fn from(blob: Blob<N>) -> Self {
         blob.0
@@ -338,21 +594,25 @@ impl<const N: usize> From<&Blob<N>> for Vec<u8> {
     }
 }
 
-impl<const N: usize> From<Vec<u8>> for Blob<N> {
-    fn from(data: Vec<u8>) -> Self {
-        Self::from_vec(data).unwrap()
+impl<const N: usize> TryFrom<Vec<u8>> for Blob<N> {
+    type Error = TryFromSliceError;
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        Self::from_vec(data)
     }
 }
 
-impl<const N: usize> From<&[u8]> for Blob<N> {
-    fn from(data: &[u8]) -> Self {
-        Self::from_vec(data.to_vec()).unwrap()
+impl<const N: usize> TryFrom<&[u8]> for Blob<N> {
+    type Error = TryFromSliceError;
+
+    fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+        Self::from_slice(data)
     }
 }
 
 impl<const N: usize> From<&[u8; N]> for Blob<N> {
     fn from(data: &[u8; N]) -> Self {
-        Self::from_vec(data.to_vec()).unwrap()
+        Self(*data)
     }
 }
 
@@ -434,6 +694,50 @@ impl<const N: usize> TryFrom<Envelope> for Blob<N> {
     }
 }
 
+/// Serializes as a lowercase hex string for human-readable formats such as
+/// JSON, and as a raw byte string for binary formats.
+#[cfg(feature = "serde")]
+impl<const N: usize> serde::Serialize for Blob<N> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.serialize_str(&hex::encode(self.0))
+        } else {
+            serializer.serialize_bytes(&self.0)
+        }
+    }
+}
+
+/// Deserializes from a hex string or a byte string, rejecting any value that
+/// is not exactly `N` bytes long.
+#[cfg(feature = "serde")]
+impl<'de, const N: usize> serde::Deserialize<'de> for Blob<N> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BlobVisitor<const N: usize>;
+
+        impl<const N: usize> serde::de::Visitor<'_> for BlobVisitor<N> {
+            type Value = Blob<N>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "{} bytes as a hex string or byte string", N)
+            }
+
+            fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<Self::Value, E> {
+                Blob::from_hex(v).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+                Blob::from_slice(v).map_err(|_| E::invalid_length(v.len(), &self))
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(BlobVisitor)
+        } else {
+            deserializer.deserialize_bytes(BlobVisitor)
+        }
+    }
+}
+
 #[cfg(test)]
 impl<const N: usize> crate::RandomInstance for Blob<N> {
     fn random() -> Self {
@@ -444,3 +748,212 @@ impl<const N: usize> crate::RandomInstance for Blob<N> {
 
 test_cbor_roundtrip!(Blob32);
 test_envelope_roundtrip!(Blob32);
+
+#[cfg(test)]
+mod tests {
+    use hex::FromHexError;
+
+    use super::{Blob, HexParseError, constant_time_eq};
+    use crate::{RandomInstance, Seed, TxId};
+
+    #[test]
+    fn test_concat() {
+        let diversifier = Blob::<11>::new(std::array::from_fn(|i| i as u8));
+        let pk_d = Blob::<32>::new(std::array::from_fn(|i| 11 + i as u8));
+        let raw_address: Blob<43> = diversifier.clone().concat(pk_d.clone());
+        assert_eq!(raw_address, Blob::new(std::array::from_fn(|i| i as u8)));
+        assert_eq!(raw_address.sub::<0, 11>(), diversifier);
+        assert_eq!(raw_address.sub::<11, 32>(), pk_d);
+
+        let empty: Blob<4> = Blob::<0>::default().concat(Blob::new([1, 2, 3, 4]));
+        assert_eq!(empty, Blob::new([1, 2, 3, 4]));
+    }
+
+    #[test]
+    fn test_sub_blob() {
+        let blob = Blob::<8>::new([10, 11, 12, 13, 14, 15, 16, 17]);
+        assert_eq!(blob.sub::<3, 4>(), Blob::new([13, 14, 15, 16]));
+        assert_eq!(blob.sub::<0, 8>(), blob);
+        assert_eq!(blob.sub::<4, 4>().as_slice(), &blob[4..]);
+        assert!(blob.sub::<8, 0>().is_empty());
+    }
+
+    #[test]
+    fn test_reversed() {
+        let blob = Blob::new([1u8, 2, 3, 4]);
+        assert_eq!(blob.reversed(), Blob::new([4, 3, 2, 1]));
+        assert_eq!(blob.reversed().reversed(), blob);
+        let mut reversed = blob.clone();
+        reversed.reverse_in_place();
+        assert_eq!(reversed, blob.reversed());
+
+        let random = Blob::<32>::random();
+        assert_eq!(random.reversed().reversed(), random);
+
+        // The genesis block's coinbase txid, in serialized and display order.
+        let display = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+        let txid = TxId::from_hex(display).unwrap();
+        let serialized = Blob::<32>::new(txid.into());
+        assert_eq!(serialized.reversed(), Blob::from_hex(display).unwrap());
+        assert_eq!(serialized.reversed().to_string(), txid.to_string());
+    }
+
+    #[test]
+    fn test_iteration() {
+        let blob = Blob::new([1u8, 2, 3]);
+        let sum: u32 = blob.iter().map(|b| u32::from(*b)).sum();
+        assert_eq!(sum, 6);
+        let mut borrowed = Vec::new();
+        for b in &blob {
+            borrowed.push(*b);
+        }
+        assert_eq!(borrowed, blob.to_vec());
+        let owned: Vec<u8> = blob.clone().into_iter().rev().collect();
+        assert_eq!(owned, [3, 2, 1]);
+    }
+
+    #[test]
+    fn test_bitwise_ops() {
+        let a = Blob::new([0b1100u8, 0xf0, 0x00]);
+        let b = Blob::new([0b1010u8, 0xff, 0x01]);
+        assert_eq!(&a ^ &b, Blob::new([0b0110, 0x0f, 0x01]));
+        assert_eq!(&a & &b, Blob::new([0b1000, 0xf0, 0x00]));
+        assert_eq!(&a | &b, Blob::new([0b1110, 0xff, 0x01]));
+        assert_eq!(a.clone() ^ b.clone(), &a ^ &b);
+
+        let mut c = a.clone();
+        c ^= &b;
+        assert_eq!(c, &a ^ &b);
+        c.xor_assign(&b);
+        assert_eq!(c, a);
+    }
+
+    #[test]
+    fn test_xor_properties() {
+        for _ in 0..20 {
+            let (a, b, c) = (Blob::<32>::random(), Blob::random(), Blob::random());
+            assert_eq!(&a ^ &a, Blob::default());
+            assert_eq!(&(&a ^ &b) ^ &c, &a ^ &(&b ^ &c));
+            assert_eq!(&(&a & &b) & &c, &a & &(&b & &c));
+            assert_eq!(&(&a | &b) | &c, &a | &(&b | &c));
+            assert_eq!(&a ^ &b, &b ^ &a);
+        }
+    }
+
+    #[test]
+    fn test_from_hex_errors() {
+        assert_eq!(
+            Blob::<4>::from_hex("0102030"),
+            Err(HexParseError::HexInvalid(FromHexError::OddLength))
+        );
+        let too_short = Blob::<32>::from_hex(&"ab".repeat(15)).unwrap_err();
+        assert_eq!(
+            too_short,
+            HexParseError::SliceInvalid {
+                expected: 32,
+                actual: 15
+            }
+        );
+        assert_eq!(too_short.to_string(), "Expected 32 bytes, got 15");
+        assert_eq!(
+            Blob::<4>::from_hex("0102030405"),
+            Err(HexParseError::SliceInvalid {
+                expected: 4,
+                actual: 5
+            })
+        );
+        assert!(matches!(
+            Blob::<1>::from_hex("zz"),
+            Err(HexParseError::HexInvalid(
+                FromHexError::InvalidHexCharacter { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_try_from_wrong_length() {
+        assert!(Blob::<4>::try_from(vec![1u8, 2, 3]).is_err());
+        assert!(Blob::<4>::try_from(&[1u8, 2, 3, 4, 5][..]).is_err());
+        assert_eq!(
+            Blob::<4>::try_from(vec![1u8, 2, 3, 4]).unwrap(),
+            Blob::new([1, 2, 3, 4])
+        );
+        assert_eq!(
+            Blob::<4>::try_from(&[1u8, 2, 3, 4][..]).unwrap(),
+            Blob::from(&[1, 2, 3, 4])
+        );
+        assert!(Seed::try_from(vec![0u8; 31]).is_err());
+        assert!(Seed::try_from(&[0u8; 33][..]).is_err());
+        assert!(Seed::try_from(&[0u8; 32][..]).is_ok());
+    }
+
+    /// Pairs of random blobs, pairs of equal blobs, and pairs differing only in
+    /// their last byte.
+    fn comparison_cases() -> Vec<(Blob<32>, Blob<32>)> {
+        (0..100)
+            .flat_map(|_| {
+                let a = Blob::<32>::random();
+                let mut last_differs = a.clone();
+                last_differs[31] ^= 1;
+                [(a.clone(), Blob::random()), (a.clone(), a.clone()), (a, last_differs)]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_constant_time_eq_matches_derived_eq() {
+        for (a, b) in comparison_cases() {
+            assert_eq!(constant_time_eq(a.as_slice(), b.as_slice()), a == b);
+        }
+        assert!(!constant_time_eq(&[0u8; 4], &[0u8; 3]));
+        assert!(constant_time_eq(&[], &[]));
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn test_ct_eq_matches_derived_eq() {
+        for (a, b) in comparison_cases() {
+            assert_eq!(bool::from(a.ct_eq(&b)), a == b);
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_roundtrip() {
+        use serde_test::{Configure, Token, assert_tokens};
+
+        let blob = Blob::<4>::new([0xde, 0xad, 0xbe, 0xef]);
+
+        // Human-readable formats get lowercase hex.
+        assert_tokens(&blob.clone().readable(), &[Token::Str("deadbeef")]);
+
+        // Binary formats get the raw bytes.
+        assert_tokens(&blob.compact(), &[Token::Bytes(&[0xde, 0xad, 0xbe, 0xef])]);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_deserialize() {
+        use serde::{
+            Deserialize,
+            de::value::{BytesDeserializer, Error, StrDeserializer},
+        };
+
+        let blob = Blob::<4>::new([0xde, 0xad, 0xbe, 0xef]);
+        let from_hex = |s: &str| Blob::<4>::deserialize(StrDeserializer::<Error>::new(s));
+        let from_bytes = |b: &[u8]| Blob::<4>::deserialize(BytesDeserializer::<Error>::new(b));
+
+        assert_eq!(from_hex("deadbeef").unwrap(), blob);
+        assert_eq!(from_bytes(&[0xde, 0xad, 0xbe, 0xef]).unwrap(), blob);
+        assert!(from_hex("deadbe").is_err());
+        assert!(from_hex("deadbeef00").is_err());
+        assert!(from_hex("not hex!").is_err());
+        assert!(from_bytes(&[0xde, 0xad, 0xbe]).is_err());
+        assert!(
+            from_bytes(&[0u8; 5])
+                .unwrap_err()
+                .to_string()
+                .contains("invalid length 5")
+        );
+    }
+}
@@ -54,6 +54,11 @@ macro_rules! blob {
                 self.0.to_vec()
             }
 
+            /// Returns a reference to the underlying `Blob`.
+            pub fn as_blob(&self) -> &$crate::Blob<$size> {
+                &self.0
+            }
+
             /// Creates an instance from a slice of bytes.
             ///
             /// # Errors
@@ -136,15 +141,19 @@ macro_rules! blob {
             }
         }
 
-        impl From<Vec<u8>> for $name {
-            fn from(data: Vec<u8>) -> Self {
-                Self::from_vec(data).unwrap()
+        impl TryFrom<Vec<u8>> for $name {
+            type Error = std::array::TryFromSliceError;
+
+            fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+                Ok(Self($crate::Blob::from_vec(data)?))
             }
         }
 
-        impl From<&[u8]> for $name {
-            fn from(data: &[u8]) -> Self {
-                Self::from_slice(data).unwrap()
+        impl TryFrom<&[u8]> for $name {
+            type Error = std::array::TryFromSliceError;
+
+            fn try_from(data: &[u8]) -> Result<Self, Self::Error> {
+                Ok(Self($crate::Blob::from_slice(data)?))
             }
         }
 
@@ -165,7 +174,7 @@ macro_rules! blob {
                 let bytes = ::anyhow::Context::with_context(parser.next($size), || {
                     format!("Parsing {}", stringify!($name))
                 })?;
-                Ok(Self($crate::Blob::from(bytes)))
+                Ok(Self($crate::Blob::from_slice(bytes)?))
             }
         }
     };
@@ -116,15 +116,15 @@ impl BlockHash {
     /// assert_eq!(format!("{}", block_hash), hex);
     /// ```
     pub fn from_hex(hex: &str) -> Result<Self, HexParseError> {
-        let mut data = hex::decode(hex).map_err(|e| crate::HexParseError::HexInvalid(e))?;
+        let mut data = hex::decode(hex).map_err(crate::HexParseError::HexInvalid)?;
         data.reverse();
 
         Ok(
             Self(
                 <[u8; 32]>::try_from(&data[..]).map_err(|_| {
                     crate::HexParseError::SliceInvalid {
-                        expected: 64,
-                        actual: hex.len(),
+                        expected: 32,
+                        actual: data.len(),
                     }
                 })?
             )
@@ -1,7 +1,7 @@
 use bc_envelope::prelude::*;
 use std::cmp::{Ord, Ordering};
 use std::fmt;
-use std::ops::{Add, Sub};
+use std::ops::{Add, RangeInclusive, Sub};
 
 use crate::{parse, parser::prelude::*};
 use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
@@ -90,6 +90,67 @@ impl BlockHeight {
     pub fn saturating_sub(self, v: u32) -> BlockHeight {
         BlockHeight(self.0.saturating_sub(v))
     }
+
+    /// Subtracts the provided value from this height, returning `None` if the result
+    /// would be below the genesis block.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::BlockHeight;
+    /// let height = BlockHeight::from(100u32);
+    /// assert_eq!(height.checked_sub(100), Some(BlockHeight::from(0u32)));
+    /// assert_eq!(height.checked_sub(101), None);
+    /// ```
+    pub fn checked_sub(self, v: u32) -> Option<BlockHeight> {
+        self.0.checked_sub(v).map(BlockHeight)
+    }
+
+    /// Returns the number of confirmations of a transaction mined at this height, when
+    /// the chain tip is at `tip`.
+    ///
+    /// A transaction mined in the tip block has one confirmation, and one mined above
+    /// the tip, as after a reorg, has none. The count saturates at `u32::MAX`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::BlockHeight;
+    /// let mined = BlockHeight::from(1_000u32);
+    /// assert_eq!(mined.confirmations(BlockHeight::from(1_000u32)), 1);
+    /// assert_eq!(mined.confirmations(BlockHeight::from(1_009u32)), 10);
+    /// assert_eq!(mined.confirmations(BlockHeight::from(999u32)), 0);
+    ///
+    /// // Transactions in the last 10 blocks.
+    /// let tip = BlockHeight::from(1_005u32);
+    /// assert!((1..=10).contains(&mined.confirmations(tip)));
+    /// ```
+    pub fn confirmations(self, tip: BlockHeight) -> u32 {
+        match tip.0.checked_sub(self.0) {
+            Some(depth) => depth.saturating_add(1),
+            None => 0,
+        }
+    }
+
+    /// Returns an iterator over the heights in `range`, in ascending order.
+    ///
+    /// `RangeInclusive<BlockHeight>` cannot be iterated directly, because `BlockHeight`
+    /// does not implement the unstable `Step` trait, but can still be used to test
+    /// whether a height lies in a window.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::BlockHeight;
+    /// let window = BlockHeight::from(10u32)..=BlockHeight::from(12u32);
+    /// assert!(window.contains(&BlockHeight::from(11u32)));
+    ///
+    /// let heights: Vec<u32> = BlockHeight::range(window).map(u32::from).collect();
+    /// assert_eq!(heights, vec![10, 11, 12]);
+    /// ```
+    pub fn range(
+        range: RangeInclusive<BlockHeight>,
+    ) -> impl DoubleEndedIterator<Item = BlockHeight> {
+        let (start, end) = range.into_inner();
+        (start.0..=end.0).map(BlockHeight)
+    }
 }
 
 /// Displays the block height as a plain number
@@ -248,3 +309,43 @@ impl crate::RandomInstance for BlockHeight {
 
 test_cbor_roundtrip!(BlockHeight);
 test_envelope_roundtrip!(BlockHeight);
+
+#[cfg(test)]
+mod tests {
+    use super::{BlockHeight, H0};
+
+    #[test]
+    fn test_underflow_at_genesis() {
+        assert_eq!(H0.checked_sub(0), Some(H0));
+        assert_eq!(H0.checked_sub(1), None);
+        assert_eq!(H0.saturating_sub(1), H0);
+        assert_eq!(H0 - 1, H0);
+        assert_eq!(H0 - BlockHeight::from(1u32), 0);
+    }
+
+    #[test]
+    fn test_confirmations() {
+        let mined = BlockHeight::from(100u32);
+        assert_eq!(mined.confirmations(BlockHeight::from(99u32)), 0);
+        assert_eq!(mined.confirmations(mined), 1);
+        assert_eq!(mined.confirmations(BlockHeight::from(199u32)), 100);
+        assert_eq!(H0.confirmations(H0), 1);
+        assert_eq!(H0.confirmations(BlockHeight::from(u32::MAX)), u32::MAX);
+    }
+
+    #[test]
+    fn test_range() {
+        let heights: Vec<BlockHeight> = BlockHeight::range(H0..=H0).collect();
+        assert_eq!(heights, vec![H0]);
+
+        let tip = BlockHeight::from(u32::MAX);
+        let last_three: Vec<u32> = BlockHeight::range(tip.saturating_sub(2)..=tip)
+            .rev()
+            .map(u32::from)
+            .collect();
+        assert_eq!(last_three, vec![u32::MAX, u32::MAX - 1, u32::MAX - 2]);
+
+        let empty = BlockHeight::from(5u32)..=BlockHeight::from(4u32);
+        assert_eq!(BlockHeight::range(empty).count(), 0);
+    }
+}
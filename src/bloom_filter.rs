@@ -0,0 +1,156 @@
+use bc_envelope::prelude::*;
+
+use crate::{Data, sha256, test_envelope_roundtrip};
+
+/// A compact probabilistic set membership filter.
+///
+/// A `BloomFilter` answers "is this item possibly in the set?" with no false
+/// negatives and a tunable false-positive rate. Light clients use filters of
+/// owned transparent address hashes to ask a server for potentially relevant
+/// transactions without disclosing the exact set of addresses.
+///
+/// Item positions are derived by double hashing the SHA-256 digest of each
+/// item, so a serialized filter can be queried by any implementation following
+/// the same scheme: for `i` in `0..hash_count`, bit
+/// `(h1 + i * h2) mod bit_count` is set, where `h1` and `h2` are the first and
+/// second little-endian `u64` words of `SHA-256(item)`.
+///
+/// # Examples
+/// ```
+/// # use zewif::BloomFilter;
+/// let mut filter = BloomFilter::new(100, 0.01);
+/// filter.insert(b"hello");
+/// assert!(filter.contains(b"hello"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: Data,
+    bit_count: u64,
+    hash_count: u32,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter sized for `expected_items` items at the given
+    /// target false-positive rate.
+    ///
+    /// The rate is clamped to the open interval `(0, 1)`.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(f64::MIN_POSITIVE, 1.0 - f64::EPSILON);
+        let ln2 = std::f64::consts::LN_2;
+        let bit_count = ((-n * p.ln()) / (ln2 * ln2)).ceil().max(8.0) as u64;
+        let hash_count = ((bit_count as f64 / n) * ln2).round().max(1.0) as u32;
+        Self::with_parameters(bit_count, hash_count)
+    }
+
+    /// Creates an empty filter with an explicit size and number of hash functions.
+    pub fn with_parameters(bit_count: u64, hash_count: u32) -> Self {
+        let bit_count = bit_count.max(1);
+        Self {
+            bits: Data::from_vec(vec![0u8; bit_count.div_ceil(8) as usize]),
+            bit_count,
+            hash_count: hash_count.max(1),
+        }
+    }
+
+    /// The number of bits in the filter.
+    pub fn bit_count(&self) -> u64 {
+        self.bit_count
+    }
+
+    /// The number of bit positions set for each inserted item.
+    pub fn hash_count(&self) -> u32 {
+        self.hash_count
+    }
+
+    /// The raw filter bits, least significant bit first within each byte.
+    pub fn bits(&self) -> &[u8] {
+        self.bits.as_ref()
+    }
+
+    /// Adds an item to the filter.
+    pub fn insert(&mut self, item: impl AsRef<[u8]>) {
+        for position in self.positions(item.as_ref()) {
+            self.bits[(position / 8) as usize] |= 1 << (position % 8);
+        }
+    }
+
+    /// Returns `true` if the item may be in the set, or `false` if it
+    /// definitely is not.
+    pub fn contains(&self, item: impl AsRef<[u8]>) -> bool {
+        self.positions(item.as_ref())
+            .all(|position| self.bits[(position / 8) as usize] & (1 << (position % 8)) != 0)
+    }
+
+    fn positions(&self, item: &[u8]) -> impl Iterator<Item = u64> + use<> {
+        let digest = sha256(item);
+        let digest: &[u8] = digest.as_ref();
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().unwrap());
+        let bit_count = self.bit_count;
+        (0..self.hash_count as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bit_count)
+    }
+}
+
+impl From<BloomFilter> for CBOR {
+    fn from(value: BloomFilter) -> Self {
+        let mut map = Map::new();
+        map.insert("bits", value.bits);
+        map.insert("bit_count", value.bit_count);
+        map.insert("hash_count", value.hash_count);
+        map.into()
+    }
+}
+
+impl TryFrom<CBOR> for BloomFilter {
+    type Error = dcbor::Error;
+
+    fn try_from(value: CBOR) -> dcbor::Result<Self> {
+        if let CBORCase::Map(map) = value.into_case() {
+            let bits: Data = map.extract("bits")?;
+            let bit_count: u64 = map.extract("bit_count")?;
+            let hash_count: u32 = map.extract("hash_count")?;
+            if bit_count == 0 || bits.len() as u64 != bit_count.div_ceil(8) {
+                return Err("BloomFilter bit count does not match its bits".into());
+            }
+            if hash_count == 0 {
+                return Err("BloomFilter must use at least one hash function".into());
+            }
+            Ok(BloomFilter {
+                bits,
+                bit_count,
+                hash_count,
+            })
+        } else {
+            Err("Expected a CBOR map".into())
+        }
+    }
+}
+
+impl From<BloomFilter> for Envelope {
+    fn from(value: BloomFilter) -> Self {
+        Envelope::new(CBOR::from(value)).add_type("BloomFilter")
+    }
+}
+
+impl TryFrom<Envelope> for BloomFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        envelope.check_type_envelope("BloomFilter")?;
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for BloomFilter {
+    fn random() -> Self {
+        let mut filter = BloomFilter::new(16, 0.05);
+        for item in Vec::<String>::random() {
+            filter.insert(item);
+        }
+        filter
+    }
+}
+
+test_envelope_roundtrip!(BloomFilter);
@@ -0,0 +1,186 @@
+//! Byte-order conformance tests for the fixed-width types.
+//!
+//! Each type is given a known ascending pattern (`0x00, 0x01, ...`) and every
+//! conversion path is checked to leave it unchanged. Binary types store their
+//! bytes in wire order and are only reversed for display, so their CBOR byte
+//! strings must equal the pattern exactly. Integer types are encoded as dCBOR
+//! integers, which are always big-endian, while their binary parsing reads the
+//! little-endian wire form.
+
+use std::fmt::Debug;
+
+use bc_envelope::prelude::*;
+
+use crate::{
+    Amount, Blob, BlockHash, BlockHeight, ExpiryHeight, NonHardenedChildIndex, Position,
+    SecondsSinceEpoch, SeedFingerprint, TxId, parser::prelude::*, u160, u252, u256,
+};
+
+/// Returns `[0x00, 0x01, ..., N - 1]`.
+fn pattern<const N: usize>() -> [u8; N] {
+    std::array::from_fn(|i| i as u8)
+}
+
+/// The value `0x00010203`, whose big- and little-endian forms differ.
+const PATTERN_U32: u32 = 0x0001_0203;
+
+/// The value `0x0001020304050607`, which is also a valid `Amount`.
+const PATTERN_U64: u64 = 0x0001_0203_0405_0607;
+
+/// Asserts that `value` encodes to a CBOR byte string equal to `bytes`, and
+/// that it decodes unchanged from both CBOR and an envelope.
+fn assert_byte_string<T>(value: T, bytes: &[u8])
+where
+    T: Clone + PartialEq + Debug + Into<CBOR> + TryFrom<CBOR> + Into<Envelope> + TryFrom<Envelope>,
+    <T as TryFrom<CBOR>>::Error: Debug,
+    <T as TryFrom<Envelope>>::Error: Debug,
+{
+    let cbor: CBOR = value.clone().into();
+    assert_eq!(cbor.clone().try_into_byte_string().unwrap(), bytes);
+    assert_eq!(T::try_from(cbor).unwrap(), value);
+    let envelope: Envelope = value.clone().into();
+    assert_eq!(T::try_from(envelope).unwrap(), value);
+}
+
+/// Asserts that `value` encodes to the dCBOR integer `expected`, and that it
+/// decodes unchanged from both CBOR and an envelope.
+fn assert_integer<T>(value: T, expected: u64)
+where
+    T: Clone + PartialEq + Debug + Into<CBOR> + TryFrom<CBOR> + Into<Envelope> + TryFrom<Envelope>,
+    <T as TryFrom<CBOR>>::Error: Debug,
+    <T as TryFrom<Envelope>>::Error: Debug,
+{
+    let cbor: CBOR = value.clone().into();
+    assert_eq!(cbor.to_cbor_data(), CBOR::from(expected).to_cbor_data());
+    assert_eq!(T::try_from(cbor).unwrap(), value);
+    let envelope: Envelope = value.clone().into();
+    assert_eq!(T::try_from(envelope).unwrap(), value);
+}
+
+/// Asserts that parsing `bytes` yields `expected` and consumes all of them.
+fn assert_parses<T: Parse + PartialEq + Debug>(bytes: &[u8], expected: T) {
+    let mut parser = Parser::new(&bytes);
+    assert_eq!(T::parse(&mut parser).unwrap(), expected);
+    parser.check_finished().unwrap();
+}
+
+#[test]
+fn test_u256_byte_order() {
+    let bytes = pattern::<32>();
+    let value = u256::try_from(&bytes).unwrap();
+    assert_eq!(AsRef::<[u8; 32]>::as_ref(&value), &bytes);
+    assert_byte_string(value, &bytes);
+    assert_parses(&bytes, value);
+}
+
+#[test]
+fn test_u160_byte_order() {
+    let bytes = pattern::<20>();
+    let value = u160::try_from(&bytes).unwrap();
+    assert_eq!(AsRef::<[u8; 20]>::as_ref(&value), &bytes);
+    assert_byte_string(value, &bytes);
+    assert_parses(&bytes, value);
+}
+
+#[test]
+fn test_u252_byte_order() {
+    let bytes = pattern::<32>();
+    let value = u252::try_from(&bytes).unwrap();
+    assert_eq!(AsRef::<[u8; 32]>::as_ref(&value), &bytes);
+    assert_byte_string(value, &bytes);
+    assert_parses(&bytes, value);
+}
+
+#[test]
+fn test_txid_byte_order() {
+    let bytes = pattern::<32>();
+    let value = TxId::from_bytes(bytes);
+    assert_eq!(value.as_ref(), &bytes);
+    assert_byte_string(value, &bytes);
+    assert_parses(&bytes, value);
+    // Only the display form is reversed.
+    let mut reversed = bytes;
+    reversed.reverse();
+    assert_eq!(value.to_string(), hex::encode(reversed));
+    assert_eq!(TxId::from_hex(&value.to_string()).unwrap(), value);
+}
+
+#[test]
+fn test_block_hash_byte_order() {
+    let bytes = pattern::<32>();
+    let value = BlockHash::from_bytes(bytes);
+    assert_eq!(value.as_ref(), &bytes);
+    assert_byte_string(value, &bytes);
+    assert_parses(&bytes, value);
+    let mut reversed = bytes;
+    reversed.reverse();
+    assert_eq!(value.to_string(), hex::encode(reversed));
+    assert_eq!(BlockHash::from_hex(&value.to_string()).unwrap(), value);
+}
+
+#[test]
+fn test_seed_fingerprint_byte_order() {
+    let bytes = pattern::<32>();
+    let value = SeedFingerprint::from_bytes(bytes);
+    assert_eq!(value.as_ref(), &bytes);
+    assert_byte_string(value, &bytes);
+}
+
+#[test]
+fn test_blob_byte_order() {
+    let bytes = pattern::<11>();
+    let value = Blob::new(bytes);
+    assert_eq!(value.as_slice(), &bytes);
+    assert_byte_string(value.clone(), &bytes);
+    assert_parses(&bytes, value);
+}
+
+#[test]
+fn test_position_byte_order() {
+    let value = Position::from(PATTERN_U64);
+    assert_eq!(u64::from(value), PATTERN_U64);
+    assert_integer(value, PATTERN_U64);
+}
+
+#[test]
+fn test_non_hardened_child_index_byte_order() {
+    let value = NonHardenedChildIndex::from(PATTERN_U32);
+    assert_eq!(u32::from(value), PATTERN_U32);
+    assert_integer(value, PATTERN_U32.into());
+}
+
+#[test]
+fn test_block_height_byte_order() {
+    let value = BlockHeight::from(PATTERN_U32);
+    assert_eq!(u32::from(value), PATTERN_U32);
+    assert_integer(value, PATTERN_U32.into());
+    assert_parses(&PATTERN_U32.to_le_bytes(), value);
+}
+
+#[test]
+fn test_expiry_height_byte_order() {
+    let value = ExpiryHeight::from(PATTERN_U32);
+    assert_eq!(u32::from(value), PATTERN_U32);
+    let cbor = CBOR::from(value);
+    assert_eq!(cbor.to_cbor_data(), CBOR::from(PATTERN_U32).to_cbor_data());
+    assert_eq!(ExpiryHeight::try_from(cbor).unwrap(), value);
+    assert_parses(&PATTERN_U32.to_le_bytes(), value);
+}
+
+#[test]
+fn test_seconds_since_epoch_byte_order() {
+    let value = SecondsSinceEpoch::from(PATTERN_U64);
+    assert_eq!(u64::from(value), PATTERN_U64);
+    assert_integer(value, PATTERN_U64);
+}
+
+#[test]
+fn test_amount_byte_order() {
+    let value = Amount::from_u64(PATTERN_U64).unwrap();
+    assert_eq!(u64::try_from(value).unwrap(), PATTERN_U64);
+    assert_integer(value, PATTERN_U64);
+    let le_bytes = PATTERN_U64.to_le_bytes();
+    assert_eq!(value.to_i64_le_bytes(), le_bytes);
+    assert_eq!(Amount::from_i64_le_bytes(le_bytes).unwrap(), value);
+    assert_parses(&le_bytes, value);
+}
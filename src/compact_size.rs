@@ -2,6 +2,13 @@ use anyhow::{Result, bail};
 
 use crate::{parse, parser::prelude::*};
 
+/// The largest value [`parse_compact_size`] accepts.
+///
+/// zcashd's `ReadCompactSize` rejects sizes above this bound (its `MAX_SIZE`,
+/// 32 MiB), so no valid serialized length or count exceeds it. Enforcing it
+/// keeps a hostile length prefix from driving a huge allocation.
+pub const MAX_COMPACT_SIZE: usize = 0x0200_0000;
+
 /// Parses a Bitcoin-style variable-length integer (compact size) from a binary data stream.
 ///
 /// This function implements the Bitcoin/Zcash compact size encoding, where:
@@ -11,7 +18,9 @@ use crate::{parse, parser::prelude::*};
 /// - Values 4294967296+ are encoded as 0xff followed by 8 bytes (little-endian)
 ///
 /// This encoding scheme optimizes space usage for small integers while allowing for very
-/// large values when needed.
+/// large values when needed. As in zcashd, only the shortest encoding of a value is
+/// accepted, and values above [`MAX_COMPACT_SIZE`] are rejected; use
+/// [`parse_compact_size_bounded`] for a different bound.
 ///
 /// # Arguments
 /// * `p` - A mutable reference to a Parser to read bytes from
@@ -22,7 +31,8 @@ use crate::{parse, parser::prelude::*};
 /// # Errors
 /// Returns an error if:
 /// - There are insufficient bytes in the parser input
-/// - The encoding doesn't follow the compact size rules (e.g., using 0xfd for a value < 253)
+/// - The encoding is non-canonical (e.g., using 0xfd for a value < 253)
+/// - The value exceeds [`MAX_COMPACT_SIZE`]
 ///
 /// # Examples
 /// ```no_run
@@ -44,35 +54,48 @@ use crate::{parse, parser::prelude::*};
 /// # fn main() {}
 /// ```
 pub fn parse_compact_size(p: &mut Parser) -> Result<usize> {
-    match parse!(p, u8, "compact size")? {
-        0xfd => {
-            let n = parse!(p, u16, "compact size")?;
-            if n < 253 {
-                bail!("Compact size with 0xfd prefix must be >= 253, got {}", n);
-            }
-            Ok(n as usize)
-        }
-        0xfe => {
-            let n = parse!(p, u32, "compact size")?;
-            if n < 0x10000 {
-                bail!(
-                    "Compact size with 0xfe prefix must be >= 0x10000, got {}",
-                    n
-                );
-            }
-            Ok(n as usize)
-        }
-        0xff => {
-            let n = parse!(p, u64, "compact size")?;
-            if n < 0x100000000 {
-                bail!(
-                    "Compact size with 0xff prefix must be >= 0x100000000, got {}",
-                    n
-                );
-            }
-            Ok(n as usize)
-        }
-        size => Ok(size as usize),
+    parse_compact_size_bounded(p, MAX_COMPACT_SIZE)
+}
+
+/// Parses a canonically encoded compact size, rejecting values above `max`.
+///
+/// # Errors
+/// Returns an error if there are insufficient bytes, if the encoding is not
+/// the shortest one for its value, or if the value exceeds `max`. The messages
+/// for the last two cases begin with "Non-canonical compact size" and
+/// "Compact size ... exceeds maximum" respectively.
+///
+/// # Examples
+/// ```
+/// # use zewif::{parse_compact_size_bounded, parser::prelude::*};
+/// let data = [0xfd, 0x00, 0x01];
+/// assert_eq!(parse_compact_size_bounded(&mut Parser::new(&data), 256).unwrap(), 256);
+///
+/// let error = parse_compact_size_bounded(&mut Parser::new(&data), 255).unwrap_err();
+/// assert!(error.to_string().contains("exceeds maximum"));
+///
+/// // 5 takes one byte, so its three-byte encoding is rejected.
+/// let error = parse_compact_size_bounded(&mut Parser::new(&[0xfd, 0x05, 0x00]), 255).unwrap_err();
+/// assert!(error.to_string().starts_with("Non-canonical"));
+/// ```
+pub fn parse_compact_size_bounded(p: &mut Parser, max: usize) -> Result<usize> {
+    let (n, min) = match parse!(p, u8, "compact size")? {
+        0xfd => (u64::from(parse!(p, u16, "compact size")?), 0xfd),
+        0xfe => (u64::from(parse!(p, u32, "compact size")?), 0x10000),
+        0xff => (parse!(p, u64, "compact size")?, 0x100000000),
+        size => (u64::from(size), 0),
+    };
+    if n < min {
+        bail!(
+            "Non-canonical compact size: {} is encoded in {} bytes instead of {}",
+            n,
+            CompactSize::encoded_len(min),
+            CompactSize::encoded_len(n)
+        );
+    }
+    match usize::try_from(n) {
+        Ok(size) if size <= max => Ok(size),
+        _ => bail!("Compact size {} exceeds maximum {}", n, max),
     }
 }
 
@@ -126,6 +149,46 @@ pub fn parse_compact_size(p: &mut Parser) -> Result<usize> {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct CompactSize(usize);
 
+impl CompactSize {
+    /// Wraps `size` for encoding with [`to_bytes`](Self::to_bytes).
+    ///
+    /// The value is not checked here, but every parse of a compact size,
+    /// including the length prefix of each parsed collection, rejects values
+    /// above [`MAX_COMPACT_SIZE`] unless the caller names a bound of its own
+    /// through [`parse_bounded`](Self::parse_bounded). A larger size therefore
+    /// encodes but does not parse back.
+    pub fn new(size: usize) -> Self {
+        Self(size)
+    }
+
+    /// Parses a compact size as [`parse_compact_size_bounded`] does, rejecting
+    /// values above `max`.
+    pub fn parse_bounded(p: &mut Parser, max: usize) -> Result<Self> {
+        parse_compact_size_bounded(p, max).map(Self)
+    }
+
+    /// Returns the number of bytes in the canonical encoding of `n`.
+    fn encoded_len(n: u64) -> usize {
+        match n {
+            0..=0xfc => 1,
+            0xfd..=0xffff => 3,
+            0x10000..=0xffffffff => 5,
+            _ => 9,
+        }
+    }
+
+    /// Returns the canonical (shortest) serialization of this size.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let n = self.0 as u64;
+        match n {
+            0..=0xfc => vec![n as u8],
+            0xfd..=0xffff => [&[0xfd][..], &(n as u16).to_le_bytes()].concat(),
+            0x10000..=0xffffffff => [&[0xfe][..], &(n as u32).to_le_bytes()].concat(),
+            _ => [&[0xff][..], &n.to_le_bytes()].concat(),
+        }
+    }
+}
+
 impl std::fmt::Display for CompactSize {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         write!(f, "{}", self.0)
@@ -145,3 +208,66 @@ impl std::ops::Deref for CompactSize {
         &self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{CompactSize, MAX_COMPACT_SIZE, parse_compact_size, parse_compact_size_bounded};
+    use crate::parser::prelude::*;
+
+    fn parse(bytes: &[u8]) -> anyhow::Result<usize> {
+        let mut p = Parser::new(&bytes);
+        let size = parse_compact_size(&mut p)?;
+        p.check_finished()?;
+        Ok(size)
+    }
+
+    #[test]
+    fn test_size_tiers() {
+        for (bytes, size) in [
+            (&[0x00][..], 0),
+            (&[0xfc][..], 0xfc),
+            (&[0xfd, 0xfd, 0x00][..], 0xfd),
+            (&[0xfd, 0xff, 0xff][..], 0xffff),
+            (&[0xfe, 0x00, 0x00, 0x01, 0x00][..], 0x10000),
+            (&[0xfe, 0x00, 0x00, 0x00, 0x02][..], MAX_COMPACT_SIZE),
+        ] {
+            assert_eq!(parse(bytes).unwrap(), size);
+            assert_eq!(CompactSize::new(size).to_bytes(), bytes);
+        }
+
+        // The nine-byte tier only holds values above any sensible maximum.
+        let bytes = [0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00];
+        let mut p = Parser::new(&bytes);
+        assert_eq!(
+            parse_compact_size_bounded(&mut p, usize::MAX).unwrap(),
+            0x1_0000_0000
+        );
+    }
+
+    #[test]
+    fn test_rejects_non_canonical() {
+        for bytes in [
+            &[0xfd, 0xfc, 0x00][..],
+            &[0xfe, 0xff, 0xff, 0x00, 0x00][..],
+            &[0xfe, 0x01, 0x00, 0x00, 0x00][..],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0x00, 0x00, 0x00, 0x00][..],
+        ] {
+            let error = parse(bytes).unwrap_err();
+            assert!(error.to_string().starts_with("Non-canonical"), "{error}");
+        }
+    }
+
+    #[test]
+    fn test_rejects_above_maximum() {
+        let error = parse(&[0xfe, 0x01, 0x00, 0x00, 0x02]).unwrap_err();
+        assert!(error.to_string().contains("exceeds maximum"), "{error}");
+        let error = parse(&[0xff, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00]).unwrap_err();
+        assert!(error.to_string().contains("exceeds maximum"), "{error}");
+
+        let mut p = Parser::new(&[0x10]);
+        assert_eq!(*CompactSize::parse_bounded(&mut p, 0x10).unwrap(), 0x10);
+        let mut p = Parser::new(&[0x11]);
+        let error = CompactSize::parse_bounded(&mut p, 0x10).unwrap_err();
+        assert!(error.to_string().contains("exceeds maximum"), "{error}");
+    }
+}
@@ -0,0 +1,203 @@
+use anyhow::{Result, anyhow, bail};
+#[cfg(not(feature = "zstd"))]
+use miniz_oxide::deflate::compress_to_vec;
+use miniz_oxide::inflate::decompress_to_vec_with_limit;
+
+/// The bytes that open a compressed container.
+const MAGIC: [u8; 4] = *b"ZWFZ";
+
+/// The version of the container layout.
+const VERSION: u8 = 1;
+
+/// Magic, version, algorithm and the little-endian `u64` uncompressed length.
+const HEADER_LEN: usize = MAGIC.len() + 2 + 8;
+
+/// The algorithm ID of a raw DEFLATE (RFC 1951) payload, as used by gzip.
+const ALGORITHM_DEFLATE: u8 = 1;
+
+/// The algorithm ID of a zstd (RFC 8878) payload.
+const ALGORITHM_ZSTD: u8 = 2;
+
+/// How hard to work at shrinking a compressed container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CompressionLevel {
+    /// The quickest compression, with the largest output.
+    Fastest,
+
+    /// A balance of speed and size.
+    #[default]
+    Default,
+
+    /// The smallest output, at the cost of speed.
+    Best,
+}
+
+impl CompressionLevel {
+    #[cfg(not(feature = "zstd"))]
+    fn deflate_level(&self) -> u8 {
+        match self {
+            CompressionLevel::Fastest => 1,
+            CompressionLevel::Default => 6,
+            CompressionLevel::Best => 9,
+        }
+    }
+
+    #[cfg(feature = "zstd")]
+    fn zstd_level(&self) -> i32 {
+        match self {
+            CompressionLevel::Fastest => 1,
+            CompressionLevel::Default => zstd::DEFAULT_COMPRESSION_LEVEL,
+            CompressionLevel::Best => 19,
+        }
+    }
+}
+
+/// Returns `data` compressed behind a header naming the algorithm and the
+/// uncompressed length.
+///
+/// The payload is zstd when the `zstd` feature is enabled, and DEFLATE
+/// otherwise.
+pub(crate) fn compress_container(data: &[u8], level: CompressionLevel) -> Result<Vec<u8>> {
+    #[cfg(feature = "zstd")]
+    let (algorithm, payload) = (
+        ALGORITHM_ZSTD,
+        zstd::bulk::compress(data, level.zstd_level())
+            .map_err(|e| anyhow!("Compressing zstd payload: {}", e))?,
+    );
+    #[cfg(not(feature = "zstd"))]
+    let (algorithm, payload) = (
+        ALGORITHM_DEFLATE,
+        compress_to_vec(data, level.deflate_level()),
+    );
+    let mut container = Vec::with_capacity(HEADER_LEN + payload.len());
+    container.extend_from_slice(&MAGIC);
+    container.push(VERSION);
+    container.push(algorithm);
+    container.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    container.extend_from_slice(&payload);
+    Ok(container)
+}
+
+#[cfg(feature = "zstd")]
+fn decompress_zstd(payload: &[u8], length: usize) -> Result<Vec<u8>> {
+    use std::io::Read;
+
+    // Read at most one byte past the declared length, so an overlong payload
+    // is caught by the length check without being inflated in full.
+    let mut data = Vec::new();
+    zstd::stream::read::Decoder::new(payload)
+        .and_then(|decoder| decoder.take(length as u64 + 1).read_to_end(&mut data))
+        .map_err(|e| anyhow!("Decompressing zstd payload: {}", e))?;
+    Ok(data)
+}
+
+#[cfg(not(feature = "zstd"))]
+fn decompress_zstd(_payload: &[u8], _length: usize) -> Result<Vec<u8>> {
+    bail!("zstd-compressed containers need the `zstd` feature")
+}
+
+/// Returns the data held in a container made by [`compress_container`].
+///
+/// DEFLATE payloads are always readable; zstd payloads need the `zstd`
+/// feature.
+///
+/// The decompressed output is never allowed to grow past its declared length,
+/// so a corrupt or hostile header cannot make this allocate more than the
+/// header claims.
+pub(crate) fn decompress_container(container: &[u8]) -> Result<Vec<u8>> {
+    if container.len() < HEADER_LEN {
+        bail!(
+            "Compressed container is {} bytes, shorter than its {}-byte header",
+            container.len(),
+            HEADER_LEN
+        );
+    }
+    let (header, payload) = container.split_at(HEADER_LEN);
+    if header[..MAGIC.len()] != MAGIC {
+        bail!("Not a compressed Zewif container: bad magic bytes");
+    }
+    let version = header[4];
+    if version != VERSION {
+        bail!("Unsupported compressed container version {}", version);
+    }
+    let algorithm = header[5];
+    let length = u64::from_le_bytes(header[6..].try_into().unwrap());
+    let length = usize::try_from(length)
+        .map_err(|_| anyhow!("Compressed container length {} is too large", length))?;
+    let data = match algorithm {
+        ALGORITHM_DEFLATE => decompress_to_vec_with_limit(payload, length)
+            .map_err(|e| anyhow!("Decompressing DEFLATE payload: {}", e))?,
+        ALGORITHM_ZSTD => decompress_zstd(payload, length)?,
+        _ => bail!("Unknown compression algorithm {}", algorithm),
+    };
+    if data.len() != length {
+        bail!(
+            "Compressed container declares {} bytes, but holds {}",
+            length,
+            data.len()
+        );
+    }
+    Ok(data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        ALGORITHM_DEFLATE, ALGORITHM_ZSTD, CompressionLevel, HEADER_LEN, MAGIC, VERSION,
+        compress_container, decompress_container,
+    };
+
+    #[test]
+    fn test_levels_roundtrip() {
+        let data: Vec<u8> = (0..10_000u32)
+            .flat_map(|i| (i % 97).to_le_bytes())
+            .collect();
+        for level in [
+            CompressionLevel::Fastest,
+            CompressionLevel::Default,
+            CompressionLevel::Best,
+        ] {
+            let container = compress_container(&data, level).unwrap();
+            assert!(container.len() < data.len());
+            assert_eq!(decompress_container(&container).unwrap(), data);
+        }
+        let empty = compress_container(&[], CompressionLevel::Default).unwrap();
+        assert!(decompress_container(&empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_deflate_always_readable() {
+        let data = [3u8; 500];
+        let mut container = MAGIC.to_vec();
+        container.push(VERSION);
+        container.push(ALGORITHM_DEFLATE);
+        container.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        container.extend_from_slice(&miniz_oxide::deflate::compress_to_vec(&data, 6));
+        assert_eq!(decompress_container(&container).unwrap(), data);
+    }
+
+    #[test]
+    fn test_corrupt_header_rejected() {
+        let container = compress_container(&[7u8; 1000], CompressionLevel::Default).unwrap();
+        assert!(decompress_container(&container[..HEADER_LEN - 1]).is_err());
+
+        let corrupt = |index: usize, byte: u8| {
+            let mut container = container.clone();
+            container[index] = byte;
+            decompress_container(&container).unwrap_err().to_string()
+        };
+        assert!(corrupt(0, b'X').contains("magic"));
+        assert!(corrupt(4, 2).contains("version"));
+        assert!(corrupt(5, 0xFF).contains("algorithm"));
+        // The payload does not match the algorithm the header names.
+        let other = if container[5] == ALGORITHM_DEFLATE {
+            ALGORITHM_ZSTD
+        } else {
+            ALGORITHM_DEFLATE
+        };
+        corrupt(5, other);
+        // A declared length that is too short or too long for the payload.
+        corrupt(6, 0xE7);
+        assert!(corrupt(7, 0xFF).contains("declares"));
+    }
+}
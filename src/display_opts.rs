@@ -0,0 +1,89 @@
+/// The unit in which an [`Amount`](crate::Amount) is displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AmountUnit {
+    /// Whole ZEC with up to eight decimal places.
+    #[default]
+    Zec,
+
+    /// Integral zatoshis.
+    Zatoshi,
+}
+
+impl AmountUnit {
+    /// The label appended to a displayed amount when requested.
+    pub fn label(&self) -> &'static str {
+        match self {
+            AmountUnit::Zec => "ZEC",
+            AmountUnit::Zatoshi => "zat",
+        }
+    }
+}
+
+/// Presentation options for [`Amount::to_display_string`](crate::Amount::to_display_string).
+///
+/// The default options render a plain ZEC value with trailing fractional zeros
+/// trimmed and no digit grouping, e.g. `1.5` or `-0.00000001`.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Amount, AmountUnit, DisplayOpts};
+/// let amount = Amount::const_from_u64(123_456_789_000);
+///
+/// assert_eq!(amount.to_display_string(&DisplayOpts::default()), "1234.56789");
+///
+/// let opts = DisplayOpts::default()
+///     .with_thousands_separator(',')
+///     .with_unit_label(true);
+/// assert_eq!(amount.to_display_string(&opts), "1,234.56789 ZEC");
+///
+/// let opts = DisplayOpts::default().with_unit(AmountUnit::Zatoshi);
+/// assert_eq!(amount.to_display_string(&opts), "123456789000");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct DisplayOpts {
+    /// The unit used to scale the value.
+    pub unit: AmountUnit,
+
+    /// The separator inserted between groups of three integer digits, if any.
+    pub thousands_separator: Option<char>,
+
+    /// Whether trailing zeros are removed from the fractional part of a ZEC value.
+    /// When all fractional digits are zero the decimal point is dropped as well.
+    pub trim_trailing_zeros: bool,
+
+    /// Whether the unit label (`ZEC` or `zat`) is appended.
+    pub unit_label: bool,
+}
+
+impl Default for DisplayOpts {
+    fn default() -> Self {
+        Self {
+            unit: AmountUnit::Zec,
+            thousands_separator: None,
+            trim_trailing_zeros: true,
+            unit_label: false,
+        }
+    }
+}
+
+impl DisplayOpts {
+    pub fn with_unit(mut self, unit: AmountUnit) -> Self {
+        self.unit = unit;
+        self
+    }
+
+    pub fn with_thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    pub fn with_trim_trailing_zeros(mut self, trim: bool) -> Self {
+        self.trim_trailing_zeros = trim;
+        self
+    }
+
+    pub fn with_unit_label(mut self, unit_label: bool) -> Self {
+        self.unit_label = unit_label;
+        self
+    }
+}
@@ -0,0 +1,304 @@
+use anyhow::{Context, Result, bail};
+use bc_envelope::prelude::*;
+
+/// Describes the assertions a type's envelope encoding is made of.
+///
+/// Every ZeWIF type that encodes to a typed envelope (one carrying an `isA`
+/// assertion) names the predicates it requires and those it may carry. The
+/// ordinary `TryFrom<Envelope>` conversions are lenient and accept predicates
+/// they do not recognize, which keeps older readers working with newer files.
+///
+/// Only the types with attachments ([`Zewif`](crate::Zewif),
+/// [`ZewifWallet`](crate::ZewifWallet), [`Account`](crate::Account),
+/// [`Address`](crate::Address) and [`Transaction`](crate::Transaction)) keep
+/// such assertions, in a [`PRESERVED_UNKNOWNS_CONFORMS_TO`] attachment, and
+/// write them back out when re-encoded. The others, including sent outputs,
+/// protocol addresses and keys, have nowhere to keep them, so an unknown
+/// assertion on one of those is dropped on a round trip. A newer writer that
+/// extends one of them should carry the new data in an assertion of the
+/// enclosing type instead. [`decode_strict`](Self::decode_strict)
+/// uses the schema to reject such inputs instead, in the envelope and in every
+/// nested envelope with a schema of its own, which is useful when validating
+/// exports.
+///
+/// Predicates that may repeat (such as an account's `address` assertions) are
+/// listed as optional, since they may also be absent.
+///
+/// # Examples
+/// ```
+/// # use zewif::{EnvelopeSchema, sapling::SaplingSentOutput};
+/// assert_eq!(
+///     SaplingSentOutput::required_predicates(),
+///     &["diversifier", "receipient_public_key", "value"]
+/// );
+/// ```
+pub trait EnvelopeSchema: TryFrom<Envelope, Error = anyhow::Error> {
+    /// The type name asserted with `isA`.
+    const TYPE_NAME: &'static str;
+
+    /// Whether the type carries vendor attachments.
+    const ATTACHMENTS: bool = false;
+
+    /// Predicates that must be present for the envelope to decode.
+    fn required_predicates() -> &'static [&'static str];
+
+    /// Predicates that may be present, possibly more than once.
+    fn optional_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Decodes `envelope`, first rejecting it if it, or any envelope nested in
+    /// it, has an assertion that is not part of its type's schema or lacks a
+    /// required predicate.
+    fn decode_strict(envelope: Envelope) -> Result<Self>
+    where
+        Self: Sized,
+    {
+        Self::check_strict(&envelope)?;
+        Self::try_from(envelope)
+    }
+
+    /// Checks `envelope` and the envelopes nested in it against their schemas,
+    /// without decoding them.
+    fn check_strict(envelope: &Envelope) -> Result<()> {
+        for assertion in envelope.assertions() {
+            if let Some(predicate) = unexpected_predicate::<Self>(&assertion)? {
+                bail!("Unexpected {} predicate {}", Self::TYPE_NAME, predicate);
+            }
+        }
+        for predicate in Self::required_predicates() {
+            if envelope.assertions_with_predicate(*predicate).is_empty() {
+                bail!(
+                    "Missing required {} predicate \"{}\"",
+                    Self::TYPE_NAME,
+                    predicate
+                );
+            }
+        }
+        Self::check_nested_strict(envelope)
+    }
+
+    /// Checks the envelopes nested in `envelope` that have schemas of their
+    /// own.
+    ///
+    /// Types whose assertions hold other schema types override this, usually
+    /// with [`check_objects_strict`] for each such predicate.
+    fn check_nested_strict(_envelope: &Envelope) -> Result<()> {
+        Ok(())
+    }
+
+    /// Returns a self-describing envelope listing this type's schema.
+    ///
+    /// The subject is the type name, with one `required` or `optional`
+    /// assertion per predicate and an `attachments` assertion recording whether
+    /// vendor attachments are carried.
+    fn schema_descriptor() -> Envelope {
+        let e = Envelope::new(Self::TYPE_NAME)
+            .add_type("EnvelopeSchema")
+            .add_assertion("attachments", Self::ATTACHMENTS);
+        let e = Self::required_predicates()
+            .iter()
+            .fold(e, |e, predicate| e.add_assertion("required", *predicate));
+        Self::optional_predicates()
+            .iter()
+            .fold(e, |e, predicate| e.add_assertion("optional", *predicate))
+    }
+}
+
+/// The vendor of the attachment in which a lenient decode preserves the
+/// assertions it does not recognize.
+pub const PRESERVED_UNKNOWNS_VENDOR: &str = "zewif";
+
+/// The `conformsTo` of the attachment in which a lenient decode preserves the
+/// assertions it does not recognize.
+pub const PRESERVED_UNKNOWNS_CONFORMS_TO: &str = "preserved_unknowns";
+
+/// Checks every object of `envelope`'s `predicate` assertions against `T`'s
+/// schema with [`EnvelopeSchema::check_strict`].
+pub fn check_objects_strict<T: EnvelopeSchema>(envelope: &Envelope, predicate: &str) -> Result<()> {
+    for object in envelope.objects_for_predicate(predicate) {
+        T::check_strict(&object).with_context(|| format!("{} {}", T::TYPE_NAME, predicate))?;
+    }
+    Ok(())
+}
+
+/// Returns the name of `assertion`'s predicate if it is not part of `T`'s
+/// schema, or `None` if it is.
+fn unexpected_predicate<T: EnvelopeSchema>(assertion: &Envelope) -> Result<Option<String>> {
+    let predicate = assertion.try_predicate()?;
+    if let Some(known_value) = predicate.as_known_value() {
+        let expected = *known_value == known_values::IS_A
+            || (T::ATTACHMENTS && *known_value == known_values::ATTACHMENT);
+        return Ok((!expected).then(|| known_value.name()));
+    }
+    let name: String = predicate
+        .extract_subject()
+        .with_context(|| format!("{} predicate", T::TYPE_NAME))?;
+    let expected = T::required_predicates().contains(&name.as_str())
+        || T::optional_predicates().contains(&name.as_str());
+    Ok((!expected).then(|| format!("\"{}\"", name)))
+}
+
+/// Decodes the attachments of a `T` envelope, preserving the assertions that
+/// are not part of `T`'s schema.
+///
+/// Assertions written by a newer version of the format would otherwise be lost
+/// when a file passes through this crate. They are gathered, unchanged, into an
+/// attachment from [`PRESERVED_UNKNOWNS_VENDOR`] conforming to
+/// [`PRESERVED_UNKNOWNS_CONFORMS_TO`], whose payload has `T`'s type name as its
+/// subject. [`encode_attachments`] puts them back where they were found.
+pub(crate) fn decode_attachments<T: EnvelopeSchema>(envelope: &Envelope) -> Result<Attachments> {
+    let mut attachments = Attachments::try_from_envelope(envelope)?;
+    let mut unknowns = Vec::new();
+    for assertion in envelope.assertions() {
+        // An assertion whose predicate cannot be read is preserved as well.
+        if !matches!(unexpected_predicate::<T>(&assertion), Ok(None)) {
+            unknowns.push(assertion);
+        }
+    }
+    if !unknowns.is_empty() {
+        let payload = Envelope::new(T::TYPE_NAME).add_assertion_envelopes(&unknowns)?;
+        attachments.add(
+            payload,
+            PRESERVED_UNKNOWNS_VENDOR,
+            Some(PRESERVED_UNKNOWNS_CONFORMS_TO),
+        );
+    }
+    Ok(attachments)
+}
+
+/// Adds `attachments` to `envelope`, restoring any assertions preserved by
+/// [`decode_attachments`] as assertions of `envelope` itself.
+pub(crate) fn encode_attachments(attachments: &Attachments, envelope: Envelope) -> Envelope {
+    let mut envelope = attachments.add_to_envelope(envelope);
+    let preserved = envelope
+        .attachments_with_vendor_and_conforms_to(
+            Some(PRESERVED_UNKNOWNS_VENDOR),
+            Some(PRESERVED_UNKNOWNS_CONFORMS_TO),
+        )
+        .unwrap_or_default();
+    for attachment in preserved {
+        // A malformed bucket is left in place as an ordinary attachment.
+        if let Ok(payload) = attachment.attachment_payload() {
+            envelope = envelope
+                .remove_assertion(attachment)
+                .add_assertion_envelopes(&payload.assertions())
+                .expect("preserved assertions are assertion envelopes");
+        }
+    }
+    envelope
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::EnvelopeSchema;
+    use crate::{
+        Account, Address, Network, OrchardSentOutput, RandomInstance, Transaction, UnifiedAddress,
+        Zewif, ZewifWallet, sapling, sapling::SaplingSentOutput, transparent,
+    };
+
+    fn check_schema<T>()
+    where
+        T: EnvelopeSchema + RandomInstance + Into<Envelope> + Clone + PartialEq + std::fmt::Debug,
+    {
+        for _ in 0..10 {
+            let value = T::random();
+            let decoded = T::decode_strict(value.clone().into())
+                .unwrap_or_else(|e| panic!("{}: {:#}", T::TYPE_NAME, e));
+            assert_eq!(decoded, value);
+        }
+    }
+
+    #[test]
+    fn test_schemas_match_encodings() {
+        check_schema::<Zewif>();
+        check_schema::<ZewifWallet>();
+        check_schema::<Account>();
+        check_schema::<Address>();
+        check_schema::<Transaction>();
+        check_schema::<SaplingSentOutput>();
+        check_schema::<OrchardSentOutput>();
+        check_schema::<transparent::Address>();
+        check_schema::<transparent::TexAddress>();
+        check_schema::<sapling::Address>();
+        check_schema::<UnifiedAddress>();
+        check_schema::<sapling::DisclosureBundle>();
+    }
+
+    #[test]
+    fn test_sapling_sent_output_schema() {
+        assert_eq!(
+            SaplingSentOutput::required_predicates(),
+            &["diversifier", "receipient_public_key", "value"]
+        );
+        assert_eq!(
+            SaplingSentOutput::optional_predicates(),
+            &["rcm", "rseed", "memo"]
+        );
+
+        let descriptor = SaplingSentOutput::schema_descriptor();
+        assert_eq!(
+            descriptor.extract_subject::<String>().unwrap(),
+            "SaplingSentOutput"
+        );
+        assert_eq!(descriptor.objects_for_predicate("required").len(), 3);
+        assert_eq!(descriptor.objects_for_predicate("optional").len(), 3);
+    }
+
+    #[test]
+    fn test_decode_strict_attachments() {
+        let mut account = Account::random();
+        account
+            .attachments_mut()
+            .add("metadata", "org.example", None::<&str>);
+        let envelope = Envelope::from(account.clone());
+        assert_eq!(Account::decode_strict(envelope).unwrap(), account);
+
+        // A type without attachments rejects them.
+        let output = SaplingSentOutput::random();
+        let envelope =
+            Envelope::from(output).add_attachment("metadata", "org.example", None::<&str>);
+        assert!(SaplingSentOutput::decode_strict(envelope).is_err());
+    }
+
+    #[test]
+    fn test_decode_strict_checks_nested_envelopes() {
+        let account = Envelope::from(Account::new()).add_assertion("future_field", "nested");
+        let wallet = Envelope::from(ZewifWallet::new(Network::Main)).add_assertion("account", account);
+        let zewif = Envelope::from(Zewif::new()).add_assertion("wallet", wallet);
+
+        // The lenient decode accepts the unknown predicate and keeps it.
+        let decoded = Zewif::try_from(zewif.clone()).unwrap();
+        assert!(Envelope::from(decoded).is_identical_to(&zewif));
+
+        let error = Zewif::decode_strict(zewif).unwrap_err();
+        assert!(format!("{:#}", error).contains("Unexpected Account predicate \"future_field\""));
+    }
+
+    #[test]
+    fn test_unknown_assertions_are_preserved() {
+        let account = Account::random();
+        let envelope = Envelope::from(account.clone())
+            .add_assertion("future_field", "from a newer writer")
+            .add_assertion(42, "with a non-string predicate");
+
+        let decoded = Account::try_from(envelope.clone()).unwrap();
+        assert_ne!(decoded, account);
+        assert!(Account::decode_strict(envelope.clone()).is_err());
+
+        let reencoded = Envelope::from(decoded);
+        assert_eq!(
+            reencoded
+                .extract_object_for_predicate::<String>("future_field")
+                .unwrap(),
+            "from a newer writer"
+        );
+        assert!(reencoded.is_identical_to(&envelope));
+
+        // An envelope without unknown assertions decodes to an equal value.
+        let decoded = Account::try_from(Envelope::from(account.clone())).unwrap();
+        assert_eq!(decoded, account);
+    }
+}
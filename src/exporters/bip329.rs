@@ -0,0 +1,179 @@
+//! Export of wallet labels in the [BIP-329](https://github.com/bitcoin/bips/blob/master/bip-0329.mediawiki)
+//! format.
+//!
+//! Named addresses are written as `addr` records whose `ref` is the address
+//! string, and labeled transactions as `tx` records whose `ref` is the txid in
+//! its usual byte-reversed hex form. ZeWIF does not model labels on individual
+//! transaction outputs, so no `output` records are produced. The output can be
+//! read back with [`importers::bip329::apply_labels`](crate::importers::bip329::apply_labels).
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::Zewif;
+
+/// Returns a BIP-329 JSON Lines document with a record for every named address
+/// and every labeled transaction in `zewif`.
+///
+/// Addresses are written in wallet, account and address order, followed by
+/// transactions in txid order. Each record is terminated by a newline.
+///
+/// # Errors
+/// Returns an error if a lazily held transaction fails to decode, since its
+/// label cannot otherwise be read.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Transaction, TxId, Zewif};
+/// # use zewif::exporters::bip329;
+/// let txid = TxId::from_bytes([1u8; 32]);
+/// let mut transaction = Transaction::new(txid);
+/// transaction.set_label("Rent");
+/// let mut zewif = Zewif::new();
+/// zewif.add_transaction(txid, transaction);
+///
+/// assert_eq!(
+///     bip329::export_labels(&zewif).unwrap(),
+///     format!("{{\"type\":\"tx\",\"ref\":\"{}\",\"label\":\"Rent\"}}\n", txid)
+/// );
+/// ```
+pub fn export_labels(zewif: &Zewif) -> Result<String> {
+    let mut jsonl = String::new();
+
+    let addresses = zewif
+        .wallets()
+        .iter()
+        .flat_map(|wallet| wallet.accounts())
+        .flat_map(|account| account.addresses())
+        .filter(|address| !address.name().is_empty());
+    for address in addresses {
+        write_record(&mut jsonl, "addr", &address.as_string(), address.name())?;
+    }
+
+    let mut txids: Vec<_> = zewif.transactions().keys().copied().collect();
+    txids.sort();
+    for txid in txids {
+        let transaction = zewif
+            .load_transaction(txid)
+            .with_context(|| format!("transaction {}", txid))?;
+        if let Some(label) = transaction.and_then(|transaction| transaction.label()) {
+            write_record(&mut jsonl, "tx", &txid.to_string(), label)?;
+        }
+    }
+
+    Ok(jsonl)
+}
+
+/// A single BIP-329 record, with its fields in the order BIP-329 lists them.
+#[derive(Serialize)]
+struct Record<'a> {
+    #[serde(rename = "type")]
+    kind: &'a str,
+    #[serde(rename = "ref")]
+    reference: &'a str,
+    label: &'a str,
+}
+
+fn write_record(jsonl: &mut String, kind: &str, reference: &str, label: &str) -> Result<()> {
+    let record = Record {
+        kind,
+        reference,
+        label,
+    };
+    jsonl.push_str(&serde_json::to_string(&record)?);
+    jsonl.push('\n');
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::export_labels;
+    use crate::{
+        Account, Address, LazyTransaction, Network, ProtocolAddress, Transaction, TxId,
+        UnifiedAddress, Zewif, ZewifWallet, importers::bip329::apply_labels, transparent,
+    };
+
+    fn zewif(named: bool) -> Zewif {
+        let mut account = Account::new();
+        for (address, name) in [
+            (
+                ProtocolAddress::Transparent(transparent::Address::new("t1first")),
+                "Savings \"main\"\n",
+            ),
+            (
+                ProtocolAddress::Unified(Box::new(UnifiedAddress::new("u1second".to_string()))),
+                "Café\u{1}",
+            ),
+            (
+                ProtocolAddress::Transparent(transparent::Address::new("t1unnamed")),
+                "",
+            ),
+        ] {
+            let mut address = Address::new(address);
+            if named {
+                address.set_name(name.to_string());
+            }
+            account.add_address(address);
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+        for (byte, label) in [(2u8, "Rent"), (1, "Salary"), (3, "")] {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut transaction = Transaction::new(txid);
+            if named && !label.is_empty() {
+                transaction.set_label(label);
+            }
+            zewif.add_transaction(txid, transaction);
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_export_labels() {
+        let jsonl = export_labels(&zewif(true)).unwrap();
+        let lines: Vec<&str> = jsonl.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                r#"{"type":"addr","ref":"t1first","label":"Savings \"main\"\n"}"#.to_string(),
+                r#"{"type":"addr","ref":"u1second","label":"Café\u0001"}"#.to_string(),
+                format!(
+                    r#"{{"type":"tx","ref":"{}","label":"Salary"}}"#,
+                    TxId::from_bytes([1; 32])
+                ),
+                format!(
+                    r#"{{"type":"tx","ref":"{}","label":"Rent"}}"#,
+                    TxId::from_bytes([2; 32])
+                ),
+            ]
+        );
+        assert!(export_labels(&zewif(false)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_export_labels_undecodable_transaction() {
+        let mut zewif = zewif(true);
+        let txid = TxId::from_bytes([4u8; 32]);
+        let envelope = Envelope::from(Transaction::new(txid)).add_assertion("mined_height", "high");
+        zewif.add_lazy_transaction(LazyTransaction::new(envelope).unwrap());
+        let error = export_labels(&zewif).unwrap_err();
+        assert!(format!("{:#}", error).contains(&txid.to_string()));
+    }
+
+    #[test]
+    fn test_export_import_roundtrip() {
+        let named = zewif(true);
+        let mut unnamed = zewif(false);
+        assert_eq!(
+            apply_labels(&mut unnamed, &export_labels(&named).unwrap()).unwrap(),
+            4
+        );
+        assert_eq!(unnamed.wallets(), named.wallets());
+        assert_eq!(unnamed.transactions(), named.transactions());
+    }
+}
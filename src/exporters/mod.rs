@@ -0,0 +1,16 @@
+//! # Exporters
+//!
+//! Writers for interchange formats defined outside ZeWIF, so that data held in a
+//! [`Zewif`](crate::Zewif) container can move to wallets that do not read ZeWIF.
+//! The exporters write JSON, and are available with the `serde` feature.
+//!
+//! ## Key Components
+//!
+//! - [`bip329`]: Wallet labels in the BIP-329 JSON Lines format
+//! - [`ndjson`]: A lossy, line-per-record dump for command-line inspection
+//!
+//! The BIP-329 exporter mirrors the importer of the same name in
+//! [`importers`](crate::importers).
+
+pub mod bip329;
+pub mod ndjson;
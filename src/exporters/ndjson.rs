@@ -0,0 +1,216 @@
+//! A line-oriented dump of a [`Zewif`] container for command-line inspection.
+//!
+//! Each address and each transaction is written as a flat JSON object on its
+//! own line ([NDJSON](https://github.com/ndjson/ndjson-spec)), so the output can
+//! be filtered with `grep` or `jq` without loading the whole container. The
+//! dump is lossy: it carries only the fields useful for finding things, and
+//! cannot be read back into a `Zewif`.
+//!
+//! Address records have `"type":"address"` and give the wallet and account
+//! indexes, the address protocol, the address string and its name. Transaction
+//! records have `"type":"transaction"` and give the txid, the mined and target
+//! heights and the label, with `null` for any that are unknown.
+
+use std::io::{self, Write};
+
+use serde::Serialize;
+
+use crate::{Address, ProtocolAddress, Transaction, TxId, Zewif};
+
+/// Writes one NDJSON record for every address and every transaction in
+/// `zewif` to `w`.
+///
+/// Addresses are written in wallet, account and address order, followed by
+/// transactions in txid order. A lazily held transaction that fails to decode
+/// is written with its txid and an `error` field in place of its other fields.
+///
+/// # Errors
+/// Returns any error reported by `w`.
+///
+/// # Examples
+/// ```
+/// # use zewif::{BlockHeight, Transaction, TxId, Zewif};
+/// # use zewif::exporters::ndjson;
+/// let txid = TxId::from_bytes([1u8; 32]);
+/// let mut transaction = Transaction::new(txid);
+/// transaction.set_mined_height(BlockHeight::from(2_000_000));
+/// let mut zewif = Zewif::new();
+/// zewif.add_transaction(txid, transaction);
+///
+/// let mut output = Vec::new();
+/// ndjson::write(&zewif, &mut output).unwrap();
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     format!(
+///         "{{\"type\":\"transaction\",\"txid\":\"{}\",\"mined_height\":2000000,\"target_height\":null,\"label\":null}}\n",
+///         txid
+///     )
+/// );
+/// ```
+pub fn write<W: Write>(zewif: &Zewif, w: &mut W) -> io::Result<()> {
+    for (wallet_index, wallet) in zewif.wallets().iter().enumerate() {
+        for (account_index, account) in wallet.accounts().iter().enumerate() {
+            for address in account.addresses() {
+                write_record(w, &address_record(wallet_index, account_index, address))?;
+            }
+        }
+    }
+
+    let mut txids: Vec<_> = zewif.transactions().keys().copied().collect();
+    txids.sort();
+    for txid in txids {
+        match zewif.load_transaction(txid) {
+            Ok(Some(transaction)) => write_record(w, &TransactionRecord::new(transaction))?,
+            Ok(None) => continue,
+            Err(error) => write_record(w, &ErrorRecord::new(txid, error.to_string()))?,
+        }
+    }
+
+    Ok(())
+}
+
+fn write_record<W: Write, R: Serialize>(w: &mut W, record: &R) -> io::Result<()> {
+    serde_json::to_writer(&mut *w, record)?;
+    w.write_all(b"\n")
+}
+
+#[derive(Serialize)]
+struct AddressRecord {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    wallet: usize,
+    account: usize,
+    protocol: &'static str,
+    address: String,
+    name: String,
+}
+
+fn address_record(wallet_index: usize, account_index: usize, address: &Address) -> AddressRecord {
+    let protocol = match address.address() {
+        ProtocolAddress::Transparent(_) => "transparent",
+        ProtocolAddress::Tex(_) => "tex",
+        ProtocolAddress::Sapling(_) => "sapling",
+        ProtocolAddress::Unified(_) => "unified",
+    };
+    AddressRecord {
+        kind: "address",
+        wallet: wallet_index,
+        account: account_index,
+        protocol,
+        address: address.as_string(),
+        name: address.name().to_string(),
+    }
+}
+
+#[derive(Serialize)]
+struct TransactionRecord<'a> {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    txid: String,
+    mined_height: Option<u32>,
+    target_height: Option<u32>,
+    label: Option<&'a str>,
+}
+
+impl<'a> TransactionRecord<'a> {
+    fn new(transaction: &'a Transaction) -> Self {
+        Self {
+            kind: "transaction",
+            txid: transaction.txid().to_string(),
+            mined_height: transaction.mined_height().copied().map(u32::from),
+            target_height: transaction.target_height().copied().map(u32::from),
+            label: transaction.label(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ErrorRecord {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    txid: String,
+    error: String,
+}
+
+impl ErrorRecord {
+    fn new(txid: TxId, error: String) -> Self {
+        Self {
+            kind: "transaction",
+            txid: txid.to_string(),
+            error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::write;
+    use crate::{
+        Account, Address, BlockHeight, Network, ProtocolAddress, Transaction, TxId, UnifiedAddress,
+        Zewif, ZewifWallet, transparent,
+    };
+
+    fn zewif() -> Zewif {
+        let mut zewif = Zewif::new();
+        for network in [Network::Main, Network::Test] {
+            let mut account = Account::new();
+            let mut named = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+                "t1first",
+            )));
+            named.set_name("Savings \"main\"\n".to_string());
+            account.add_address(named);
+            account.add_address(Address::new(ProtocolAddress::Unified(Box::new(
+                UnifiedAddress::new("u1second".to_string()),
+            ))));
+            let mut wallet = ZewifWallet::new(network);
+            wallet.add_account(account);
+            wallet.add_account(Account::new());
+            zewif.add_wallet(wallet);
+        }
+
+        for byte in 1..=3u8 {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut transaction = Transaction::new(txid);
+            if byte == 2 {
+                transaction.set_mined_height(BlockHeight::from(1_000_000));
+                transaction.set_label("Café\u{1}");
+            }
+            zewif.add_transaction(txid, transaction);
+        }
+        zewif
+    }
+
+    #[test]
+    fn test_write() {
+        let zewif = zewif();
+        let mut output = Vec::new();
+        write(&zewif, &mut output).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.ends_with('\n'));
+
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 4 + 3);
+        for line in &lines {
+            serde_json::from_str::<serde_json::Value>(line).unwrap();
+        }
+        assert_eq!(
+            lines[0],
+            r#"{"type":"address","wallet":0,"account":0,"protocol":"transparent","address":"t1first","name":"Savings \"main\"\n"}"#
+        );
+        assert_eq!(
+            lines[3],
+            r#"{"type":"address","wallet":1,"account":0,"protocol":"unified","address":"u1second","name":""}"#
+        );
+        assert_eq!(
+            lines[5],
+            format!(
+                r#"{{"type":"transaction","txid":"{}","mined_height":1000000,"target_height":null,"label":"Café\u0001"}}"#,
+                TxId::from_bytes([2; 32])
+            )
+        );
+
+        let mut empty = Vec::new();
+        write(&Zewif::new(), &mut empty).unwrap();
+        assert!(empty.is_empty());
+    }
+}
@@ -0,0 +1,373 @@
+//! Import of wallet labels in the [BIP-329](https://github.com/bitcoin/bips/blob/master/bip-0329.mediawiki)
+//! format.
+//!
+//! A BIP-329 export is a JSON Lines file: each line is a JSON object with a
+//! `type`, a `ref` identifying the labeled item and an optional `label`. Records
+//! of type `addr` name the matching addresses, and records of type `tx` label
+//! the matching transactions. ZeWIF does not model individual transaction
+//! outputs, inputs or public keys, so `output`, `input`, `pubkey` and `xpub`
+//! records are accepted but not applied, as are records of unknown types.
+
+use anyhow::{Context, Result, bail};
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::ImportWarnings;
+use crate::{TxId, Zewif};
+
+/// Applies the labels in a BIP-329 JSON Lines document to `zewif`, returning
+/// the number of records that matched at least one address or transaction.
+///
+/// An `addr` label becomes the name of every address, in any wallet, whose
+/// string encoding equals the record's `ref`. A `tx` label is set on the
+/// transaction whose txid, in its usual byte-reversed hex form, equals the
+/// record's `ref`. Blank lines and records without a label are skipped.
+///
+/// # Errors
+/// Returns an error, without applying any label, if a line is not a JSON
+/// object, if a record lacks a string `type` or `ref`, if a `tx` record's
+/// `ref` is not a txid, or if a lazily held transaction named by a `tx` record
+/// cannot be decoded.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Account, Address, Network, ProtocolAddress, Zewif, ZewifWallet, transparent};
+/// # use zewif::importers::bip329;
+/// let mut account = Account::new();
+/// account.add_address(Address::new(ProtocolAddress::Transparent(
+///     transparent::Address::new("t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs"),
+/// )));
+/// let mut wallet = ZewifWallet::new(Network::Main);
+/// wallet.add_account(account);
+/// let mut zewif = Zewif::new();
+/// zewif.add_wallet(wallet);
+///
+/// let labels = r#"{"type":"addr","ref":"t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs","label":"Savings"}"#;
+/// assert_eq!(bip329::apply_labels(&mut zewif, labels).unwrap(), 1);
+/// assert_eq!(zewif.wallets()[0].accounts()[0].addresses()[0].name(), "Savings");
+/// ```
+pub fn apply_labels(zewif: &mut Zewif, jsonl: &str) -> Result<usize> {
+    let records = read_records(zewif, jsonl)?;
+    let mut applied = 0;
+    for record in records {
+        let Some(label) = record.label else {
+            continue;
+        };
+        let matched = match record.target {
+            Target::Address(reference) => {
+                let mut matched = false;
+                for wallet in zewif.wallets_mut() {
+                    for account in wallet.accounts_mut() {
+                        for address in account.addresses_mut() {
+                            if address.as_string() == reference {
+                                address.set_name(label.clone());
+                                matched = true;
+                            }
+                        }
+                    }
+                }
+                matched
+            }
+            Target::Transaction(txid) => match zewif.load_transaction_mut(txid)? {
+                Some(transaction) => {
+                    transaction.set_label(label);
+                    true
+                }
+                None => false,
+            },
+            Target::Unsupported => false,
+        };
+        if matched {
+            applied += 1;
+        }
+    }
+    Ok(applied)
+}
+
+/// Adds the labels in a BIP-329 JSON Lines document to `existing` without
+/// overwriting the labels it already holds, for wallets whose labels are
+/// imported in stages.
+///
+/// Labels are matched as in [`apply_labels`], and follow the rules of
+/// [`Zewif::merge`]: a label is set on an address or transaction that has
+/// none, a label equal to the one held is skipped, and a differing label is
+/// reported in the returned [`ImportWarnings`] while the held label is kept.
+/// Labeled records that match nothing, or whose type ZeWIF does not model,
+/// are counted as unapplied.
+///
+/// # Errors
+/// Returns an error, without applying any label, in the same cases as
+/// [`apply_labels`].
+///
+/// # Examples
+/// ```
+/// # use zewif::{Account, Address, Network, ProtocolAddress, Zewif, ZewifWallet, transparent};
+/// # use zewif::importers::bip329;
+/// let mut account = Account::new();
+/// account.add_address(Address::new(ProtocolAddress::Transparent(
+///     transparent::Address::new("t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs"),
+/// )));
+/// let mut wallet = ZewifWallet::new(Network::Main);
+/// wallet.add_account(account);
+/// let mut zewif = Zewif::new();
+/// zewif.add_wallet(wallet);
+///
+/// let first = r#"{"type":"addr","ref":"t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs","label":"Savings"}"#;
+/// assert!(bip329::import_into(&mut zewif, first).unwrap().is_empty());
+///
+/// let second = r#"{"type":"addr","ref":"t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs","label":"Spending"}"#;
+/// let warnings = bip329::import_into(&mut zewif, second).unwrap();
+/// assert_eq!(warnings.conflicting_addresses, ["t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs"]);
+/// assert_eq!(zewif.wallets()[0].accounts()[0].addresses()[0].name(), "Savings");
+/// ```
+pub fn import_into(existing: &mut Zewif, jsonl: &str) -> Result<ImportWarnings> {
+    let records = read_records(existing, jsonl)?;
+    let mut warnings = ImportWarnings::default();
+    for record in records {
+        let Some(label) = record.label else {
+            continue;
+        };
+        match record.target {
+            Target::Address(reference) => {
+                let mut matched = false;
+                let mut conflicting = false;
+                for wallet in existing.wallets_mut() {
+                    for account in wallet.accounts_mut() {
+                        for address in account.addresses_mut() {
+                            if address.as_string() != reference {
+                                continue;
+                            }
+                            matched = true;
+                            if address.name().is_empty() {
+                                address.set_name(label.clone());
+                            } else if address.name() != label {
+                                conflicting = true;
+                            }
+                        }
+                    }
+                }
+                if !matched {
+                    warnings.unapplied_records += 1;
+                } else if conflicting && !warnings.conflicting_addresses.contains(&reference) {
+                    warnings.conflicting_addresses.push(reference);
+                }
+            }
+            Target::Transaction(txid) => match existing.load_transaction_mut(txid)? {
+                Some(transaction) => match transaction.label() {
+                    None => transaction.set_label(label),
+                    Some(held) if held != label => {
+                        if !warnings.conflicting_transactions.contains(&txid) {
+                            warnings.conflicting_transactions.push(txid);
+                        }
+                    }
+                    Some(_) => {}
+                },
+                None => warnings.unapplied_records += 1,
+            },
+            Target::Unsupported => warnings.unapplied_records += 1,
+        }
+    }
+    Ok(warnings)
+}
+
+/// Parses the records of a BIP-329 document and decodes every transaction
+/// they label, so that a failure leaves `zewif` untouched rather than
+/// partially labeled.
+fn read_records(zewif: &mut Zewif, jsonl: &str) -> Result<Vec<Record>> {
+    let records = jsonl
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| Record::parse(line).with_context(|| format!("BIP-329 line {}", i + 1)))
+        .collect::<Result<Vec<_>>>()?;
+    for record in &records {
+        if let (Target::Transaction(txid), Some(_)) = (&record.target, &record.label) {
+            zewif.load_transaction_mut(*txid)?;
+        }
+    }
+    Ok(records)
+}
+
+/// A single BIP-329 record, reduced to the fields ZeWIF can apply.
+struct Record {
+    target: Target,
+    label: Option<String>,
+}
+
+/// The item a BIP-329 record labels.
+enum Target {
+    Address(String),
+    Transaction(TxId),
+    Unsupported,
+}
+
+/// The fields of a BIP-329 record that ZeWIF reads. Other fields, such as
+/// `origin` or `spendable`, are ignored.
+#[derive(Deserialize)]
+struct RawRecord {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(rename = "ref")]
+    reference: String,
+    #[serde(default)]
+    label: Option<String>,
+}
+
+impl Record {
+    fn parse(line: &str) -> Result<Self> {
+        let Value::Object(fields) = serde_json::from_str(line)? else {
+            bail!("Record must be a JSON object");
+        };
+        let RawRecord {
+            kind,
+            reference,
+            label,
+        } = serde_json::from_value(Value::Object(fields))?;
+        let target = match kind.as_str() {
+            "addr" => Target::Address(reference),
+            "tx" => Target::Transaction(
+                TxId::from_hex(&reference).with_context(|| format!("txid \"{}\"", reference))?,
+            ),
+            _ => Target::Unsupported,
+        };
+        Ok(Self { target, label })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::{apply_labels, import_into};
+    use crate::{
+        Account, Address, LazyTransaction, Network, ProtocolAddress, Transaction, TxId,
+        UnifiedAddress, Zewif, ZewifWallet, transparent,
+    };
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    fn zewif() -> Zewif {
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1first"),
+        )));
+        account.add_address(Address::new(ProtocolAddress::Unified(Box::new(
+            UnifiedAddress::new("u1second".to_string()),
+        ))));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+        let txid = TxId::from_hex(TXID).unwrap();
+        zewif.add_transaction(txid, Transaction::new(txid));
+        zewif
+    }
+
+    #[test]
+    fn test_apply_labels() {
+        let mut zewif = zewif();
+        let labels = [
+            r#"{"type":"addr","ref":"t1first","label":"Savings \"main\""}"#,
+            "",
+            r#"{ "type": "addr", "ref": "u1second", "label": "Café", "origin": null }"#,
+            &format!(r#"{{"type":"tx","ref":"{}","label":"Rent"}}"#, TXID),
+            &format!(
+                r#"{{"type":"output","ref":"{}:0","label":"Change","spendable":true}}"#,
+                TXID
+            ),
+            r#"{"type":"addr","ref":"t1unknown","label":"Elsewhere"}"#,
+            r#"{"type":"addr","ref":"t1first"}"#,
+        ]
+        .join("\n");
+
+        assert_eq!(apply_labels(&mut zewif, &labels).unwrap(), 3);
+
+        let addresses = zewif.wallets()[0].accounts()[0].addresses();
+        assert_eq!(addresses[0].name(), "Savings \"main\"");
+        assert_eq!(addresses[1].name(), "Café");
+        let txid = TxId::from_hex(TXID).unwrap();
+        assert_eq!(
+            zewif.load_transaction(txid).unwrap().unwrap().label(),
+            Some("Rent")
+        );
+    }
+
+    #[test]
+    fn test_apply_labels_rejects_malformed_records() {
+        let mut zewif = zewif();
+        let labels = [
+            r#"{"type":"addr","ref":"t1first","label":"Savings"}"#,
+            r#"{"type":"tx","ref":"not a txid","label":"Rent"}"#,
+        ]
+        .join("\n");
+        let error = apply_labels(&mut zewif, &labels).unwrap_err();
+        assert!(format!("{:#}", error).contains("line 2"));
+        // Nothing is applied when any record is malformed.
+        assert_eq!(zewif.wallets()[0].accounts()[0].addresses()[0].name(), "");
+
+        assert!(apply_labels(&mut zewif, r#"{"ref":"t1first","label":"x"}"#).is_err());
+        assert!(apply_labels(&mut zewif, r#"{"type":"addr","ref":"t1first","label":"x""#).is_err());
+        assert!(apply_labels(&mut zewif, r#"["addr"]"#).is_err());
+        assert!(
+            apply_labels(&mut zewif, r#"{"type":"addr","ref":"t1first","label":NaN}"#).is_err()
+        );
+        assert!(apply_labels(&mut zewif, "{\"type\":\"addr\",\"ref\":\"t1\tfirst\"}").is_err());
+    }
+
+    #[test]
+    fn test_apply_labels_undecodable_transaction_applies_nothing() {
+        let mut zewif = zewif();
+        let txid = TxId::from_hex(TXID).unwrap();
+        let broken = Envelope::from(Transaction::new(txid)).add_assertion("mined_height", "high");
+        zewif.add_lazy_transaction(LazyTransaction::new(broken).unwrap());
+
+        let labels = [
+            r#"{"type":"addr","ref":"t1first","label":"Savings"}"#,
+            &format!(r#"{{"type":"tx","ref":"{}","label":"Rent"}}"#, TXID),
+        ]
+        .join("\n");
+        assert!(apply_labels(&mut zewif, &labels).is_err());
+        assert_eq!(zewif.wallets()[0].accounts()[0].addresses()[0].name(), "");
+    }
+
+    #[test]
+    fn test_import_into_in_stages() {
+        let mut zewif = zewif();
+        let txid = TxId::from_hex(TXID).unwrap();
+
+        // The transparent labels are imported first.
+        let transparent = [
+            r#"{"type":"addr","ref":"t1first","label":"Savings"}"#,
+            r#"{"type":"addr","ref":"t1unknown","label":"Elsewhere"}"#,
+        ]
+        .join("\n");
+        let warnings = import_into(&mut zewif, &transparent).unwrap();
+        assert_eq!(warnings.unapplied_records, 1);
+        assert!(!warnings.has_conflicts());
+
+        // The shielded labels follow, repeating one label and contradicting
+        // another.
+        let shielded = [
+            r#"{"type":"addr","ref":"u1second","label":"Café"}"#,
+            &format!(r#"{{"type":"tx","ref":"{}","label":"Rent"}}"#, TXID),
+            r#"{"type":"addr","ref":"t1first","label":"Savings"}"#,
+            r#"{"type":"addr","ref":"t1first","label":"Spending"}"#,
+            &format!(r#"{{"type":"tx","ref":"{}","label":"Groceries"}}"#, TXID),
+            &format!(r#"{{"type":"output","ref":"{}:0","label":"Change"}}"#, TXID),
+        ]
+        .join("\n");
+        let warnings = import_into(&mut zewif, &shielded).unwrap();
+        assert_eq!(warnings.conflicting_addresses, ["t1first"]);
+        assert_eq!(warnings.conflicting_transactions, [txid]);
+        assert_eq!(warnings.unapplied_records, 1);
+
+        let addresses = zewif.wallets()[0].accounts()[0].addresses();
+        assert_eq!(addresses[0].name(), "Savings");
+        assert_eq!(addresses[1].name(), "Café");
+        assert_eq!(
+            zewif.load_transaction(txid).unwrap().unwrap().label(),
+            Some("Rent")
+        );
+    }
+}
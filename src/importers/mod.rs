@@ -0,0 +1,48 @@
+//! # Importers
+//!
+//! Readers for interchange formats defined outside ZeWIF that carry data a
+//! migrated wallet should keep, applied on top of an existing [`Zewif`](crate::Zewif)
+//! container. The importers read JSON, and are available with the `serde`
+//! feature.
+//!
+//! ## Key Components
+//!
+//! - [`bip329`]: Wallet labels in the BIP-329 JSON Lines format
+//! - [`ImportWarnings`]: What an import into an existing container left unapplied
+
+pub mod bip329;
+
+use crate::TxId;
+
+/// What an importer did not apply when adding data to an existing
+/// [`Zewif`](crate::Zewif) container.
+///
+/// Like [`Zewif::merge`](crate::Zewif::merge), an import keeps the data the
+/// container already holds. Items the imported data records differently are
+/// listed here so the caller can decide which record is right.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ImportWarnings {
+    /// The addresses, in input order, whose held name differs from the
+    /// imported one.
+    pub conflicting_addresses: Vec<String>,
+
+    /// The txids, in input order, whose held label differs from the imported
+    /// one.
+    pub conflicting_transactions: Vec<TxId>,
+
+    /// The number of imported records that matched nothing in the container
+    /// or that carry data ZeWIF does not model.
+    pub unapplied_records: usize,
+}
+
+impl ImportWarnings {
+    /// Returns `true` if any imported item conflicted with one already held.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicting_addresses.is_empty() || !self.conflicting_transactions.is_empty()
+    }
+
+    /// Returns `true` if the import applied everything it read.
+    pub fn is_empty(&self) -> bool {
+        !self.has_conflicts() && self.unapplied_records == 0
+    }
+}
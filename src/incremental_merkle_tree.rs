@@ -1,9 +1,9 @@
-use anyhow::{Result, Context};
+use anyhow::{Context, Result, bail};
 use bc_envelope::prelude::*;
 
 use crate::test_envelope_roundtrip;
 
-use super::u256;
+use super::{MerkleHasher, PathFiller, u256};
 
 use super::{parse, parser::prelude::*};
 
@@ -159,6 +159,124 @@ impl IncrementalMerkleTree {
     pub fn push_parent(&mut self, parent: Option<u256>) {
         self.parents.push(parent);
     }
+
+    /// Returns the number of leaves appended to the tree.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{IncrementalMerkleTree, u256};
+    /// let tree = IncrementalMerkleTree::with_fields(
+    ///     Some(u256::default()),
+    ///     None,
+    ///     vec![None, Some(u256::default())],
+    /// );
+    /// assert_eq!(tree.size(), 5);
+    /// ```
+    pub fn size(&self) -> u64 {
+        let leaves = self.left.is_some() as u64 + self.right.is_some() as u64;
+        self.parents
+            .iter()
+            .enumerate()
+            .filter(|(_, parent)| parent.is_some())
+            .fold(leaves, |size, (level, _)| size + (1 << (level + 1)))
+    }
+
+    /// Returns the root of the tree when it is completed to `depth` levels
+    /// with empty subtrees, as hashed by `H`.
+    ///
+    /// This is the anchor a spend refers to when the tree holds the note
+    /// commitments of a given block.
+    pub fn root<H: MerkleHasher>(&self, depth: usize) -> u256 {
+        self.root_with_filler(depth, &mut PathFiller::<H>::empty())
+    }
+
+    /// Returns the root at `depth`, taking the nodes missing from the tree from
+    /// `filler`.
+    pub(crate) fn root_with_filler<H: MerkleHasher>(
+        &self,
+        depth: usize,
+        filler: &mut PathFiller<H>,
+    ) -> u256 {
+        let left = self.left.unwrap_or_else(|| filler.next(0));
+        let right = self.right.unwrap_or_else(|| filler.next(0));
+        let leaf_root = H::combine(0, &left, &right);
+        let mid_root = self
+            .parents
+            .iter()
+            .enumerate()
+            .fold(leaf_root, |root, (i, parent)| match parent {
+                Some(node) => H::combine(i + 1, node, &root),
+                None => H::combine(i + 1, &root, &filler.next(i + 1)),
+            });
+        ((self.parents.len() + 1)..depth).fold(mid_root, |root, level| {
+            H::combine(level, &root, &filler.next(level))
+        })
+    }
+
+    /// Returns `true` if every leaf of a tree of `depth` levels is filled.
+    pub(crate) fn is_complete(&self, depth: usize) -> bool {
+        self.left.is_some()
+            && self.right.is_some()
+            && self.parents.len() + 1 == depth
+            && self.parents.iter().all(Option::is_some)
+    }
+
+    /// Appends `leaf` to a tree of `depth` levels, hashing with `H`, as
+    /// zcashd's `IncrementalMerkleTree::append` does.
+    ///
+    /// # Errors
+    /// Returns an error if the tree is already complete.
+    pub(crate) fn append<H: MerkleHasher>(&mut self, depth: usize, leaf: u256) -> Result<()> {
+        if self.is_complete(depth) {
+            bail!("Merkle tree of depth {} is full", depth);
+        }
+        let (Some(left), Some(right)) = (self.left, self.right) else {
+            if self.left.is_none() {
+                self.left = Some(leaf);
+            } else {
+                self.right = Some(leaf);
+            }
+            return Ok(());
+        };
+        let mut combined = H::combine(0, &left, &right);
+        self.left = Some(leaf);
+        self.right = None;
+        for (i, parent) in self.parents.iter_mut().enumerate() {
+            match parent.take() {
+                Some(node) => combined = H::combine(i + 1, &node, &combined),
+                None => {
+                    *parent = Some(combined);
+                    return Ok(());
+                }
+            }
+        }
+        self.parents.push(Some(combined));
+        Ok(())
+    }
+
+    /// Returns the level of the first unfilled subtree to the right of the
+    /// tree's last leaf, after skipping `skip` of them.
+    ///
+    /// A witness fills these subtrees in order as later leaves are appended, so
+    /// this is the depth of the subtree its cursor is building.
+    pub(crate) fn next_depth(&self, skip: usize) -> usize {
+        let mut skip = skip;
+        if self.right.is_none() {
+            if skip == 0 {
+                return 0;
+            }
+            skip -= 1;
+        }
+        for (i, parent) in self.parents.iter().enumerate() {
+            if parent.is_none() {
+                if skip == 0 {
+                    return i + 1;
+                }
+                skip -= 1;
+            }
+        }
+        self.parents.len() + 1 + skip
+    }
 }
 
 /// Default implementation creates an empty incremental Merkle tree
@@ -1,6 +1,6 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 
-use super::IncrementalMerkleTree;
+use super::{IncrementalMerkleTree, MerkleHasher, MerklePath, PathFiller, u256};
 use super::{parse, parser::prelude::*};
 
 /// An authentication path generator for a specific note in a Merkle tree.
@@ -119,6 +119,209 @@ impl<const DEPTH: usize, Hash> IncrementalWitness<DEPTH, Hash> {
     }
 }
 
+impl<const DEPTH: usize> IncrementalWitness<DEPTH, u256> {
+    /// Returns the position of the witnessed leaf, or `None` if the witness's
+    /// tree is empty.
+    pub fn position(&self) -> Option<u64> {
+        self.tree.size().checked_sub(1)
+    }
+
+    /// Returns the authentication path of the witnessed leaf, hashing with
+    /// `H` where the path needs nodes that the witness does not store.
+    ///
+    /// This follows the algorithm zcashd uses to turn a witness into a path.
+    /// The witnessed leaf is the last leaf of [`tree`](Self::tree). Its
+    /// siblings come from the tree where it already holds them, and otherwise,
+    /// in order, from the [`filled`](Self::filled) nodes, then from the root of
+    /// the partially built [`cursor`](Self::cursor) subtree, then from the roots
+    /// of empty subtrees. The witness stores no cursor depth, so it is derived
+    /// from the tree and the number of filled nodes, as zcashd does when
+    /// reading a serialized witness.
+    ///
+    /// Returns `None` if the witness is incomplete, because its tree holds no
+    /// leaf, or if the tree is deeper than `DEPTH`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{IncrementalMerkleTree, MerkleHasher, SproutHasher, SproutWitness, u256};
+    /// // A witness for the only leaf of a Sprout tree.
+    /// let leaf = u256::try_from(&[1u8; 32]).unwrap();
+    /// let tree = IncrementalMerkleTree::with_fields(Some(leaf), None, Vec::new());
+    /// let witness = SproutWitness::with_fields(tree.clone(), Vec::new(), None);
+    ///
+    /// let path = witness.path::<SproutHasher>().unwrap();
+    /// assert_eq!(path.position(), 0);
+    /// assert_eq!(path.auth_path()[0], (SproutHasher::empty_root(0), false));
+    /// assert_eq!(path.root::<SproutHasher>(leaf), tree.root::<SproutHasher>(29));
+    ///
+    /// let empty = SproutWitness::with_fields(IncrementalMerkleTree::new(), Vec::new(), None);
+    /// assert!(empty.path::<SproutHasher>().is_none());
+    /// ```
+    pub fn path<H: MerkleHasher>(&self) -> Option<MerklePath<DEPTH>> {
+        let left = self.tree.left()?;
+        let parents = self.tree.parents();
+        if parents.len() >= DEPTH {
+            return None;
+        }
+
+        let mut filler = self.filler::<H>();
+        let mut auth_path = Vec::with_capacity(DEPTH);
+        auth_path.push(if self.tree.right().is_some() {
+            (left, true)
+        } else {
+            (filler.next(0), false)
+        });
+        for (i, parent) in parents.iter().enumerate() {
+            auth_path.push(match parent {
+                Some(node) => (*node, true),
+                None => (filler.next(i + 1), false),
+            });
+        }
+        for level in (parents.len() + 1)..DEPTH {
+            auth_path.push((filler.next(level), false));
+        }
+        MerklePath::from_path(auth_path, self.tree.size() - 1)
+    }
+
+    /// Returns the root of the tree that the witness currently authenticates
+    /// its leaf against, hashing with `H`.
+    ///
+    /// The root is the witness's [`tree`](Self::tree) completed to `DEPTH`
+    /// levels with the [`filled`](Self::filled) nodes, the root of the
+    /// [`cursor`](Self::cursor), and then empty subtrees, in the same order
+    /// that [`path`](Self::path) uses. A witness is only useful for a spend if
+    /// this root is the spend's anchor.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{IncrementalMerkleTree, SproutHasher, SproutWitness};
+    /// let witness = SproutWitness::with_fields(IncrementalMerkleTree::new(), Vec::new(), None);
+    ///
+    /// // The Sprout anchor of an empty tree, as zcashd displays it.
+    /// assert_eq!(
+    ///     witness.root::<SproutHasher>().to_string(),
+    ///     "59d2cde5e65c1414c32ba54f0fe4bdb3d67618125286e6a191317917c812c6d7"
+    /// );
+    /// ```
+    pub fn root<H: MerkleHasher>(&self) -> u256 {
+        self.tree.root_with_filler(DEPTH, &mut self.filler::<H>())
+    }
+
+    /// Updates the witness for a leaf appended to the tree after the
+    /// witnessed leaf, hashing with `H`, as zcashd's
+    /// `IncrementalWitness::append` does.
+    ///
+    /// # Errors
+    /// Returns an error if the tree already holds `2^DEPTH` leaves.
+    pub fn append<H: MerkleHasher>(&mut self, commitment: u256) -> Result<()> {
+        let cursor_depth = self.tree.next_depth(self.filled.len());
+        match self.cursor.as_mut() {
+            Some(cursor) => {
+                cursor.append::<H>(cursor_depth, commitment)?;
+                if cursor.is_complete(cursor_depth) {
+                    let root = cursor.root::<H>(cursor_depth);
+                    self.filled.push(root);
+                    self.cursor = None;
+                }
+            }
+            None => {
+                if cursor_depth >= DEPTH {
+                    bail!("Merkle tree of depth {} is full", DEPTH);
+                }
+                if cursor_depth == 0 {
+                    self.filled.push(commitment);
+                } else {
+                    self.cursor = Some(IncrementalMerkleTree::with_fields(
+                        Some(commitment),
+                        None,
+                        Vec::new(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Updates the witness for a block of leaves appended to the tree after
+    /// the witnessed leaf, hashing with `H`.
+    ///
+    /// The result is the same as calling [`append`](Self::append) for each
+    /// commitment in order, but whenever the block covers a whole subtree
+    /// that the witness needs, that subtree's root is hashed straight from
+    /// the commitments instead of being built up in the cursor one leaf at a
+    /// time.
+    ///
+    /// # Errors
+    /// Returns an error, leaving the witness unchanged, if the commitments
+    /// do not fit in a tree of `DEPTH` levels.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{IncrementalMerkleTree, SproutHasher, SproutWitness, u256};
+    /// let leaf = |n: u8| u256::try_from(&[n; 32]).unwrap();
+    /// let tree = IncrementalMerkleTree::with_fields(Some(leaf(0)), None, Vec::new());
+    /// let mut one_by_one = SproutWitness::with_fields(tree.clone(), Vec::new(), None);
+    /// let mut batched = SproutWitness::with_fields(tree, Vec::new(), None);
+    ///
+    /// let block: Vec<u256> = (1..=10).map(leaf).collect();
+    /// for commitment in &block {
+    ///     one_by_one.append::<SproutHasher>(*commitment).unwrap();
+    /// }
+    /// batched.append_block::<SproutHasher>(&block).unwrap();
+    /// assert_eq!(batched.root::<SproutHasher>(), one_by_one.root::<SproutHasher>());
+    /// ```
+    pub fn append_block<H: MerkleHasher>(&mut self, commitments: &[u256]) -> Result<()> {
+        let mut witness = self.clone();
+        let mut rest = commitments;
+        while let Some((&first, tail)) = rest.split_first() {
+            if witness.cursor.is_some() {
+                witness.append::<H>(first)?;
+                rest = tail;
+                continue;
+            }
+            let cursor_depth = witness.tree.next_depth(witness.filled.len());
+            if cursor_depth >= DEPTH {
+                bail!("Merkle tree of depth {} is full", DEPTH);
+            }
+            let width = 1usize << cursor_depth;
+            if rest.len() < width {
+                for &commitment in rest {
+                    witness.append::<H>(commitment)?;
+                }
+                break;
+            }
+            witness.filled.push(subtree_root::<H>(&rest[..width]));
+            rest = &rest[width..];
+        }
+        *self = witness;
+        Ok(())
+    }
+
+    fn filler<H: MerkleHasher>(&self) -> PathFiller<H> {
+        let cursor_depth = self.tree.next_depth(self.filled.len());
+        let cursor_root = self
+            .cursor
+            .as_ref()
+            .map(|cursor| cursor.root::<H>(cursor_depth));
+        PathFiller::new(self.filled.iter().copied().chain(cursor_root).collect())
+    }
+}
+
+/// Returns the root of the complete subtree whose leaves are `leaves`, a
+/// power-of-two number of them.
+fn subtree_root<H: MerkleHasher>(leaves: &[u256]) -> u256 {
+    let mut level = leaves.to_vec();
+    let mut depth = 0;
+    while level.len() > 1 {
+        level = level
+            .chunks_exact(2)
+            .map(|pair| H::combine(depth, &pair[0], &pair[1]))
+            .collect();
+        depth += 1;
+    }
+    level[0]
+}
+
 /// Implementation of the Parse trait for binary deserialization
 impl<const DEPTH: usize, Hash: Parse> Parse for IncrementalWitness<DEPTH, Hash> {
     fn parse(p: &mut Parser) -> Result<Self> {
@@ -1,9 +1,26 @@
-use anyhow::Result;
+use anyhow::{Result, bail};
 use bc_envelope::prelude::*;
 
 pub trait Indexed {
     fn index(&self) -> usize;
     fn set_index(&mut self, index: usize);
+
+    /// Returns `true` if `self` and `other` are equal apart from their indexes.
+    ///
+    /// The index only records an item's position in its containing collection,
+    /// so two items at different positions compare unequal with `==` even when
+    /// they carry the same data. Deduplication and merge logic should compare
+    /// with `content_eq` instead. Only the index of the item itself is ignored;
+    /// the indexes of any items it contains are still compared, since they
+    /// record the order of its contents.
+    fn content_eq(&self, other: &Self) -> bool
+    where
+        Self: Clone + PartialEq,
+    {
+        let mut this = self.clone();
+        this.set_index(other.index());
+        this == *other
+    }
 }
 
 pub fn set_indexes<T: Indexed>(mut vec: Vec<T>) -> Vec<T> {
@@ -46,3 +63,99 @@ where
     vec.sort_by_key(|input| input.index());
     Ok(vec)
 }
+
+/// Decodes the objects for `predicate` as an indexed collection, returning them
+/// in index order.
+///
+/// Unlike [`envelope_indexed_objects_for_predicate`], which accepts whatever
+/// indexes it finds so that hand-edited containers can still be read and then
+/// repaired with `reindex_all`, this requires the indexes to be exactly
+/// `0..n`: each index must be unique and there must be no gaps.
+///
+/// # Errors
+/// Returns an error if an object fails to decode, if two objects share an
+/// index, or if an index is missing.
+pub fn decode_indexed_collection<T>(envelope: &Envelope, predicate: impl AsRef<str>) -> Result<Vec<T>>
+where
+    T: Indexed + TryFrom<Envelope, Error = anyhow::Error> + 'static,
+{
+    let predicate = predicate.as_ref();
+    let vec: Vec<T> = envelope_indexed_objects_for_predicate(envelope, predicate)?;
+    for (expected, item) in vec.iter().enumerate() {
+        match item.index() {
+            index if index == expected => {}
+            index if index + 1 == expected => {
+                bail!("Duplicate index {} in \"{}\" collection", index, predicate)
+            }
+            _ => bail!("Missing index {} in \"{}\" collection", expected, predicate),
+        }
+    }
+    Ok(vec)
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::{Indexed, decode_indexed_collection};
+    use crate::{Address, ProtocolAddress, transparent};
+
+    fn envelope_with_indexes(indexes: &[usize]) -> Envelope {
+        let mut envelope = Envelope::new("collection");
+        for index in indexes {
+            // Distinct contents, so equal indexes don't collapse into one assertion.
+            let name = format!("t1address{}", envelope.assertions().len());
+            let mut address = Address::new(ProtocolAddress::Transparent(
+                transparent::Address::new(name),
+            ));
+            address.set_index(*index);
+            envelope = envelope.add_assertion("address", address);
+        }
+        envelope
+    }
+
+    #[test]
+    fn test_content_eq_ignores_index() {
+        let mut first = Address::new(ProtocolAddress::Transparent(transparent::Address::new(
+            "t1address",
+        )));
+        first.set_name("Savings".to_string());
+        let mut second = first.clone();
+        second.set_index(3);
+
+        assert_ne!(first, second);
+        assert!(first.content_eq(&second));
+        assert!(second.content_eq(&first));
+
+        second.set_name("Spending".to_string());
+        assert!(!first.content_eq(&second));
+    }
+
+    #[test]
+    fn test_decode_indexed_collection() {
+        let addresses: Vec<Address> =
+            decode_indexed_collection(&envelope_with_indexes(&[2, 0, 1]), "address").unwrap();
+        let indexes: Vec<usize> = addresses.iter().map(Indexed::index).collect();
+        assert_eq!(indexes, [0, 1, 2]);
+        assert_eq!(addresses[2].as_string(), "t1address0");
+
+        let empty: Vec<Address> =
+            decode_indexed_collection(&envelope_with_indexes(&[]), "address").unwrap();
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    fn test_decode_indexed_collection_rejects_bad_indexes() {
+        let error = |indexes: &[usize]| {
+            decode_indexed_collection::<Address>(&envelope_with_indexes(indexes), "address")
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error(&[0, 1, 1]),
+            "Duplicate index 1 in \"address\" collection"
+        );
+        assert_eq!(error(&[0, 2]), "Missing index 1 in \"address\" collection");
+        assert_eq!(error(&[1]), "Missing index 0 in \"address\" collection");
+    }
+}
@@ -0,0 +1,20 @@
+//! # Viewing Keys
+//!
+//! Keys that let a wallet detect and decrypt its transactions without being able
+//! to spend. A migrated watch-only wallet, or a light client resuming sync after
+//! migration, needs these for each account.
+//!
+//! ## Key Components
+//!
+//! - [`UnifiedFullViewingKey`]: The per-pool full viewing keys of an account, as bundled
+//!   by a ZIP 316 Unified Full Viewing Key
+//! - [`TransparentAccountPubKey`]: The BIP 44 account-level extended public key
+//! - [`SaplingDiversifiableFullViewingKey`]: A Sapling full viewing key with its diversifier key
+//! - [`OrchardFullViewingKey`]: An Orchard full viewing key
+
+use crate::mod_use;
+
+mod_use!(orchard_full_viewing_key);
+mod_use!(sapling_diversifiable_full_viewing_key);
+mod_use!(transparent_account_pub_key);
+mod_use!(unified_full_viewing_key);
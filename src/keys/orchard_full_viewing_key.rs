@@ -0,0 +1,14 @@
+use crate::{blob, blob_envelope};
+
+// An Orchard full viewing key: the spend validating key `ak`, the nullifier
+// deriving key `nk` and the commitment randomness key `rivk`.
+//
+// This key is encoded as the Orchard FVK item of a Unified Full Viewing Key, as
+// defined in https://zips.z.cash/zip-0316
+blob!(
+    OrchardFullViewingKey,
+    96,
+    "An Orchard Full Viewing Key, encoded as specified in ZIP 316"
+);
+
+blob_envelope!(OrchardFullViewingKey);
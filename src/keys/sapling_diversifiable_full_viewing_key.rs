@@ -0,0 +1,14 @@
+use crate::{blob, blob_envelope};
+
+// A Sapling full viewing key (`ak`, `nk`, `ovk`) together with the diversifier
+// key `dk` used to derive the account's diversified addresses.
+//
+// This key is encoded as the Sapling FVK item of a Unified Full Viewing Key, as
+// defined in https://zips.z.cash/zip-0316
+blob!(
+    SaplingDiversifiableFullViewingKey,
+    128,
+    "A Sapling Diversifiable Full Viewing Key, encoded as specified in ZIP 316"
+);
+
+blob_envelope!(SaplingDiversifiableFullViewingKey);
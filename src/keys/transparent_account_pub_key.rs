@@ -0,0 +1,15 @@
+use crate::{blob, blob_envelope};
+
+// The BIP 44 account-level extended public key from which an account's
+// transparent receiving and change addresses are derived: the 32-byte chain
+// code followed by the 33-byte compressed public key.
+//
+// This key is encoded as the P2PKH item of a Unified Full Viewing Key, as
+// defined in https://zips.z.cash/zip-0316
+blob!(
+    TransparentAccountPubKey,
+    65,
+    "A transparent account-level extended public key, encoded as specified in ZIP 316"
+);
+
+blob_envelope!(TransparentAccountPubKey);
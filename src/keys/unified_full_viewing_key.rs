@@ -0,0 +1,177 @@
+use bc_envelope::prelude::*;
+
+use super::{OrchardFullViewingKey, SaplingDiversifiableFullViewingKey, TransparentAccountPubKey};
+use crate::{Blob, test_cbor_roundtrip, test_envelope_roundtrip};
+
+/// The full viewing keys of one account, one per shielded or transparent pool.
+///
+/// `UnifiedFullViewingKey` bundles the keys a watch-only wallet or light client
+/// needs to scan the chain for an account's transactions and to see their
+/// contents, without the ability to spend.
+///
+/// # Zcash Concept Relation
+/// [ZIP 316](https://zips.z.cash/zip-0316) defines Unified Full Viewing Keys
+/// (UFVKs) that combine a transparent account public key, a Sapling full viewing
+/// key and an Orchard full viewing key into a single string that wallets share
+/// with sync services. Any of the components may be absent, but at least one
+/// must be present.
+///
+/// # Data Preservation
+/// Each component is kept in the raw byte form of its ZIP 316 item, so the keys
+/// survive migration exactly and the encoded UFVK can be reconstructed.
+///
+/// # Examples
+/// ```
+/// # use zewif::keys::{OrchardFullViewingKey, UnifiedFullViewingKey};
+/// let mut ufvk = UnifiedFullViewingKey::new();
+/// ufvk.set_orchard(OrchardFullViewingKey::new([0u8; 96]));
+/// assert!(ufvk.orchard().is_some());
+/// assert!(ufvk.sapling().is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UnifiedFullViewingKey {
+    transparent: Option<TransparentAccountPubKey>,
+    sapling: Option<SaplingDiversifiableFullViewingKey>,
+    orchard: Option<OrchardFullViewingKey>,
+}
+
+impl UnifiedFullViewingKey {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn transparent(&self) -> Option<&TransparentAccountPubKey> {
+        self.transparent.as_ref()
+    }
+
+    pub fn set_transparent(&mut self, key: TransparentAccountPubKey) {
+        self.transparent = Some(key);
+    }
+
+    pub fn sapling(&self) -> Option<&SaplingDiversifiableFullViewingKey> {
+        self.sapling.as_ref()
+    }
+
+    pub fn set_sapling(&mut self, key: SaplingDiversifiableFullViewingKey) {
+        self.sapling = Some(key);
+    }
+
+    pub fn orchard(&self) -> Option<&OrchardFullViewingKey> {
+        self.orchard.as_ref()
+    }
+
+    pub fn set_orchard(&mut self, key: OrchardFullViewingKey) {
+        self.orchard = Some(key);
+    }
+
+    /// Returns `true` if no component key is present.
+    pub fn is_empty(&self) -> bool {
+        self.transparent.is_none() && self.sapling.is_none() && self.orchard.is_none()
+    }
+}
+
+fn optional_blob<const N: usize>(map: &Map, key: &str) -> dcbor::Result<Option<Blob<N>>> {
+    map.get::<_, CBOR>(key).map(Blob::<N>::try_from).transpose()
+}
+
+impl From<UnifiedFullViewingKey> for CBOR {
+    fn from(value: UnifiedFullViewingKey) -> Self {
+        let mut map = Map::new();
+        if let Some(key) = value.transparent {
+            map.insert("transparent", Blob::from(key));
+        }
+        if let Some(key) = value.sapling {
+            map.insert("sapling", Blob::from(key));
+        }
+        if let Some(key) = value.orchard {
+            map.insert("orchard", Blob::from(key));
+        }
+        map.into()
+    }
+}
+
+impl TryFrom<CBOR> for UnifiedFullViewingKey {
+    type Error = dcbor::Error;
+
+    fn try_from(value: CBOR) -> dcbor::Result<Self> {
+        if let CBORCase::Map(map) = value.into_case() {
+            let ufvk = UnifiedFullViewingKey {
+                transparent: optional_blob::<65>(&map, "transparent")?
+                    .map(|blob| TransparentAccountPubKey::new(blob.into())),
+                sapling: optional_blob::<128>(&map, "sapling")?
+                    .map(|blob| SaplingDiversifiableFullViewingKey::new(blob.into())),
+                orchard: optional_blob::<96>(&map, "orchard")?
+                    .map(|blob| OrchardFullViewingKey::new(blob.into())),
+            };
+            if ufvk.is_empty() {
+                return Err("UnifiedFullViewingKey must contain at least one key".into());
+            }
+            Ok(ufvk)
+        } else {
+            Err("Expected a CBOR map".into())
+        }
+    }
+}
+
+impl From<UnifiedFullViewingKey> for Envelope {
+    fn from(value: UnifiedFullViewingKey) -> Self {
+        Envelope::new(CBOR::from(value)).add_type("UnifiedFullViewingKey")
+    }
+}
+
+impl TryFrom<Envelope> for UnifiedFullViewingKey {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        envelope.check_type_envelope("UnifiedFullViewingKey")?;
+        envelope.extract_subject()
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for UnifiedFullViewingKey {
+    fn random() -> Self {
+        let mut ufvk = Self {
+            transparent: TransparentAccountPubKey::opt_random(),
+            sapling: SaplingDiversifiableFullViewingKey::opt_random(),
+            orchard: OrchardFullViewingKey::opt_random(),
+        };
+        if ufvk.is_empty() {
+            ufvk.orchard = Some(OrchardFullViewingKey::random());
+        }
+        ufvk
+    }
+}
+
+test_cbor_roundtrip!(UnifiedFullViewingKey);
+test_envelope_roundtrip!(UnifiedFullViewingKey);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::UnifiedFullViewingKey;
+    use crate::keys::{
+        OrchardFullViewingKey, SaplingDiversifiableFullViewingKey, TransparentAccountPubKey,
+    };
+
+    #[test]
+    fn test_three_pool_ufvk_roundtrip() {
+        let mut ufvk = UnifiedFullViewingKey::new();
+        ufvk.set_transparent(TransparentAccountPubKey::new([1u8; 65]));
+        ufvk.set_sapling(SaplingDiversifiableFullViewingKey::new([2u8; 128]));
+        ufvk.set_orchard(OrchardFullViewingKey::new([3u8; 96]));
+
+        let decoded = UnifiedFullViewingKey::try_from(Envelope::from(ufvk.clone())).unwrap();
+        assert_eq!(decoded, ufvk);
+        assert_eq!(decoded.transparent().unwrap().to_vec(), vec![1u8; 65]);
+        assert_eq!(decoded.sapling().unwrap().to_vec(), vec![2u8; 128]);
+        assert_eq!(decoded.orchard().unwrap().to_vec(), vec![3u8; 96]);
+    }
+
+    #[test]
+    fn test_empty_ufvk_rejected() {
+        let envelope = Envelope::from(UnifiedFullViewingKey::new());
+        assert!(UnifiedFullViewingKey::try_from(envelope).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+use anyhow::{Context, Result};
+use bc_envelope::prelude::*;
+use std::sync::OnceLock;
+
+use crate::{Transaction, TxId};
+
+#[cfg(test)]
+thread_local! {
+    static DECODE_COUNT: std::cell::Cell<usize> = const { std::cell::Cell::new(0) };
+}
+
+/// A transaction held in its envelope encoding and decoded on first access.
+///
+/// Loading a large `Zewif` with
+/// [`Zewif::try_from_envelope_lazy`](crate::Zewif::try_from_envelope_lazy) keeps
+/// each transaction as a `LazyTransaction`, so only the transactions that are
+/// actually inspected pay the cost of decoding. The decoded value is memoized,
+/// and re-encoding a `LazyTransaction` reuses the original envelope without
+/// decoding it.
+///
+/// Two lazy transactions are equal when their envelopes have the same digest,
+/// regardless of whether either has been decoded.
+#[derive(Clone)]
+pub struct LazyTransaction {
+    txid: TxId,
+    envelope: Envelope,
+    decoded: OnceLock<Transaction>,
+}
+
+impl LazyTransaction {
+    /// Wraps a `Transaction` envelope, reading only its txid.
+    pub fn new(envelope: Envelope) -> Result<Self> {
+        envelope.check_type_envelope("Transaction")?;
+        let txid = envelope.extract_subject().context("txid")?;
+        Ok(Self {
+            txid,
+            envelope,
+            decoded: OnceLock::new(),
+        })
+    }
+
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    pub fn envelope(&self) -> &Envelope {
+        &self.envelope
+    }
+
+    /// Returns `true` if the transaction has already been decoded.
+    pub fn is_decoded(&self) -> bool {
+        self.decoded.get().is_some()
+    }
+
+    /// Returns the decoded transaction, decoding it on first access.
+    pub fn get(&self) -> Result<&Transaction> {
+        if let Some(transaction) = self.decoded.get() {
+            return Ok(transaction);
+        }
+        #[cfg(test)]
+        DECODE_COUNT.with(|count| count.set(count.get() + 1));
+        let transaction = Transaction::try_from(self.envelope.clone())
+            .with_context(|| format!("transaction {}", self.txid))?;
+        Ok(self.decoded.get_or_init(|| transaction))
+    }
+
+    /// Consumes the wrapper, returning the decoded transaction.
+    pub fn into_transaction(self) -> Result<Transaction> {
+        match self.decoded.into_inner() {
+            Some(transaction) => Ok(transaction),
+            None => Transaction::try_from(self.envelope),
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn decode_count() -> usize {
+        DECODE_COUNT.with(|count| count.get())
+    }
+}
+
+impl std::fmt::Debug for LazyTransaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LazyTransaction")
+            .field("txid", &self.txid)
+            .field("decoded", &self.decoded.get())
+            .finish()
+    }
+}
+
+impl PartialEq for LazyTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        self.envelope.digest() == other.envelope.digest()
+    }
+}
+
+impl From<Transaction> for LazyTransaction {
+    fn from(transaction: Transaction) -> Self {
+        Self {
+            txid: transaction.txid(),
+            envelope: Envelope::from(transaction.clone()),
+            decoded: OnceLock::from(transaction),
+        }
+    }
+}
+
+impl From<LazyTransaction> for Envelope {
+    fn from(value: LazyTransaction) -> Self {
+        value.envelope
+    }
+}
+
+impl TryFrom<Envelope> for LazyTransaction {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        Self::new(envelope)
+    }
+}
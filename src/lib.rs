@@ -89,31 +89,48 @@ mod test_roundtrip_macros;
 #[cfg(test)]
 // Test utilities
 mod_use!(test_utils);
+#[cfg(test)]
+mod byte_order_tests;
 
 // Modules requiring qualified paths
+#[cfg(feature = "serde")]
+pub mod exporters;
+#[cfg(feature = "serde")]
+pub mod importers;
+pub mod keys;
 pub mod parser;
 pub mod sapling;
 pub mod transparent;
+pub mod unified;
 
 // Modules that can use unqualified paths
 mod_use!(account);
 mod_use!(address);
+mod_use!(address_sort_key);
 mod_use!(amount);
 mod_use!(anchor);
 mod_use!(bip_39_mnemonic);
 mod_use!(blob);
 mod_use!(block_hash);
 mod_use!(block_height);
+mod_use!(bloom_filter);
 mod_use!(branch_id);
 mod_use!(compact_size);
+mod_use!(compressed_cbor);
 mod_use!(data);
 mod_use!(derivation_info);
 mod_use!(digest_utils);
+mod_use!(display_opts);
+mod_use!(envelope_schema);
 mod_use!(expiry_height);
 mod_use!(incremental_merkle_tree);
 mod_use!(incremental_witness);
 mod_use!(indexed);
 mod_use!(int_id);
+mod_use!(lazy_transaction);
+mod_use!(merge_report);
+mod_use!(memo);
+mod_use!(merkle_path);
 mod_use!(mnemonic_language);
 mod_use!(network);
 mod_use!(non_hardened_child_index);
@@ -123,21 +140,30 @@ mod_use!(phgr_proof);
 mod_use!(position);
 mod_use!(protocol_address);
 mod_use!(receiver_type);
+mod_use!(red_pallas_signature);
 mod_use!(script);
 mod_use!(seconds_since_epoch);
+mod_use!(secret_blob);
 mod_use!(seed);
+mod_use!(seed_fingerprint);
 mod_use!(seed_material);
 mod_use!(sprout_witness);
+mod_use!(stored_transaction);
 mod_use!(string_utils);
+#[cfg(feature = "test-vectors")]
+mod_use!(test_vectors);
 mod_use!(transaction_status);
 mod_use!(transaction);
 mod_use!(tx_block_position);
+mod_use!(tx_header);
 mod_use!(tx_out_point);
 mod_use!(txid);
 mod_use!(u160_type);
 mod_use!(u252_type);
 mod_use!(u256_type);
 mod_use!(unified_address);
+mod_use!(validation_issue);
+mod_use!(wallet_stats);
 mod_use!(zewif_envelope);
 mod_use!(zewif_impl);
 mod_use!(zewif_wallet);
@@ -0,0 +1,114 @@
+use anyhow::{Result, bail};
+
+use crate::{blob, blob_envelope};
+
+// The 512-byte memo field of a shielded note plaintext.
+//
+// ZIP 302 gives the first byte its meaning: a value up to 0xF4 starts a UTF-8
+// text memo padded with zero bytes, and 0xF6 followed by zero bytes means that
+// no memo was attached. Other values are reserved or carry arbitrary data, and
+// are preserved as-is.
+blob!(
+    Memo,
+    512,
+    "A 512-byte shielded note memo, as defined by ZIP 302"
+);
+
+blob_envelope!(Memo);
+
+impl Memo {
+    /// The size of a memo in bytes.
+    pub const SIZE: usize = 512;
+
+    /// Returns the memo that signals that no memo was attached: `0xF6`
+    /// followed by zero bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Memo;
+    /// let memo = Memo::empty();
+    /// assert!(memo.is_empty_memo());
+    /// assert_eq!(memo.as_ref()[0], 0xF6);
+    /// assert_eq!(memo.text(), None);
+    /// ```
+    pub fn empty() -> Self {
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[0] = 0xF6;
+        Self::new(bytes)
+    }
+
+    /// Returns a text memo holding `text`, padded with zero bytes.
+    ///
+    /// # Errors
+    /// Returns an error if `text` is longer than 512 bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Memo;
+    /// let memo = Memo::from_text("Thanks for lunch").unwrap();
+    /// assert_eq!(memo.text(), Some("Thanks for lunch"));
+    /// assert!(!memo.is_empty_memo());
+    ///
+    /// assert!(Memo::from_text(&"x".repeat(513)).is_err());
+    /// ```
+    pub fn from_text(text: &str) -> Result<Self> {
+        if text.len() > Self::SIZE {
+            bail!(
+                "Memo text is {} bytes, more than the {} bytes a memo holds",
+                text.len(),
+                Self::SIZE
+            );
+        }
+        let mut bytes = [0u8; Self::SIZE];
+        bytes[..text.len()].copy_from_slice(text.as_bytes());
+        Ok(Self::new(bytes))
+    }
+
+    /// Returns `true` if this is the memo that signals that no memo was
+    /// attached.
+    pub fn is_empty_memo(&self) -> bool {
+        let bytes = self.as_ref();
+        bytes[0] == 0xF6 && bytes[1..].iter().all(|b| *b == 0)
+    }
+
+    /// Returns the text of a text memo, without its zero padding.
+    ///
+    /// Returns `None` if the memo is not a text memo, or if its text is not
+    /// valid UTF-8.
+    pub fn text(&self) -> Option<&str> {
+        let bytes = self.as_ref();
+        if bytes[0] > 0xF4 {
+            return None;
+        }
+        let end = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+        std::str::from_utf8(&bytes[..end]).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Memo;
+
+    #[test]
+    fn test_empty_memo() {
+        let memo = Memo::empty();
+        assert!(memo.is_empty_memo());
+        assert_eq!(memo.text(), None);
+
+        // A memo of all zero bytes is an empty text memo, not the empty memo.
+        let zeros = Memo::default();
+        assert!(!zeros.is_empty_memo());
+        assert_eq!(zeros.text(), Some(""));
+    }
+
+    #[test]
+    fn test_text_memo() {
+        let text = "ü".repeat(256);
+        let memo = Memo::from_text(&text).unwrap();
+        assert_eq!(memo.text(), Some(text.as_str()));
+
+        let mut bytes = [0u8; Memo::SIZE];
+        bytes[0] = 0xFF;
+        assert_eq!(Memo::new(bytes).text(), None);
+    }
+}
@@ -0,0 +1,49 @@
+use crate::TxId;
+
+/// A summary of what [`Zewif::merge`](crate::Zewif::merge) combined.
+///
+/// A transaction present in both containers with identical contents is
+/// counted once as a duplicate. If its contents differ, the copy already held
+/// is kept and the txid is listed in
+/// [`conflicting_transactions`](Self::conflicting_transactions) so the caller
+/// can decide which record is right.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Network, Zewif, ZewifWallet};
+/// let mut zewif = Zewif::new();
+/// let mut other = Zewif::new();
+/// other.add_wallet(ZewifWallet::new(Network::Main));
+///
+/// let report = zewif.merge(other).unwrap();
+/// assert_eq!(report.wallets_added, 1);
+/// assert!(!report.has_conflicts());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MergeReport {
+    /// The number of wallets appended to the container.
+    pub wallets_added: usize,
+
+    /// The number of merged wallets skipped because an identical wallet was
+    /// already present.
+    pub duplicate_wallets: usize,
+
+    /// The number of transactions added to the container.
+    pub transactions_added: usize,
+
+    /// The number of merged transactions skipped because an identical copy was
+    /// already present.
+    pub duplicate_transactions: usize,
+
+    /// The txids present in both containers with differing contents, in
+    /// ascending order.
+    pub conflicting_transactions: Vec<TxId>,
+}
+
+impl MergeReport {
+    /// Returns `true` if any transaction was recorded differently by the two
+    /// containers.
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicting_transactions.is_empty()
+    }
+}
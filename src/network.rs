@@ -62,6 +62,40 @@ impl From<Network> for NetworkType {
     }
 }
 
+/// Converts to the consensus parameters of the network, which carry its
+/// network upgrade activation heights.
+///
+/// # Errors
+/// Regtest has no fixed consensus parameters, since each regtest node chooses
+/// its own activation heights, so converting [`Network::Regtest`] fails.
+///
+/// # Examples
+/// ```
+/// # use zewif::Network;
+/// use zcash_protocol::consensus::{self, NetworkUpgrade, Parameters};
+/// let params = consensus::Network::try_from(Network::Main).unwrap();
+/// assert!(params.activation_height(NetworkUpgrade::Nu5).is_some());
+/// assert!(consensus::Network::try_from(Network::Regtest).is_err());
+/// ```
+impl TryFrom<Network> for zcash_protocol::consensus::Network {
+    type Error = anyhow::Error;
+
+    fn try_from(value: Network) -> Result<Self> {
+        match value.0 {
+            NetworkType::Main => Ok(zcash_protocol::consensus::Network::MainNetwork),
+            NetworkType::Test => Ok(zcash_protocol::consensus::Network::TestNetwork),
+            NetworkType::Regtest => bail!("The regtest network has no fixed consensus parameters"),
+        }
+    }
+}
+
+impl From<zcash_protocol::consensus::Network> for Network {
+    fn from(value: zcash_protocol::consensus::Network) -> Self {
+        use zcash_protocol::consensus::Parameters;
+        Network(value.network_type())
+    }
+}
+
 impl From<Network> for String {
     fn from(value: Network) -> String {
         match value.0 {
@@ -130,3 +164,23 @@ impl crate::RandomInstance for Network {
 
 test_cbor_roundtrip!(Network);
 test_envelope_roundtrip!(Network);
+
+#[cfg(test)]
+mod tests {
+    use zcash_protocol::consensus::{self, NetworkType, Parameters};
+
+    use super::Network;
+
+    #[test]
+    fn test_consensus_network_mapping() {
+        for (network, params) in [
+            (Network::Main, consensus::Network::MainNetwork),
+            (Network::Test, consensus::Network::TestNetwork),
+        ] {
+            assert_eq!(consensus::Network::try_from(network).unwrap(), params);
+            assert_eq!(Network::from(params), network);
+            assert_eq!(params.network_type(), NetworkType::from(network));
+        }
+        assert!(consensus::Network::try_from(Network::Regtest).is_err());
+    }
+}
@@ -2,7 +2,7 @@ use anyhow::Context;
 use bc_envelope::prelude::*;
 use crate::{test_envelope_roundtrip, Indexed};
 
-use super::{Amount, Blob, u256};
+use super::{Amount, Blob, Memo, SecretBlob, u256};
 
 /// Represents a sent output in an Orchard shielded transaction within a Zcash wallet.
 ///
@@ -25,6 +25,8 @@ use super::{Amount, Blob, u256};
 /// - Value: The amount of ZEC transferred
 /// - Rho/Psi: Protocol-specific randomness values
 /// - Rcm: Random commitment material
+/// - Rseed: The seed that psi and rcm are derived from, if the source wallet kept it
+/// - Memo: The 512-byte memo the sender attached, if the source wallet kept it
 ///
 /// # Data Preservation
 /// During wallet migration, Orchard sent output information must be preserved to maintain
@@ -106,6 +108,22 @@ pub struct OrchardSentOutput {
     /// the note's contents on the blockchain. It is stored to enable the wallet to
     /// regenerate the commitment for proving payment details.
     rcm: u256,
+
+    /// The 32-byte `rseed` of the note, if the source wallet recorded it.
+    ///
+    /// Orchard notes always follow ZIP 212, so `psi` and `rcm` are both derived
+    /// from `rseed` and `rho`. Wallets that keep the note rather than its
+    /// commitment inputs store `rseed`, and it is preserved alongside the
+    /// derived values because this crate cannot recompute them. It is held in
+    /// a [`SecretBlob`] so that it is scrubbed from memory on drop.
+    rseed: Option<SecretBlob<32>>,
+
+    /// The memo attached to the note, if the source wallet recorded it.
+    ///
+    /// As for [`SaplingSentOutput`](crate::sapling::SaplingSentOutput), a note
+    /// sent without a memo carries the empty memo, and `None` means that the
+    /// memo is not known.
+    memo: Option<Memo>,
 }
 
 impl Indexed for OrchardSentOutput {
@@ -174,6 +192,8 @@ impl OrchardSentOutput {
             rho,
             psi,
             rcm,
+            rseed: None,
+            memo: None,
         }
     }
 
@@ -491,6 +511,43 @@ impl OrchardSentOutput {
     pub fn set_rcm(&mut self, rcm: u256) {
         self.rcm = rcm;
     }
+
+    /// Returns the `rseed` that `psi` and `rcm` are derived from, if it is known.
+    pub fn rseed(&self) -> Option<&SecretBlob<32>> {
+        self.rseed.as_ref()
+    }
+
+    /// Sets the `rseed` of the note, or `None` if it is not known.
+    pub fn set_rseed(&mut self, rseed: Option<SecretBlob<32>>) {
+        self.rseed = rseed;
+    }
+
+    /// Returns the memo attached to the note, if it is known.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{OrchardSentOutput, Blob, Memo, u256, Amount};
+    /// # use anyhow::Result;
+    /// #
+    /// # fn example() -> Result<()> {
+    /// # let mut sent_output = OrchardSentOutput::new(
+    /// #     Blob::<11>::default(), u256::default(), Amount::from_u64(1000)?,
+    /// #     u256::default(), u256::default(), u256::default());
+    /// #
+    /// assert_eq!(sent_output.memo(), None);
+    /// sent_output.set_memo(Some(Memo::from_text("Rent for May")?));
+    /// assert_eq!(sent_output.memo().and_then(Memo::text), Some("Rent for May"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn memo(&self) -> Option<&Memo> {
+        self.memo.as_ref()
+    }
+
+    /// Sets the memo attached to the note, or `None` if it is not known.
+    pub fn set_memo(&mut self, memo: Option<Memo>) {
+        self.memo = memo;
+    }
 }
 
 impl From<OrchardSentOutput> for Envelope {
@@ -503,6 +560,8 @@ impl From<OrchardSentOutput> for Envelope {
             .add_assertion("rho", value.rho)
             .add_assertion("psi", value.psi)
             .add_assertion("rcm", value.rcm)
+            .add_optional_assertion("rseed", value.rseed)
+            .add_optional_assertion("memo", value.memo)
     }
 }
 
@@ -518,6 +577,8 @@ impl TryFrom<Envelope> for OrchardSentOutput {
         let rho = envelope.extract_object_for_predicate("rho").context("rho")?;
         let psi = envelope.extract_object_for_predicate("psi").context("psi")?;
         let rcm = envelope.extract_object_for_predicate("rcm").context("rcm")?;
+        let rseed = envelope.try_optional_object_for_predicate("rseed").context("rseed")?;
+        let memo = envelope.try_optional_object_for_predicate("memo").context("memo")?;
 
         Ok(OrchardSentOutput {
             index,
@@ -527,10 +588,24 @@ impl TryFrom<Envelope> for OrchardSentOutput {
             rho,
             psi,
             rcm,
+            rseed,
+            memo,
         })
     }
 }
 
+impl crate::EnvelopeSchema for OrchardSentOutput {
+    const TYPE_NAME: &'static str = "OrchardSentOutput";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["diversifier", "receipient_public_key", "value", "rho", "psi", "rcm"]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["rseed", "memo"]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for OrchardSentOutput {
     fn random() -> Self {
@@ -542,8 +617,40 @@ impl crate::RandomInstance for OrchardSentOutput {
             rho: u256::random(),
             psi: u256::random(),
             rcm: u256::random(),
+            rseed: SecretBlob::opt_random(),
+            memo: Memo::opt_random(),
         }
     }
 }
 
 test_envelope_roundtrip!(OrchardSentOutput);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::OrchardSentOutput;
+    use crate::{Amount, Blob, Memo, SecretBlob, u256};
+
+    #[test]
+    fn test_rseed_and_memo_roundtrip() {
+        let mut output = OrchardSentOutput::new(
+            Blob::new([1u8; 11]),
+            u256::try_from(&[2u8; 32]).unwrap(),
+            Amount::from_u64(1000).unwrap(),
+            u256::try_from(&[3u8; 32]).unwrap(),
+            u256::try_from(&[4u8; 32]).unwrap(),
+            u256::try_from(&[5u8; 32]).unwrap(),
+        );
+        let without = OrchardSentOutput::try_from(Envelope::from(output.clone())).unwrap();
+        assert_eq!(without.rseed(), None);
+        assert_eq!(without.memo(), None);
+
+        output.set_rseed(Some(SecretBlob::new([6u8; 32])));
+        output.set_memo(Some(Memo::from_text("Rent for May").unwrap()));
+        let decoded = OrchardSentOutput::try_from(Envelope::from(output.clone())).unwrap();
+        assert_eq!(decoded, output);
+        assert_eq!(decoded.rseed(), Some(&SecretBlob::new([6u8; 32])));
+        assert_eq!(decoded.memo().and_then(Memo::text), Some("Rent for May"));
+    }
+}
@@ -16,7 +16,7 @@
 //! The parsing system is designed with several key principles:
 //!
 //! 1. **Type Safety**: Each parsed type has a dedicated implementation with appropriate validation
-//! 2. **Contextual Errors**: Error messages include both the type being parsed and the context
+//! 2. **Contextual Errors**: Error messages include the item being parsed and its byte offset
 //! 3. **Composition**: Complex types are built by combining parsers for simpler types
 //! 4. **Extensibility**: New types can easily implement the `Parse` trait
 //!
@@ -46,6 +46,7 @@
 //! ## Module Structure
 //!
 //! - [`parse_macro`]: Defines the `parse!` macro for context-aware parsing
+//! - `parse_error`: The `ParseError` that locates a failure by its byte offset
 //! - `parser_impl`: Core parser implementation and the `Parse` trait definition
 //! - `parseable_types`: Standard implementations of the `Parse` trait for common types
 //! - [`prelude`]: Common imports for convenient parser usage
@@ -57,5 +58,6 @@ use crate::mod_use;
 pub mod parse_macro;
 pub mod prelude;
 
+mod_use!(parse_error);
 mod_use!(parser_impl);
 mod_use!(parseable_types);
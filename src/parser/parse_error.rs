@@ -0,0 +1,152 @@
+//! The error the `parse!` macro reports, locating a failure in the input.
+
+use std::fmt::{self, Display, Formatter};
+
+/// An error from parsing one item of a binary data stream, with the position
+/// in the stream where the item started.
+///
+/// The `parse!` macro wraps each failure of the item it parses in a
+/// `ParseError`, so an error from a nested structure carries one for every
+/// level of nesting. Its message names the item and its byte offset, such as
+/// `Parsing ReceiverType at byte 1423`, and its [`source`] is the error that
+/// made the item fail. [`ParseError::innermost`] finds the deepest item, which
+/// is usually the one to look at in a malformed input.
+///
+/// [`source`]: std::error::Error::source
+///
+/// # Examples
+/// ```
+/// # use zewif::{parse, parser::prelude::*, TxId};
+/// let data = [0u8; 20];
+/// let mut p = Parser::new(&data);
+/// parse!(&mut p, u32, "version").unwrap();
+///
+/// // Only 16 of the 32 bytes of a TxId are left.
+/// let error = parse!(&mut p, TxId, "txid").unwrap_err();
+/// assert_eq!(error.to_string(), "Parsing txid at byte 4");
+///
+/// let parse_error = ParseError::innermost(&error).unwrap();
+/// assert_eq!(parse_error.context(), "txid");
+/// assert_eq!(parse_error.offset(), 4);
+/// assert_eq!(parse_error.remaining(), 16);
+/// ```
+#[derive(Debug)]
+pub struct ParseError {
+    context: String,
+    offset: usize,
+    remaining: usize,
+    source: anyhow::Error,
+}
+
+impl ParseError {
+    /// Creates an error for the item described by `context`, which started at
+    /// `offset` with `remaining` bytes of input left, and failed with `source`.
+    pub fn new(
+        context: impl Display,
+        offset: usize,
+        remaining: usize,
+        source: anyhow::Error,
+    ) -> Self {
+        Self {
+            context: context.to_string(),
+            offset,
+            remaining,
+            source,
+        }
+    }
+
+    /// Returns the description of the item that failed to parse.
+    pub fn context(&self) -> &str {
+        &self.context
+    }
+
+    /// Returns the byte offset in the input where the item started.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Returns the number of bytes of input that were left when the item
+    /// started.
+    pub fn remaining(&self) -> usize {
+        self.remaining
+    }
+
+    /// Returns the `ParseError` of the most deeply nested item in `error`'s
+    /// chain of causes, if there is one.
+    pub fn innermost(error: &anyhow::Error) -> Option<&ParseError> {
+        error
+            .chain()
+            .filter_map(|cause| cause.downcast_ref::<ParseError>())
+            .last()
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "Parsing {} at byte {}", self.context, self.offset)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.source.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::ParseError;
+    use crate::{TxId, parse, parser::prelude::*};
+
+    #[derive(Debug)]
+    struct Item {
+        _kind: u16,
+        _txid: TxId,
+    }
+
+    impl Parse for Item {
+        fn parse(p: &mut Parser) -> Result<Self> {
+            let kind = parse!(p, u16, "kind")?;
+            let txid = parse!(p, TxId, "item txid")?;
+            Ok(Self {
+                _kind: kind,
+                _txid: txid,
+            })
+        }
+    }
+
+    #[test]
+    fn test_truncated_input_reports_offset() {
+        // A header, then an item whose txid is cut off after 10 of its 32 bytes.
+        let data = [0u8; 16];
+        let mut p = Parser::new(&data);
+        parse!(&mut p, u32, "header").unwrap();
+        assert_eq!(p.offset(), 4);
+
+        let error = parse!(&mut p, Item, "item").unwrap_err();
+        assert_eq!(error.to_string(), "Parsing item at byte 4");
+
+        let outer = error.downcast_ref::<ParseError>().unwrap();
+        assert_eq!(outer.offset(), 4);
+        assert_eq!(outer.remaining(), 12);
+
+        let inner = ParseError::innermost(&error).unwrap();
+        assert_eq!(inner.context(), "item txid");
+        assert_eq!(inner.offset(), 6);
+        assert_eq!(inner.remaining(), 10);
+        assert!(format!("{:#}", error).contains("Parsing item txid at byte 6"));
+    }
+
+    #[test]
+    fn test_bytes_report_offset() {
+        let data = [1u8, 2, 3];
+        let mut p = Parser::new(&data);
+        assert_eq!(parse!(&mut p, bytes = 2, "prefix").unwrap(), &[1, 2]);
+        let error = parse!(&mut p, bytes = 2, "suffix").unwrap_err();
+        let parse_error = ParseError::innermost(&error).unwrap();
+        assert_eq!(parse_error.offset(), 2);
+        assert_eq!(parse_error.remaining(), 1);
+    }
+}
@@ -53,7 +53,9 @@
 ///
 /// # Error Handling
 /// The macro automatically adds context to errors, making debugging easier by
-/// describing what was being parsed when an error occurred.
+/// describing what was being parsed when an error occurred. When parsing from a
+/// parser, the context is a [`ParseError`](crate::parser::ParseError) that also
+/// records the byte offset where the item started.
 ///
 /// # Relation to ZCash Data Formats
 /// This macro is particularly useful when parsing ZCash wallet and transaction data,
@@ -86,48 +88,60 @@ macro_rules! parse {
             format!("Parsing {}", $context),
         )
     };
-    ($parser:expr, $type:ty, $context:expr) => {
-        ::anyhow::Context::context(
-            <$type as $crate::parser::Parse>::parse($parser),
-            format!("Parsing {}", $context),
-        )
-    };
-    ($parser:expr, $type:ty, param = $param:expr, $context:expr) => {
-        ::anyhow::Context::context(
-            <$type as $crate::parser::ParseWithParam<_>>::parse($parser, $param),
-            format!("Parsing {}", $context),
-        )
-    };
-    ($parser:expr, bytes = $length:expr, $context:expr) => {
-        ::anyhow::Context::context(
-            $crate::parser::Parser::next($parser, $length),
-            format!("Parsing {}", $context),
-        )
-    };
-    ($parser:expr, data = $length:expr, $context:expr) => {
-        ::anyhow::Context::context(
-            $crate::Data::parse_len($parser, $length),
-            format!("Parsing {}", $context),
-        )
-    };
-    ($parser:expr, $context:expr) => {
-        ::anyhow::Context::context(
-            $crate::parser::Parse::parse($parser),
-            format!("Parsing {}", $context),
-        )
-    };
-    ($parser:expr, param = $param:expr, $context:expr) => {
-        ::anyhow::Context::context(
-            $crate::parser::ParseWithParam::parse($parser, $param),
-            format!("Parsing {}", $context),
-        )
-    };
+    ($parser:expr, $type:ty, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| <$type as $crate::parser::Parse>::parse(p),
+        )
+    }};
+    ($parser:expr, $type:ty, param = $param:expr, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| <$type as $crate::parser::ParseWithParam<_>>::parse(p, $param),
+        )
+    }};
+    ($parser:expr, bytes = $length:expr, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| $crate::parser::Parser::next(p, $length),
+        )
+    }};
+    ($parser:expr, data = $length:expr, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| $crate::Data::parse_len(p, $length),
+        )
+    }};
+    ($parser:expr, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| $crate::parser::Parse::parse(p),
+        )
+    }};
+    ($parser:expr, param = $param:expr, $context:expr) => {{
+        let context = $context;
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || context,
+            |p| $crate::parser::ParseWithParam::parse(p, $param),
+        )
+    }};
 }
 
 /// A macro for parsing binary data with context-aware error messages.
 ///
 /// This version of the macro is enabled when the "with-context" feature is activated,
-/// using the `with_context` method from `anyhow` for more efficient error handling.
+/// evaluating the context only when parsing fails, for more efficient error handling.
 ///
 /// See the documentation for the non-feature version for detailed usage examples.
 #[cfg(feature = "with-context")]
@@ -158,35 +172,45 @@ macro_rules! parse {
         )
     };
     ($parser:expr, $type:ty, $context:expr) => {
-        ::anyhow::Context::with_context(<$type as $crate::parser::Parse>::parse($parser), || {
-            format!("Parsing {}", $context)
-        })
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| <$type as $crate::parser::Parse>::parse(p),
+        )
     };
     ($parser:expr, $type:ty, param = $param:expr, $context:expr) => {
-        ::anyhow::Context::with_context(
-            <$type as $crate::parser::ParseWithParam<_>>::parse($parser, $param),
-            || format!("Parsing {}", $context),
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| <$type as $crate::parser::ParseWithParam<_>>::parse(p, $param),
         )
     };
     ($parser:expr, bytes = $length:expr, $context:expr) => {
-        ::anyhow::Context::with_context($crate::parser::Parser::next($parser, $length), || {
-            format!("Parsing {}", $context)
-        })
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| $crate::parser::Parser::next(p, $length),
+        )
     };
     ($parser:expr, data = $length:expr, $context:expr) => {
-        ::anyhow::Context::with_context($crate::Data::parse_len($parser, $length), || {
-            format!("Parsing {}", $context)
-        })
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| $crate::Data::parse_len(p, $length),
+        )
     };
     ($parser:expr, $context:expr) => {
-        ::anyhow::Context::with_context($crate::parser::Parse::parse($parser), || {
-            format!("Parsing {}", $context)
-        })
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| $crate::parser::Parse::parse(p),
+        )
     };
     ($parser:expr, param = $param:expr, $context:expr) => {
-        ::anyhow::Context::with_context(
-            $crate::parser::ParseWithParam::parse($parser, $param),
-            || format!("Parsing {}", $context),
+        $crate::parser::Parser::parse_context(
+            $parser,
+            || $context,
+            |p| $crate::parser::ParseWithParam::parse(p, $param),
         )
     };
 }
@@ -124,7 +124,9 @@ impl<T: Parse, U: Parse> Parse for (T, U) {
 }
 
 pub fn parse_fixed_length_vec<T: Parse>(p: &mut Parser, length: usize) -> Result<Vec<T>> {
-    let mut items = Vec::with_capacity(length);
+    // A declared length is not trusted for preallocation beyond one item per
+    // remaining byte.
+    let mut items = Vec::with_capacity(length.min(p.remaining()));
     for i in 0..length {
         items.push(parse!(p, format!("array item {} of {}", i, length - 1))?);
     }
@@ -171,6 +173,25 @@ pub fn parse_vec<T: Parse>(p: &mut Parser) -> Result<Vec<T>> {
     parse_fixed_length_vec(p, length)
 }
 
+/// Parses a `CompactSize` count followed by that many items, like [`parse_vec`],
+/// but rejects a count above `max` before parsing any item.
+///
+/// Use this for lists whose size a hostile input could inflate, when the format
+/// gives a smaller bound than [`MAX_COMPACT_SIZE`](crate::MAX_COMPACT_SIZE).
+///
+/// # Examples
+/// ```
+/// # use zewif::parser::prelude::*;
+/// let data = [0x02, 0x0a, 0x0b];
+/// let items: Vec<u8> = parse_vec_bounded(&mut Parser::new(&data), 2).unwrap();
+/// assert_eq!(items, vec![0x0a, 0x0b]);
+/// assert!(parse_vec_bounded::<u8>(&mut Parser::new(&data), 1).is_err());
+/// ```
+pub fn parse_vec_bounded<T: Parse>(p: &mut Parser, max: usize) -> Result<Vec<T>> {
+    let length = *p.parse_context(|| "array length", |p| CompactSize::parse_bounded(p, max))?;
+    parse_fixed_length_vec(p, length)
+}
+
 pub fn parse_vec_with_param<T: ParseWithParam<U>, U: Clone>(
     p: &mut Parser,
     param: U,
@@ -260,3 +281,44 @@ impl<T: Parse> Parse for Option<T> {
         parse_optional(p)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_vec, parse_vec_bounded};
+    use crate::parser::prelude::*;
+
+    #[test]
+    fn test_parse_vec() {
+        let empty: Vec<u16> = parse_vec(&mut Parser::new(&[0x00])).unwrap();
+        assert!(empty.is_empty());
+
+        let data = [0x03, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00];
+        let mut p = Parser::new(&data);
+        let items: Vec<u16> = parse_vec(&mut p).unwrap();
+        assert_eq!(items, vec![1, 2, 3]);
+        p.check_finished().unwrap();
+
+        // A count larger than the items present fails on the first missing one.
+        let error = parse_vec::<u16>(&mut Parser::new(&&data[..5])).unwrap_err();
+        assert_eq!(ParseError::innermost(&error).unwrap().offset(), 5);
+    }
+
+    #[test]
+    fn test_parse_vec_bounded() {
+        let empty: Vec<u8> = parse_vec_bounded(&mut Parser::new(&[0x00]), 0).unwrap();
+        assert!(empty.is_empty());
+
+        let data = [0x03, 0x0a, 0x0b, 0x0c];
+        let items: Vec<u8> = parse_vec_bounded(&mut Parser::new(&data), 3).unwrap();
+        assert_eq!(items, vec![0x0a, 0x0b, 0x0c]);
+
+        let mut p = Parser::new(&data);
+        let error = parse_vec_bounded::<u8>(&mut p, 2).unwrap_err();
+        assert!(format!("{:#}", error).contains("exceeds maximum 2"));
+        assert_eq!(ParseError::innermost(&error).unwrap().context(), "array length");
+
+        // A huge declared count is rejected without reading or allocating items.
+        let hostile = [0xfe, 0x00, 0x00, 0x00, 0x01];
+        assert!(parse_vec_bounded::<u64>(&mut Parser::new(&hostile), 1000).is_err());
+    }
+}
@@ -5,9 +5,11 @@
 //! raw bytes. It includes both the low-level `Parser` for byte manipulation and the
 //! higher-level `Parse` and `ParseWithParam` traits for structured type parsing.
 
+use std::fmt::Display;
+
 use anyhow::{Result, bail};
 
-use super::super::Data;
+use super::{super::Data, ParseError};
 
 /// A trait for types that can be parsed from a binary data stream.
 ///
@@ -222,6 +224,25 @@ impl<'a> Parser<'a> {
         self.len() - self.offset
     }
 
+    /// Returns the current position within the buffer, as a byte offset from its start.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Runs `parse` on this parser, wrapping any error in a [`ParseError`] that
+    /// records `context` and the offset where parsing started.
+    ///
+    /// This is what the `parse!` macro expands to. `context` is only called if
+    /// parsing fails.
+    pub fn parse_context<T, C: Display>(
+        &mut self,
+        context: impl FnOnce() -> C,
+        parse: impl FnOnce(&mut Self) -> Result<T>,
+    ) -> Result<T> {
+        let (offset, remaining) = (self.offset, self.remaining());
+        parse(self).map_err(|source| ParseError::new(context(), offset, remaining, source).into())
+    }
+
     pub fn check_finished(&self) -> Result<()> {
         if self.offset < self.buffer.len() {
             bail!("Buffer has {} bytes left", self.remaining());
@@ -257,6 +278,58 @@ impl<'a> Parser<'a> {
         &self.buffer[self.offset..self.offset + available]
     }
 
+    /// Returns the next byte without consuming it, or `None` at the end of the buffer.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::parser::prelude::*;
+    /// let data = [0x02, 0xaa];
+    /// let mut parser = Parser::new(&data);
+    /// assert_eq!(parser.peek_u8(), Some(0x02));
+    /// assert_eq!(parser.offset(), 0);
+    /// ```
+    pub fn peek_u8(&self) -> Option<u8> {
+        self.buffer.get(self.offset).copied()
+    }
+
+    /// Returns the next `n` bytes without consuming them, or `None` if fewer
+    /// than `n` bytes remain.
+    ///
+    /// Unlike [`peek`](Self::peek), this never returns a shorter slice.
+    pub fn peek_bytes(&self, n: usize) -> Option<&'a [u8]> {
+        self.buffer.get(self.offset..self.offset.checked_add(n)?)
+    }
+
+    /// Records the current position, so that [`restore`](Self::restore) can
+    /// rewind to it.
+    ///
+    /// This lets a parser attempt one interpretation of the input and back out
+    /// if it fails, for example to try each variant of a type in turn.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{parse, parser::prelude::*};
+    /// let data = [0x01, 0x02, 0x03];
+    /// let mut parser = Parser::new(&data);
+    /// let checkpoint = parser.checkpoint();
+    ///
+    /// // A u32 does not fit, so back out and read the bytes one at a time.
+    /// assert!(parse!(&mut parser, u32, "value").is_err());
+    /// parser.restore(checkpoint);
+    /// assert_eq!(parse!(&mut parser, u8, "value").unwrap(), 0x01);
+    /// ```
+    pub fn checkpoint(&self) -> ParserCheckpoint {
+        ParserCheckpoint(self.offset)
+    }
+
+    /// Rewinds the parser to a position recorded by [`checkpoint`](Self::checkpoint).
+    ///
+    /// The checkpoint must come from this parser. One from another parser
+    /// moves this one to the same offset, clamped to the end of its buffer.
+    pub fn restore(&mut self, checkpoint: ParserCheckpoint) {
+        self.offset = checkpoint.0.min(self.buffer.len());
+    }
+
     pub fn rest(&mut self) -> Data {
         Data::parse_len(self, self.remaining()).unwrap()
     }
@@ -276,6 +349,10 @@ impl<'a> Parser<'a> {
     }
 }
 
+/// A position in a [`Parser`]'s buffer, recorded by [`Parser::checkpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParserCheckpoint(usize);
+
 impl std::io::Read for &mut Parser<'_> {
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         let parser = &mut **self;
@@ -285,3 +362,53 @@ impl std::io::Read for &mut Parser<'_> {
         Ok(n)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::Parser;
+    use crate::parse;
+
+    #[test]
+    fn test_peek_does_not_consume() {
+        let data = [0x01, 0x02, 0x03];
+        let mut p = Parser::new(&data);
+        assert_eq!(p.peek_u8(), Some(0x01));
+        assert_eq!(p.peek_bytes(2), Some(&data[..2]));
+        assert_eq!(p.peek_bytes(3), Some(&data[..]));
+        assert_eq!(p.peek_bytes(4), None);
+        assert_eq!(p.offset(), 0);
+
+        p.next(2).unwrap();
+        assert_eq!(p.peek_u8(), Some(0x03));
+        assert_eq!(p.peek_bytes(1), Some(&data[2..]));
+        assert_eq!(p.peek_bytes(2), None);
+        assert_eq!(p.offset(), 2);
+
+        p.next(1).unwrap();
+        assert_eq!(p.peek_u8(), None);
+        assert_eq!(p.peek_bytes(0), Some(&[][..]));
+        assert_eq!(p.peek_bytes(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_restore_rewinds_exactly() {
+        let data: Vec<u8> = (0..10).collect();
+        let mut p = Parser::new(&data);
+        p.next(3).unwrap();
+        let checkpoint = p.checkpoint();
+
+        let first = parse!(&mut p, u32, "speculative").unwrap();
+        assert_eq!(p.offset(), 7);
+        p.restore(checkpoint);
+        assert_eq!(p.offset(), 3);
+        assert_eq!(parse!(&mut p, u32, "again").unwrap(), first);
+
+        // A failed parse can be backed out of too, even after it consumed bytes.
+        let checkpoint = p.checkpoint();
+        p.next(2).unwrap();
+        assert!(parse!(&mut p, u32, "too long").is_err());
+        p.restore(checkpoint);
+        assert_eq!(p.offset(), 7);
+        assert_eq!(p.remaining(), 3);
+    }
+}
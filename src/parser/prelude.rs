@@ -7,6 +7,7 @@
 //! ## Included Components
 //!
 //! - **Core Parser Types**: The `Parser` struct and `Parse` trait from `parser_impl`
+//! - **Errors**: The `ParseError` from `parse_error`
 //! - **Parseable Type Implementations**: Standard parsers from `parseable_types`
 //!
 //! ## Usage
@@ -30,6 +31,8 @@
 //! This prelude pattern follows Rust's convention (like the standard library's
 //! `std::prelude`) of providing the most commonly needed imports in a single module.
 
+#[doc(hidden)]
+pub use super::parse_error::*;
 #[doc(hidden)]
 pub use super::parseable_types::*;
 #[doc(hidden)]
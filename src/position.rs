@@ -1,7 +1,7 @@
-use anyhow::Context;
+use anyhow::{Context, Result};
 use bc_envelope::prelude::*;
 
-use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
+use crate::{parse, parser::prelude::*, test_cbor_roundtrip, test_envelope_roundtrip};
 
 /// A position index in a ZCash note commitment tree.
 ///
@@ -26,10 +26,93 @@ use crate::{test_cbor_roundtrip, test_envelope_roundtrip};
 /// The `Position` type preserves the exact numeric position identifiers from wallet data,
 /// which is critical for being able to spend notes after wallet migration.
 ///
-/// Internally, positions are stored as unsigned 32-bit integers, allowing for
-/// up to 4 billion notes in a commitment tree.
+/// Internally, positions are stored as unsigned 64-bit integers. A single
+/// depth-32 tree holds at most 2^32 notes, but a position counted across the
+/// whole chain history is not bound by one tree's capacity. Positions encoded
+/// when this type held a `u32` decode unchanged, since CBOR integers carry no
+/// fixed width.
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
-pub struct Position(u32);
+pub struct Position(u64);
+
+impl Position {
+    /// The largest representable position.
+    pub const MAX: Position = Position(u64::MAX);
+
+    /// Returns the position of the node that shares this node's parent.
+    ///
+    /// Positions here index the nodes of a single tree level, so the sibling
+    /// of a left child is the next position and the sibling of a right child
+    /// is the previous one.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Position;
+    /// assert_eq!(Position::from(4u32).sibling(), Position::from(5u32));
+    /// assert_eq!(Position::from(5u32).sibling(), Position::from(4u32));
+    /// ```
+    pub fn sibling(&self) -> Position {
+        Position(self.0 ^ 1)
+    }
+
+    /// Returns the position of this node's parent in the level above.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Position;
+    /// assert_eq!(Position::from(5u32).parent(), Position::from(2u32));
+    /// assert_eq!(Position::from(5u32).sibling().parent(), Position::from(2u32));
+    /// ```
+    pub fn parent(&self) -> Position {
+        Position(self.0 >> 1)
+    }
+
+    /// Returns `true` if this node is the right child of its parent.
+    ///
+    /// This is the bit that decides on which side a node is hashed with its
+    /// sibling when walking an authentication path to the root.
+    pub fn is_right_child(&self) -> bool {
+        self.0 & 1 == 1
+    }
+
+    /// Returns the position `n` leaves further on, or `None` if that would be
+    /// beyond [`Position::MAX`].
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::Position;
+    /// assert_eq!(Position::from(1u32).checked_add(2), Some(Position::from(3u32)));
+    /// assert_eq!(Position::MAX.checked_add(1), None);
+    /// ```
+    pub fn checked_add(&self, n: u64) -> Option<Position> {
+        self.0.checked_add(n).map(Position)
+    }
+
+    /// Returns the position `n` leaves further on, stopping at
+    /// [`Position::MAX`].
+    pub fn saturating_add(&self, n: u64) -> Position {
+        Position(self.0.saturating_add(n))
+    }
+
+    /// Parses a position from the 64-bit little-endian form zcashd stores on
+    /// disk.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Position, parser::Parser};
+    /// let bytes = 7u64.to_le_bytes();
+    /// let position = Position::parse_zcashd(&mut Parser::new(&bytes)).unwrap();
+    /// assert_eq!(position, Position::from(7u32));
+    /// assert_eq!(position.to_zcashd_bytes(), bytes);
+    /// ```
+    pub fn parse_zcashd(p: &mut Parser) -> Result<Self> {
+        Ok(Position(parse!(p, u64, "zcashd position")?))
+    }
+
+    /// Returns the 64-bit little-endian form zcashd stores on disk.
+    pub fn to_zcashd_bytes(&self) -> [u8; 8] {
+        self.0.to_le_bytes()
+    }
+}
 
 /// Debug formatting that shows the numeric position value
 impl std::fmt::Debug for Position {
@@ -41,21 +124,37 @@ impl std::fmt::Debug for Position {
 /// Creates a Position from a u32 value
 impl From<u32> for Position {
     fn from(value: u32) -> Self {
+        Self(value.into())
+    }
+}
+
+/// Creates a Position from a u64 value
+impl From<u64> for Position {
+    fn from(value: u64) -> Self {
         Self(value)
     }
 }
 
-/// Extracts the u32 value from a Position
-impl From<Position> for u32 {
+/// Extracts the u64 value from a Position
+impl From<Position> for u64 {
     fn from(value: Position) -> Self {
         value.0
     }
 }
 
+/// Extracts the u32 value from a Position, failing if it exceeds `u32::MAX`
+impl TryFrom<Position> for u32 {
+    type Error = std::num::TryFromIntError;
+
+    fn try_from(value: Position) -> Result<Self, Self::Error> {
+        u32::try_from(value.0)
+    }
+}
+
 /// Creates a Position from a usize value (useful for array indexing)
 impl From<usize> for Position {
     fn from(value: usize) -> Self {
-        Self(value as u32)
+        Self(value as u64)
     }
 }
 
@@ -75,7 +174,7 @@ impl TryFrom<CBOR> for Position {
     type Error = dcbor::Error;
 
     fn try_from(value: CBOR) -> dcbor::Result<Self> {
-        let position: u32 = value.try_into()?;
+        let position: u64 = value.try_into()?;
         Ok(Position(position))
     }
 }
@@ -97,9 +196,95 @@ impl TryFrom<Envelope> for Position {
 #[cfg(test)]
 impl crate::RandomInstance for Position {
     fn random() -> Self {
-        Self(u32::random())
+        Self(u64::random())
     }
 }
 
 test_cbor_roundtrip!(Position);
 test_envelope_roundtrip!(Position);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::Position;
+    use crate::parser::Parser;
+
+    #[test]
+    fn test_zcashd_roundtrip() {
+        for value in [0u64, 1, 0x0102_0304, u32::MAX.into(), u64::MAX] {
+            let position = Position::from(value);
+            let bytes = position.to_zcashd_bytes();
+            assert_eq!(bytes, value.to_le_bytes());
+            let mut p = Parser::new(&bytes);
+            assert_eq!(Position::parse_zcashd(&mut p).unwrap(), position);
+            p.check_finished().unwrap();
+        }
+
+        // Fewer than eight bytes is an error.
+        assert!(Position::parse_zcashd(&mut Parser::new(&[0u8; 4])).is_err());
+    }
+
+    #[test]
+    fn test_sibling_and_parent() {
+        // A leaf and its sibling share every ancestor, and the position of
+        // the ancestor at depth `d` is the leaf position shifted right by `d`.
+        let leaf = Position::from(0b1011_0110u32);
+        let mut node = leaf;
+        for depth in 0..8u32 {
+            assert_eq!(u64::from(node), u64::from(leaf) >> depth);
+            assert_eq!(node.sibling().sibling(), node);
+            assert_ne!(node.is_right_child(), node.sibling().is_right_child());
+            assert_eq!(node.sibling().parent(), node.parent());
+            assert_eq!(node.is_right_child(), (u64::from(leaf) >> depth) & 1 == 1);
+            node = node.parent();
+        }
+        assert_eq!(node, Position::from(0u32));
+
+        assert_eq!(Position::MAX.sibling(), Position::from(u64::MAX - 1));
+        assert!(Position::MAX.is_right_child());
+        assert_eq!(Position::MAX.parent(), Position::from(u64::MAX >> 1));
+    }
+
+    #[test]
+    fn test_add_at_max() {
+        let almost = Position::from(u64::MAX - 1);
+        assert_eq!(almost.checked_add(1), Some(Position::MAX));
+        assert_eq!(almost.checked_add(2), None);
+        assert_eq!(Position::MAX.checked_add(0), Some(Position::MAX));
+
+        assert_eq!(almost.saturating_add(1), Position::MAX);
+        assert_eq!(almost.saturating_add(2), Position::MAX);
+        assert_eq!(Position::MAX.saturating_add(u64::MAX), Position::MAX);
+
+        // Adding past the range of a single depth-32 tree is not an overflow.
+        let last_leaf = Position::from(u32::MAX);
+        assert_eq!(
+            last_leaf.checked_add(1),
+            Some(Position::from(u64::from(u32::MAX) + 1))
+        );
+    }
+
+    #[test]
+    fn test_roundtrip_above_u32_max() {
+        let position = Position::from(u64::from(u32::MAX) + 1);
+        assert!(u32::try_from(position).is_err());
+
+        let cbor = CBOR::from(position);
+        assert_eq!(Position::try_from(cbor).unwrap(), position);
+        let envelope = Envelope::from(position);
+        assert_eq!(Position::try_from(envelope).unwrap(), position);
+    }
+
+    #[test]
+    fn test_decodes_u32_encoding() {
+        // Data written when positions were `u32` values.
+        let value = u32::MAX;
+        let position = Position::try_from(CBOR::from(value)).unwrap();
+        assert_eq!(position, Position::from(value));
+        assert_eq!(u32::try_from(position).unwrap(), value);
+
+        let envelope = Envelope::new(CBOR::from(7u32));
+        assert_eq!(Position::try_from(envelope).unwrap(), Position::from(7u32));
+    }
+}
@@ -1,5 +1,10 @@
-use crate::{UnifiedAddress, sapling, transparent};
+use std::str::FromStr;
+
+use crate::{EnvelopeSchema, Network, ReceiverType, UnifiedAddress, sapling, transparent, unified};
+use anyhow::{Context, Result, bail};
 use bc_envelope::prelude::*;
+use bech32::{Bech32, Bech32m, primitives::decode::CheckedHrpstring};
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
 
 /// A protocol-specific Zcash address representation without additional metadata.
 ///
@@ -14,6 +19,9 @@ use bc_envelope::prelude::*;
 /// - **Transparent addresses (t-prefixed)**: Function like Bitcoin addresses,
 ///   exposing all transaction details on the blockchain.
 ///
+/// - **TEX addresses (tex-prefixed)**: Transparent P2PKH addresses whose holder
+///   accepts funds only from transparent inputs ([ZIP 320](https://zips.z.cash/zip-0320)).
+///
 /// - **Shielded addresses (z-prefixed)**: Use zero-knowledge proofs to encrypt
 ///   transaction details. Originally included Sprout (legacy), now primarily
 ///   Sapling (zs-prefixed) and Orchard (zo-prefixed) protocols.
@@ -57,6 +65,9 @@ pub enum ProtocolAddress {
     /// An exposed transparent (T-address) similar to Bitcoin's.
     Transparent(transparent::Address),
 
+    /// A transparent-source-only (TEX) address.
+    Tex(transparent::TexAddress),
+
     /// A shielded address (Z-address). This can include Sapling, Sprout
     Sapling(Box<sapling::Address>),
 
@@ -66,6 +77,52 @@ pub enum ProtocolAddress {
 }
 
 impl ProtocolAddress {
+    /// Builds an address from raw receiver bytes.
+    ///
+    /// A single transparent or Sapling receiver produces the corresponding
+    /// single-protocol address; any other combination (including a lone Orchard
+    /// receiver) is encoded as a Unified Address, which requires distinct
+    /// receivers in ascending typecode order. Each receiver's byte length is
+    /// validated against its type.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, ProtocolAddress, ReceiverType};
+    /// let receivers = vec![
+    ///     (ReceiverType::P2PKH, vec![0u8; 20]),
+    ///     (ReceiverType::Orchard, vec![0u8; 43]),
+    /// ];
+    /// let address = ProtocolAddress::from_receivers(receivers, Network::Main).unwrap();
+    /// assert!(address.is_unified());
+    /// assert!(address.as_string().starts_with("u1"));
+    /// ```
+    pub fn from_receivers(
+        receivers: Vec<(ReceiverType, Vec<u8>)>,
+        network: Network,
+    ) -> Result<ProtocolAddress> {
+        for (receiver_type, data) in &receivers {
+            unified::check_receiver_len(*receiver_type, data)?;
+        }
+        match receivers.as_slice() {
+            [] => bail!("Cannot build an address without receivers"),
+            [(ReceiverType::P2PKH, data)] => Ok(ProtocolAddress::Transparent(
+                transparent::Address::from_pubkey_hash(network, data.as_slice().try_into()?),
+            )),
+            [(ReceiverType::P2SH, data)] => Ok(ProtocolAddress::Transparent(
+                transparent::Address::from_script_hash(network, data.as_slice().try_into()?),
+            )),
+            [(ReceiverType::Sapling, data)] => Ok(ProtocolAddress::Sapling(Box::new(
+                sapling::Address::from_raw(network, data.as_slice().try_into()?),
+            ))),
+            _ => {
+                let encoded = unified::encode_unified_address(network, &receivers)?;
+                Ok(ProtocolAddress::Unified(Box::new(UnifiedAddress::new(
+                    encoded,
+                ))))
+            }
+        }
+    }
+
     /// Returns the address as a string in its canonical format.
     ///
     /// This method returns the string representation of the address, regardless
@@ -92,11 +149,83 @@ impl ProtocolAddress {
     pub fn as_string(&self) -> String {
         match self {
             ProtocolAddress::Transparent(addr) => addr.address().to_string(),
+            ProtocolAddress::Tex(addr) => addr.address().to_string(),
             ProtocolAddress::Sapling(addr) => addr.address().to_string(),
             ProtocolAddress::Unified(addr) => addr.address().to_string(),
         }
     }
 
+    /// Returns the address string exactly as it was stored.
+    ///
+    /// ZeWIF never re-encodes an address, so this is the string read from the
+    /// source wallet, including any non-canonical but valid form such as an
+    /// upper-case Bech32 string.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{ProtocolAddress, transparent};
+    /// let address = ProtocolAddress::Transparent(transparent::Address::new("t1example"));
+    /// assert_eq!(address.original_string(), "t1example");
+    /// ```
+    pub fn original_string(&self) -> &str {
+        match self {
+            ProtocolAddress::Transparent(addr) => addr.address(),
+            ProtocolAddress::Tex(addr) => addr.address(),
+            ProtocolAddress::Sapling(addr) => addr.address(),
+            ProtocolAddress::Unified(addr) => addr.address(),
+        }
+    }
+
+    /// Returns the receiver payload carried by the address, or an empty slice
+    /// if its string cannot be decoded.
+    ///
+    /// The payload is the address's binary content with every layer of its
+    /// text encoding removed: no version bytes, HRP or checksum, and for a
+    /// Unified Address no F4Jumble or HRP padding. It is the 20-byte hash of a
+    /// transparent or TEX address, the 43-byte raw payment address of a
+    /// Sapling address, and the typecode/length/value receiver items of a
+    /// Unified Address. Addresses that differ only in letter case have the same
+    /// payload.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, ProtocolAddress, ReceiverType};
+    /// let address = ProtocolAddress::from_receivers(
+    ///     vec![(ReceiverType::Sapling, vec![7u8; 43])],
+    ///     Network::Main,
+    /// ).unwrap();
+    /// assert_eq!(address.raw_bytes(), [7u8; 43]);
+    /// ```
+    pub fn raw_bytes(&self) -> &[u8] {
+        match self {
+            ProtocolAddress::Transparent(addr) => addr.raw_bytes(),
+            ProtocolAddress::Tex(addr) => addr.raw_bytes(),
+            ProtocolAddress::Sapling(addr) => addr.raw_bytes(),
+            ProtocolAddress::Unified(addr) => addr.raw_bytes(),
+        }
+    }
+
+    /// Returns `true` if the address carries key material that can spend from it.
+    ///
+    /// TEX and Unified Addresses never carry spending keys of their own.
+    pub fn has_spending_key(&self) -> bool {
+        match self {
+            ProtocolAddress::Transparent(addr) => addr.spend_authority().is_some(),
+            ProtocolAddress::Sapling(addr) => addr.spending_key().is_some(),
+            ProtocolAddress::Tex(_) | ProtocolAddress::Unified(_) => false,
+        }
+    }
+
+    /// Removes any spending key material from the address, keeping its viewing
+    /// keys and metadata.
+    pub fn clear_spending_key(&mut self) {
+        match self {
+            ProtocolAddress::Transparent(addr) => addr.clear_spend_authority(),
+            ProtocolAddress::Sapling(addr) => addr.clear_spending_key(),
+            ProtocolAddress::Tex(_) | ProtocolAddress::Unified(_) => {}
+        }
+    }
+
     /// Returns true if this is a Sapling address.
     ///
     /// # Returns
@@ -165,24 +294,153 @@ impl ProtocolAddress {
     pub fn is_unified(&self) -> bool {
         matches!(self, ProtocolAddress::Unified(_))
     }
+
+    /// Returns true if this is a TEX address.
+    ///
+    /// A TEX address is not reported by [`is_transparent`](Self::is_transparent),
+    /// since funds sent to it are restricted to transparent sources.
+    pub fn is_tex(&self) -> bool {
+        matches!(self, ProtocolAddress::Tex(_))
+    }
+
+    /// Returns the network the address belongs to, as identified by its encoding.
+    ///
+    /// A transparent address is identified by its Base58Check version bytes,
+    /// which testnet and regtest share, so a testnet or regtest transparent
+    /// address is reported as [`Network::Test`]. TEX, Sapling and Unified
+    /// Addresses are identified by their human-readable part.
+    ///
+    /// # Returns
+    /// - `Some(network)` if the address is well-formed for its encoding
+    /// - `None` if the string cannot be decoded or names no known network
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, ProtocolAddress, sapling};
+    /// let address = ProtocolAddress::Sapling(Box::new(sapling::Address::from_raw(
+    ///     Network::Regtest,
+    ///     &[0u8; 43],
+    /// )));
+    /// assert_eq!(address.network(), Some(Network::Regtest));
+    /// ```
+    pub fn network(&self) -> Option<Network> {
+        match self {
+            ProtocolAddress::Transparent(addr) => [Network::Main, Network::Test]
+                .into_iter()
+                .find(|network| transparent::Address::decode(addr.address(), *network).is_ok()),
+            ProtocolAddress::Tex(addr) => addr.network(),
+            ProtocolAddress::Sapling(addr) => {
+                let hrp = CheckedHrpstring::new::<Bech32>(addr.address())
+                    .ok()?
+                    .hrp()
+                    .to_lowercase();
+                [Network::Main, Network::Test, Network::Regtest]
+                    .into_iter()
+                    .find(|network| {
+                        NetworkType::from(*network).hrp_sapling_payment_address() == hrp
+                    })
+            }
+            ProtocolAddress::Unified(addr) => unified::decode_unified_address(addr.address())
+                .ok()
+                .map(|(network, _)| network),
+        }
+    }
 }
 
 impl From<ProtocolAddress> for Envelope {
     fn from(value: ProtocolAddress) -> Self {
         match value {
             ProtocolAddress::Transparent(addr) => addr.into(),
+            ProtocolAddress::Tex(addr) => addr.into(),
             ProtocolAddress::Sapling(addr) => (*addr).into(),
             ProtocolAddress::Unified(addr) => (*addr).into(),
         }
     }
 }
 
+/// Parses an address string of any supported kind.
+///
+/// The kind is chosen from the encoding's prefix: the Bech32 human-readable
+/// part of a Sapling (`zs`, `ztestsapling`, `zregtestsapling`), TEX (`tex`,
+/// `textest`, `texregtest`) or Unified (`u`, `utest`, `uregtest`) address, or
+/// a leading `t` for a Base58Check transparent address. Bech32 addresses may
+/// be all upper case, and keep that case when parsed. The string must then
+/// be a valid encoding of that kind, though the network it belongs to is not
+/// checked against any wallet.
+///
+/// # Errors
+/// Returns an error naming the prefix if it belongs to no supported kind, or
+/// describing why the string is not a valid address of the kind it names.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Network, ProtocolAddress, transparent};
+/// let encoded = transparent::Address::from_script_hash(Network::Main, &[0u8; 20]);
+/// let address: ProtocolAddress = encoded.address().parse().unwrap();
+/// assert!(address.is_transparent());
+///
+/// let error = "bc1qexample".parse::<ProtocolAddress>().unwrap_err();
+/// assert_eq!(error.to_string(), "Unrecognized address prefix \"bc\"");
+/// ```
+impl FromStr for ProtocolAddress {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let hrp = s.rfind('1').map(|separator| &s[..separator]);
+        let lowercase_hrp = hrp.map(str::to_ascii_lowercase);
+        let lowercase_hrp = lowercase_hrp.as_deref();
+        for network in [Network::Main, Network::Test, Network::Regtest] {
+            let network = NetworkType::from(network);
+            if lowercase_hrp == Some(network.hrp_sapling_payment_address()) {
+                let checked = CheckedHrpstring::new::<Bech32>(s)
+                    .context("Sapling address Bech32 encoding")?;
+                let len = checked.byte_iter().len();
+                if len != 43 {
+                    bail!("Sapling address payload must be 43 bytes, got {}", len);
+                }
+                return Ok(ProtocolAddress::Sapling(Box::new(sapling::Address::new(
+                    s.to_string(),
+                ))));
+            }
+            if lowercase_hrp == Some(network.hrp_tex_address()) {
+                let checked =
+                    CheckedHrpstring::new::<Bech32m>(s).context("TEX address Bech32m encoding")?;
+                let len = checked.byte_iter().len();
+                if len != 20 {
+                    bail!("TEX address payload must be 20 bytes, got {}", len);
+                }
+                return Ok(ProtocolAddress::Tex(transparent::TexAddress::new(s)));
+            }
+            if lowercase_hrp == Some(network.hrp_unified_address()) {
+                unified::decode_unified_address(s)?;
+                return Ok(ProtocolAddress::Unified(Box::new(UnifiedAddress::new(
+                    s.to_string(),
+                ))));
+            }
+        }
+        if s.starts_with('t') {
+            // Testnet and regtest share their version bytes.
+            let address = transparent::Address::decode(s, Network::Main)
+                .or_else(|_| transparent::Address::decode(s, Network::Test))?;
+            return Ok(ProtocolAddress::Transparent(address));
+        }
+        // Name a Bech32 human-readable part if there is a plausible one, and
+        // otherwise the first two characters.
+        let prefix = hrp
+            .filter(|hrp| !hrp.is_empty() && hrp.len() <= 16)
+            .unwrap_or_else(|| s.char_indices().nth(2).map_or(s, |(i, _)| &s[..i]));
+        bail!("Unrecognized address prefix \"{}\"", prefix)
+    }
+}
+
 impl TryFrom<Envelope> for ProtocolAddress {
     type Error = anyhow::Error;
 
     fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
         if envelope.has_type_envelope("TransparentAddress") {
             Ok(ProtocolAddress::Transparent(envelope.try_into()?))
+        } else if envelope.has_type_envelope("TexAddress") {
+            Ok(ProtocolAddress::Tex(envelope.try_into()?))
         } else if envelope.has_type_envelope("SaplingAddress") {
             Ok(ProtocolAddress::Sapling(Box::new(envelope.try_into()?)))
         } else if envelope.has_type_envelope("UnifiedAddress") {
@@ -193,14 +451,34 @@ impl TryFrom<Envelope> for ProtocolAddress {
     }
 }
 
+impl ProtocolAddress {
+    /// Checks an address envelope against the schema of the address type it
+    /// names, as [`EnvelopeSchema::check_strict`](crate::EnvelopeSchema::check_strict)
+    /// does for a single type.
+    pub fn check_strict(envelope: &Envelope) -> Result<()> {
+        if envelope.has_type_envelope("TransparentAddress") {
+            transparent::Address::check_strict(envelope)
+        } else if envelope.has_type_envelope("TexAddress") {
+            transparent::TexAddress::check_strict(envelope)
+        } else if envelope.has_type_envelope("SaplingAddress") {
+            sapling::Address::check_strict(envelope)
+        } else if envelope.has_type_envelope("UnifiedAddress") {
+            UnifiedAddress::check_strict(envelope)
+        } else {
+            Err(anyhow::anyhow!("Invalid ProtocolAddress type"))
+        }
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for ProtocolAddress {
     fn random() -> Self {
         let mut rng = rand::thread_rng();
-        let choice = rand::Rng::gen_range(&mut rng, 0..3);
+        let choice = rand::Rng::gen_range(&mut rng, 0..4);
         match choice {
             0 => ProtocolAddress::Transparent(transparent::Address::random()),
-            1 => ProtocolAddress::Sapling(Box::new(sapling::Address::random())),
+            1 => ProtocolAddress::Tex(transparent::TexAddress::random()),
+            2 => ProtocolAddress::Sapling(Box::new(sapling::Address::random())),
             _ => ProtocolAddress::Unified(Box::new(UnifiedAddress::random())),
         }
     }
@@ -212,4 +490,232 @@ mod tests {
     use crate::test_envelope_roundtrip;
 
     test_envelope_roundtrip!(ProtocolAddress);
+
+    use crate::{Network, ReceiverType, sapling, transparent};
+
+    #[test]
+    fn test_from_str() {
+        let cases = [
+            ProtocolAddress::Transparent(transparent::Address::from_pubkey_hash(
+                Network::Main,
+                &[1u8; 20],
+            )),
+            ProtocolAddress::Transparent(transparent::Address::from_script_hash(
+                Network::Main,
+                &[2u8; 20],
+            )),
+            ProtocolAddress::Transparent(transparent::Address::from_pubkey_hash(
+                Network::Test,
+                &[3u8; 20],
+            )),
+            ProtocolAddress::Tex(transparent::TexAddress::from_pubkey_hash(
+                Network::Main,
+                &[9u8; 20],
+            )),
+            ProtocolAddress::Sapling(Box::new(sapling::Address::from_raw(
+                Network::Main,
+                &[4u8; 43],
+            ))),
+            ProtocolAddress::from_receivers(
+                vec![
+                    (ReceiverType::P2PKH, vec![5u8; 20]),
+                    (ReceiverType::Orchard, vec![6u8; 43]),
+                ],
+                Network::Main,
+            )
+            .unwrap(),
+            ProtocolAddress::from_receivers(
+                vec![
+                    (ReceiverType::Sapling, vec![7u8; 43]),
+                    (ReceiverType::Orchard, vec![8u8; 43]),
+                ],
+                Network::Test,
+            )
+            .unwrap(),
+        ];
+        let prefixes = ["t1", "t3", "tm", "tex1", "zs1", "u1", "utest1"];
+        for (address, prefix) in cases.into_iter().zip(prefixes) {
+            let encoded = address.as_string();
+            assert!(encoded.starts_with(prefix), "{}", encoded);
+            assert_eq!(encoded.parse::<ProtocolAddress>().unwrap(), address);
+        }
+    }
+
+    #[test]
+    fn test_from_str_errors() {
+        let error = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+            .parse::<ProtocolAddress>()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Unrecognized address prefix \"bc\"");
+        let error = "".parse::<ProtocolAddress>().unwrap_err();
+        assert_eq!(error.to_string(), "Unrecognized address prefix \"\"");
+
+        // A recognized prefix on an invalid encoding is reported as such.
+        assert!("zs1invalid".parse::<ProtocolAddress>().is_err());
+        assert!("u1invalid".parse::<ProtocolAddress>().is_err());
+        assert!("t1invalid".parse::<ProtocolAddress>().is_err());
+        assert!("tex1invalid".parse::<ProtocolAddress>().is_err());
+    }
+
+    #[test]
+    fn test_canonical_tex_roundtrip() {
+        let tex = "tex1s2rt77ggv6q989lr49rkgzmh5slsksa9khdgte";
+        let address: ProtocolAddress = tex.parse().unwrap();
+        assert!(address.is_tex());
+        assert!(!address.is_transparent());
+        assert_eq!(address.as_string(), tex);
+        assert_eq!(address.network(), Some(Network::Main));
+        assert_eq!(
+            address.raw_bytes(),
+            transparent::Address::new("t1VmmGiyjVNeCjxDZzg7vZmd99WyzVby9yC")
+                .hash()
+                .unwrap()
+        );
+
+        let envelope = bc_envelope::Envelope::from(address.clone());
+        assert_eq!(ProtocolAddress::try_from(envelope).unwrap(), address);
+    }
+
+    #[test]
+    fn test_from_receivers_sapling_only() {
+        let address = ProtocolAddress::from_receivers(
+            vec![(ReceiverType::Sapling, vec![7u8; 43])],
+            Network::Main,
+        )
+        .unwrap();
+        assert!(address.is_sapling());
+        let encoded = address.as_string();
+        assert!(encoded.starts_with("zs1"));
+        assert_eq!(encoded.len(), 78);
+
+        let address = ProtocolAddress::from_receivers(
+            vec![(ReceiverType::Sapling, vec![7u8; 43])],
+            Network::Test,
+        )
+        .unwrap();
+        assert!(address.as_string().starts_with("ztestsapling1"));
+    }
+
+    #[test]
+    fn test_from_receivers_transparent_and_orchard() {
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+            (ReceiverType::Orchard, vec![2u8; 43]),
+        ];
+        let address = ProtocolAddress::from_receivers(receivers.clone(), Network::Main).unwrap();
+        assert!(address.is_unified());
+        assert_eq!(
+            address.as_string(),
+            "u1feukxdlmv7ancxyz7afwza6tz0edrqjvte3kwy0mhjuzz2ggmexjwlsze3qy8vku75ngtf94la6ucu4vvk3h2z6c6d0zmeqahv4cpxteyns4mxx6yrctummd05qede2y8c63kuwraqz"
+        );
+
+        // Encoding is deterministic.
+        let again = ProtocolAddress::from_receivers(receivers, Network::Main).unwrap();
+        assert_eq!(address, again);
+    }
+
+    #[test]
+    fn test_from_receivers_transparent_only() {
+        let address = ProtocolAddress::from_receivers(
+            vec![(ReceiverType::P2PKH, vec![0u8; 20])],
+            Network::Main,
+        )
+        .unwrap();
+        assert!(address.is_transparent());
+        assert_eq!(address.as_string(), "t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs");
+    }
+
+    #[test]
+    fn test_non_canonical_string_preserved() {
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+            (ReceiverType::Orchard, vec![2u8; 43]),
+        ];
+        let cases = [
+            ProtocolAddress::from_receivers(
+                vec![(ReceiverType::Sapling, vec![7u8; 43])],
+                Network::Main,
+            )
+            .unwrap(),
+            ProtocolAddress::Tex(transparent::TexAddress::from_pubkey_hash(
+                Network::Test,
+                &[3u8; 20],
+            )),
+            ProtocolAddress::from_receivers(receivers, Network::Main).unwrap(),
+        ];
+        for canonical in cases {
+            let upper = canonical.as_string().to_uppercase();
+            let address: ProtocolAddress = upper.parse().unwrap();
+            assert_eq!(address.original_string(), upper);
+            assert_eq!(address.raw_bytes(), canonical.raw_bytes());
+            assert!(!address.raw_bytes().is_empty());
+            assert_eq!(address.network(), canonical.network());
+
+            let decoded = ProtocolAddress::try_from(bc_envelope::Envelope::from(address)).unwrap();
+            assert_eq!(decoded.original_string(), upper);
+            assert_eq!(decoded.raw_bytes(), canonical.raw_bytes());
+        }
+
+        // Mixed case is not valid Bech32.
+        let sapling = ProtocolAddress::from_receivers(
+            vec![(ReceiverType::Sapling, vec![7u8; 43])],
+            Network::Main,
+        )
+        .unwrap()
+        .as_string();
+        let mixed = format!("ZS{}", &sapling[2..]);
+        assert!(mixed.parse::<ProtocolAddress>().is_err());
+    }
+
+    #[test]
+    fn test_raw_bytes() {
+        let address = ProtocolAddress::from_receivers(
+            vec![(ReceiverType::P2PKH, vec![9u8; 20])],
+            Network::Main,
+        )
+        .unwrap();
+        assert_eq!(address.raw_bytes(), [9u8; 20]);
+
+        let tex = ProtocolAddress::Tex(transparent::TexAddress::from_pubkey_hash(
+            Network::Main,
+            &[9u8; 20],
+        ));
+        assert_eq!(tex.raw_bytes(), [9u8; 20]);
+
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+            (ReceiverType::Orchard, vec![2u8; 43]),
+        ];
+        let address = ProtocolAddress::from_receivers(receivers, Network::Main).unwrap();
+        let items = [&[0x00, 20][..], &[1u8; 20], &[0x03, 43], &[2u8; 43]].concat();
+        assert_eq!(address.raw_bytes(), items);
+
+        let address = ProtocolAddress::Transparent(transparent::Address::new("t1bad"));
+        assert!(address.raw_bytes().is_empty());
+        let mut sapling = crate::sapling::Address::new("zs1bad".to_string());
+        assert!(sapling.raw_bytes().is_empty());
+        sapling.set_address(
+            crate::sapling::Address::from_raw(Network::Main, &[4u8; 43])
+                .address()
+                .to_string(),
+        );
+        assert_eq!(sapling.raw_bytes(), [4u8; 43]);
+    }
+
+    #[test]
+    fn test_from_receivers_rejects_bad_lengths() {
+        assert!(ProtocolAddress::from_receivers(vec![], Network::Main).is_err());
+        assert!(
+            ProtocolAddress::from_receivers(
+                vec![(ReceiverType::Sapling, vec![0u8; 42])],
+                Network::Main
+            )
+            .is_err()
+        );
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![0u8; 21]),
+            (ReceiverType::Orchard, vec![0u8; 43]),
+        ];
+        assert!(ProtocolAddress::from_receivers(receivers, Network::Main).is_err());
+    }
 }
@@ -1,5 +1,6 @@
 use crate::CompactSize;
 use crate::parser::prelude::*;
+use crate::unified::{ParseOptions, UnknownReceivers};
 use crate::{parse, test_cbor_roundtrip};
 use anyhow::{Result, bail};
 use bc_envelope::prelude::*;
@@ -25,7 +26,9 @@ use bc_envelope::prelude::*;
 /// # Data Preservation
 /// The `ReceiverType` enum preserves the exact type identifiers from wallet data,
 /// ensuring that Unified Addresses can be properly reconstructed during wallet migration.
-/// The underlying byte values match the Zcash protocol specification for UA encoding.
+/// The [`typecode`](Self::typecode) of each variant matches the Zcash protocol
+/// specification for UA encoding, and a receiver of a type defined after this crate
+/// was written is kept as [`Unknown`](Self::Unknown) with its typecode.
 ///
 /// # Examples
 /// In a Unified Address, multiple receiver types might be present:
@@ -39,32 +42,111 @@ use bc_envelope::prelude::*;
 /// assert!(!has_orchard);
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-#[repr(u8)]
 pub enum ReceiverType {
     /// P2PKH (Pay to Public Key Hash) transparent address type
-    P2PKH = 0x00,
+    P2PKH,
     /// P2SH (Pay to Script Hash) transparent address type
-    P2SH = 0x01,
+    P2SH,
     /// Sapling shielded address type
-    Sapling = 0x02,
+    Sapling,
     /// Orchard shielded address type
-    Orchard = 0x03,
+    Orchard,
+    /// A receiver type this crate does not recognize, identified by its
+    /// typecode. Its data is carried unchanged so the address can be
+    /// re-encoded as it was found.
+    Unknown(u32),
 }
 
-/// Parses a ReceiverType from a binary data stream
-impl Parse for ReceiverType {
-    fn parse(p: &mut Parser) -> Result<Self> {
-        let byte = *parse!(p, CompactSize, "ReceiverType")?;
-        match byte {
+impl ReceiverType {
+    /// Orders receiver types as ZIP 316 requires them to appear in an encoded
+    /// Unified Address: by ascending typecode.
+    ///
+    /// This is the reverse of the order in which a sender prefers receivers,
+    /// which puts the newest shielded protocol first.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::ReceiverType;
+    /// let mut types = vec![ReceiverType::Orchard, ReceiverType::P2PKH, ReceiverType::Sapling];
+    /// types.sort_by(ReceiverType::cmp_priority);
+    /// assert_eq!(types, [ReceiverType::P2PKH, ReceiverType::Sapling, ReceiverType::Orchard]);
+    /// ```
+    pub fn cmp_priority(&self, other: &Self) -> std::cmp::Ordering {
+        self.typecode().cmp(&other.typecode())
+    }
+
+    /// Returns the ZIP 316 typecode identifying this receiver type.
+    pub fn typecode(&self) -> u32 {
+        match self {
+            ReceiverType::P2PKH => 0x00,
+            ReceiverType::P2SH => 0x01,
+            ReceiverType::Sapling => 0x02,
+            ReceiverType::Orchard => 0x03,
+            ReceiverType::Unknown(typecode) => *typecode,
+        }
+    }
+
+    /// Returns the receiver type with the given typecode.
+    ///
+    /// A typecode this crate does not recognize yields
+    /// [`Unknown`](Self::Unknown), or an error if `options` ask for unknown
+    /// receivers to be rejected.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{ReceiverType, unified::{ParseOptions, UnknownReceivers}};
+    /// let preserve = ParseOptions::default();
+    /// assert_eq!(ReceiverType::from_typecode(0x02, &preserve).unwrap(), ReceiverType::Sapling);
+    /// assert_eq!(ReceiverType::from_typecode(0x05, &preserve).unwrap(), ReceiverType::Unknown(0x05));
+    ///
+    /// let reject = ParseOptions::default().with_unknown_receivers(UnknownReceivers::Reject);
+    /// assert!(ReceiverType::from_typecode(0x05, &reject).is_err());
+    /// ```
+    pub fn from_typecode(typecode: u32, options: &ParseOptions) -> Result<Self> {
+        match typecode {
             0x00 => Ok(ReceiverType::P2PKH),
             0x01 => Ok(ReceiverType::P2SH),
             0x02 => Ok(ReceiverType::Sapling),
             0x03 => Ok(ReceiverType::Orchard),
-            _ => Err(anyhow::anyhow!("Invalid ReceiverType byte: 0x{:02x}", byte)),
+            _ => match options.unknown_receivers {
+                UnknownReceivers::Preserve => Ok(ReceiverType::Unknown(typecode)),
+                UnknownReceivers::Reject => bail!("Unknown receiver typecode 0x{:02x}", typecode),
+            },
+        }
+    }
+
+    /// Parses a ReceiverType from its CompactSize typecode, handling unknown
+    /// typecodes as `options` direct.
+    pub fn parse_with_options(p: &mut Parser, options: &ParseOptions) -> Result<Self> {
+        let typecode = *parse!(p, CompactSize, "ReceiverType")?;
+        let typecode = u32::try_from(typecode)
+            .map_err(|_| anyhow::anyhow!("Receiver typecode 0x{:x} is too large", typecode))?;
+        Self::from_typecode(typecode, options)
+    }
+
+    /// Returns the length in bytes of the raw receiver data for this type, or
+    /// `None` for an unknown type, whose data may have any length.
+    ///
+    /// Transparent receivers are 20-byte public key or script hashes; Sapling
+    /// and Orchard receivers are 43-byte raw payment addresses (an 11-byte
+    /// diversifier followed by a 32-byte transmission key).
+    pub fn receiver_len(&self) -> Option<usize> {
+        match self {
+            ReceiverType::P2PKH | ReceiverType::P2SH => Some(20),
+            ReceiverType::Sapling | ReceiverType::Orchard => Some(43),
+            ReceiverType::Unknown(_) => None,
         }
     }
 }
 
+/// Parses a ReceiverType from its CompactSize typecode, preserving unknown
+/// typecodes as [`ReceiverType::Unknown`].
+impl Parse for ReceiverType {
+    fn parse(p: &mut Parser) -> Result<Self> {
+        Self::parse_with_options(p, &ParseOptions::default())
+    }
+}
+
 impl From<ReceiverType> for String {
     fn from(value: ReceiverType) -> Self {
         match value {
@@ -72,6 +154,7 @@ impl From<ReceiverType> for String {
             ReceiverType::P2SH => "P2SH".to_string(),
             ReceiverType::Sapling => "Sapling".to_string(),
             ReceiverType::Orchard => "Orchard".to_string(),
+            ReceiverType::Unknown(typecode) => format!("Unknown(0x{:02x})", typecode),
         }
     }
 }
@@ -85,7 +168,13 @@ impl TryFrom<String> for ReceiverType {
             "P2SH" => Ok(ReceiverType::P2SH),
             "Sapling" => Ok(ReceiverType::Sapling),
             "Orchard" => Ok(ReceiverType::Orchard),
-            _ => bail!("Invalid ReceiverType string: {}", value),
+            _ => value
+                .strip_prefix("Unknown(0x")
+                .and_then(|rest| rest.strip_suffix(')'))
+                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                .filter(|typecode| *typecode > 0x03)
+                .map(ReceiverType::Unknown)
+                .ok_or_else(|| anyhow::anyhow!("Invalid ReceiverType string: {}", value)),
         }
     }
 }
@@ -108,14 +197,51 @@ impl TryFrom<CBOR> for ReceiverType {
 impl crate::RandomInstance for ReceiverType {
     fn random() -> Self {
         let mut rng = rand::thread_rng();
-        let a = rand::Rng::gen_range(&mut rng, 0..=3);
+        let a = rand::Rng::gen_range(&mut rng, 0..=4);
         match a {
             0 => ReceiverType::P2PKH,
             1 => ReceiverType::P2SH,
             2 => ReceiverType::Sapling,
-            _ => ReceiverType::Orchard,
+            3 => ReceiverType::Orchard,
+            _ => ReceiverType::Unknown(rand::Rng::gen_range(&mut rng, 0x04..=0xff)),
         }
     }
 }
 
 test_cbor_roundtrip!(ReceiverType);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::ReceiverType;
+    use crate::parser::prelude::*;
+
+    #[test]
+    fn test_unknown_typecode_roundtrip() {
+        // A future receiver typecode and a metadata typecode (which needs a
+        // three-byte CompactSize) are both kept rather than rejected.
+        for (bytes, typecode) in [(&[0x1f][..], 0x1f), (&[0xfd, 0x00, 0xf6][..], 0xf600)] {
+            let mut p = Parser::new(&bytes);
+            let receiver_type = ReceiverType::parse(&mut p).unwrap();
+            p.check_finished().unwrap();
+            assert_eq!(receiver_type, ReceiverType::Unknown(typecode));
+            assert_eq!(receiver_type.typecode(), typecode);
+
+            let cbor = CBOR::from(receiver_type);
+            assert_eq!(ReceiverType::try_from(cbor).unwrap(), receiver_type);
+        }
+    }
+
+    #[test]
+    fn test_unknown_string_roundtrip() {
+        let unknown = ReceiverType::Unknown(0x1f);
+        assert_eq!(String::from(unknown), "Unknown(0x1f)");
+        assert_eq!(
+            ReceiverType::try_from(String::from(unknown)).unwrap(),
+            unknown
+        );
+        // Known typecodes are never written as unknown.
+        assert!(ReceiverType::try_from("Unknown(0x02)".to_string()).is_err());
+    }
+}
@@ -0,0 +1,34 @@
+use bc_envelope::prelude::*;
+
+use crate::{blob, blob_envelope, test_cbor_roundtrip};
+
+// A RedPallas signature: the encoding of the point R followed by the scalar S,
+// 32 bytes each.
+//
+// Orchard bundles carry one as the spendAuthSig of each action and one as the
+// bundle's bindingSig. See section 5.4.7 of the Zcash protocol specification.
+blob!(
+    RedPallasSignature,
+    64,
+    "A 64-byte RedPallas signature, as used for Orchard spend authorization and binding signatures"
+);
+
+blob_envelope!(RedPallasSignature);
+
+impl From<RedPallasSignature> for CBOR {
+    fn from(value: RedPallasSignature) -> Self {
+        CBOR::to_byte_string(value)
+    }
+}
+
+impl TryFrom<CBOR> for RedPallasSignature {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        let bytes = cbor.try_into_byte_string()?;
+        RedPallasSignature::from_slice(&bytes)
+            .map_err(|e| dcbor::Error::Custom(format!("RedPallasSignature: {e}")))
+    }
+}
+
+test_cbor_roundtrip!(RedPallasSignature);
@@ -1,8 +1,10 @@
 use super::{SaplingExtendedFullViewingKey, SaplingExtendedSpendingKey, SaplingIncomingViewingKey};
-use crate::{Blob, NoQuotesDebugOption, test_envelope_roundtrip};
+use crate::{Blob, Network, NoQuotesDebugOption, test_envelope_roundtrip};
 
 use anyhow::Context;
 use bc_envelope::prelude::*;
+use bech32::{Bech32, Hrp, primitives::decode::CheckedHrpstring};
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
 
 /// A Zcash Sapling address and associated key data.
 ///
@@ -82,6 +84,9 @@ pub struct Address {
     /// The diversifier index used creating this address, if known, stored as a byte array in
     /// little-endian order.
     diversifier_index: Option<Blob<11>>,
+
+    /// The 43-byte payment address decoded from `address`, or empty if it does not decode.
+    raw: Vec<u8>,
 }
 
 impl std::fmt::Debug for Address {
@@ -102,6 +107,7 @@ impl std::fmt::Debug for Address {
 impl Address {
     pub fn new(address: String) -> Self {
         Address {
+            raw: decode_raw(&address),
             address,
             incoming_viewing_key: None,
             full_viewing_key: None,
@@ -111,14 +117,40 @@ impl Address {
         }
     }
 
+    /// Creates a Sapling address by Bech32-encoding a raw 43-byte payment address
+    /// (an 11-byte diversifier followed by a 32-byte transmission key).
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, sapling};
+    /// let address = sapling::Address::from_raw(Network::Main, &[0u8; 43]);
+    /// assert!(address.address().starts_with("zs1"));
+    /// ```
+    pub fn from_raw(network: Network, raw: &[u8; 43]) -> Self {
+        let hrp = Hrp::parse(NetworkType::from(network).hrp_sapling_payment_address())
+            .expect("Sapling HRPs are valid");
+        let address = bech32::encode::<Bech32>(hrp, raw)
+            .expect("Sapling addresses are within the length limit");
+        Self::new(address)
+    }
+
     pub fn address(&self) -> &str {
         &self.address
     }
 
     pub fn set_address(&mut self, address: String) {
+        self.raw = decode_raw(&address);
         self.address = address;
     }
 
+    /// Returns the 43-byte raw payment address carried by this address, or an
+    /// empty slice if the string cannot be decoded.
+    ///
+    /// The payment address is decoded when the address string is set.
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
     /// Returns the Incoming Viewing Key (IVK) associated with this address, if available.
     ///
     /// The IVK enables viewing incoming transactions without granting spending capability.
@@ -159,6 +191,11 @@ impl Address {
         self.spending_key = Some(key);
     }
 
+    /// Removes the spending key, leaving the address watch-only.
+    pub fn clear_spending_key(&mut self) {
+        self.spending_key = None;
+    }
+
     pub fn diversifier_index(&self) -> Option<&Blob<11>> {
         self.diversifier_index.as_ref()
     }
@@ -178,6 +215,16 @@ impl Address {
     }
 }
 
+/// Decodes the raw payment address from a Bech32 Sapling address, returning an
+/// empty vector if it is malformed.
+fn decode_raw(address: &str) -> Vec<u8> {
+    CheckedHrpstring::new::<Bech32>(address)
+        .map(|checked| checked.byte_iter().collect::<Vec<u8>>())
+        .ok()
+        .filter(|raw| raw.len() == 43)
+        .unwrap_or_default()
+}
+
 impl From<Address> for Envelope {
     fn from(value: Address) -> Self {
         Envelope::new(value.address)
@@ -197,7 +244,7 @@ impl TryFrom<Envelope> for Address {
         envelope
             .check_type_envelope("SaplingAddress")
             .context("SaplingAddress")?;
-        let address = envelope.extract_subject().context("address")?;
+        let address: String = envelope.extract_subject().context("address")?;
         let incoming_viewing_key = envelope
             .try_optional_object_for_predicate("incoming_viewing_key")
             .context("incoming_viewing_key")?;
@@ -214,6 +261,7 @@ impl TryFrom<Envelope> for Address {
             .try_optional_object_for_predicate("hd_derivation_path")
             .context("hd_derivation_path")?;
         Ok(Address {
+            raw: decode_raw(&address),
             address,
             incoming_viewing_key,
             full_viewing_key,
@@ -224,16 +272,34 @@ impl TryFrom<Envelope> for Address {
     }
 }
 
+impl crate::EnvelopeSchema for Address {
+    const TYPE_NAME: &'static str = "SaplingAddress";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &[
+            "incoming_viewing_key",
+            "full_viewing_key",
+            "spending_key",
+            "diversifier_index",
+            "hd_derivation_path",
+        ]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for Address {
     fn random() -> Self {
         Self {
-            address: String::random(),
             incoming_viewing_key: SaplingIncomingViewingKey::opt_random(),
             full_viewing_key: SaplingExtendedFullViewingKey::opt_random(),
             spending_key: SaplingExtendedSpendingKey::opt_random(),
             diversifier_index: Blob::<11>::opt_random(),
             hd_derivation_path: String::opt_random(),
+            ..Self::new(String::random())
         }
     }
 }
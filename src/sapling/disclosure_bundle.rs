@@ -0,0 +1,266 @@
+use std::collections::BTreeSet;
+
+use anyhow::{Context, Result, bail};
+use bc_envelope::prelude::*;
+
+use super::SaplingSentOutput;
+use crate::{
+    Amount, Indexed, TxId, envelope_indexed_objects_for_predicate, test_envelope_roundtrip_strict,
+};
+
+/// A selective disclosure of all the Sapling outputs a wallet sent in one
+/// transaction.
+///
+/// `DisclosureBundle` lets a sender reveal an entire multi-output payment at
+/// once: it carries the transaction's id, the plaintext of each disclosed
+/// [`SaplingSentOutput`] and the total value they transfer, so a recipient of
+/// the bundle can check the amount without adding up the outputs themselves.
+///
+/// # Zcash Concept Relation
+/// A Sapling output description on chain commits to its note through the note
+/// commitment `cmu`, computed from the recipient's diversifier and public key,
+/// the value and the commitment randomness. Each disclosed output supplies
+/// exactly these plaintext fields, and its index identifies the output
+/// description within the transaction. A verifier with access to the chain
+/// recomputes `cmu` for each output with a Sapling implementation and compares
+/// it with the transaction's output descriptions.
+///
+/// # Verification
+/// [`verify`](Self::verify) checks the bundle's internal consistency: that it
+/// discloses at least one output, that no output index is disclosed twice, and
+/// that the stated total equals the sum of the output values. This crate does
+/// not implement the Jubjub arithmetic that note commitments require, so the
+/// comparison against on-chain commitments is left to the verifier.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Amount, Indexed, TxId, sapling::{DisclosureBundle, SaplingSentOutput}};
+/// let outputs: Vec<SaplingSentOutput> = [1000, 2500]
+///     .into_iter()
+///     .enumerate()
+///     .map(|(index, zats)| {
+///         let mut output = SaplingSentOutput::new();
+///         output.set_index(index);
+///         output.set_value(Amount::from_u64(zats).unwrap());
+///         output
+///     })
+///     .collect();
+///
+/// let bundle = DisclosureBundle::from_outputs(&outputs, TxId::from_bytes([1u8; 32])).unwrap();
+/// assert_eq!(bundle.total_value(), Amount::from_u64(3500).unwrap());
+/// assert!(bundle.verify().is_ok());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisclosureBundle {
+    txid: TxId,
+    outputs: Vec<SaplingSentOutput>,
+    total_value: Amount,
+}
+
+impl DisclosureBundle {
+    /// Creates a bundle disclosing `outputs`, all sent in the transaction
+    /// `txid`, with their total value.
+    ///
+    /// Each output's index is kept as its position among the transaction's
+    /// Sapling outputs, and the bundle lists the outputs in index order.
+    ///
+    /// # Errors
+    /// Returns an error if `outputs` is empty, or if the output values
+    /// overflow the range of an [`Amount`].
+    pub fn from_outputs(outputs: &[SaplingSentOutput], txid: TxId) -> Result<Self> {
+        if outputs.is_empty() {
+            bail!("Disclosure bundle for {} has no outputs", txid);
+        }
+        let mut outputs = outputs.to_vec();
+        outputs.sort_by_key(Indexed::index);
+        let total_value = Self::sum_values(&outputs)?;
+        Ok(Self {
+            txid,
+            outputs,
+            total_value,
+        })
+    }
+
+    pub fn txid(&self) -> TxId {
+        self.txid
+    }
+
+    pub fn outputs(&self) -> &[SaplingSentOutput] {
+        &self.outputs
+    }
+
+    /// Returns the total value the bundle states for its outputs.
+    pub fn total_value(&self) -> Amount {
+        self.total_value
+    }
+
+    /// Checks that the bundle is internally consistent.
+    ///
+    /// # Errors
+    /// Returns an error if the bundle discloses no outputs, if an output index
+    /// appears more than once, or if the stated total value differs from the
+    /// sum of the output values.
+    pub fn verify(&self) -> Result<()> {
+        if self.outputs.is_empty() {
+            bail!("Disclosure bundle for {} has no outputs", self.txid);
+        }
+        let mut indexes = BTreeSet::new();
+        for output in &self.outputs {
+            if !indexes.insert(output.index()) {
+                bail!(
+                    "Disclosure bundle for {} discloses output {} more than once",
+                    self.txid,
+                    output.index()
+                );
+            }
+        }
+        let sum = Self::sum_values(&self.outputs)?;
+        if sum != self.total_value {
+            bail!(
+                "Disclosure bundle for {} states a total of {} zats, but its outputs sum to {} zats",
+                self.txid,
+                i64::from(self.total_value),
+                i64::from(sum)
+            );
+        }
+        Ok(())
+    }
+
+    fn sum_values(outputs: &[SaplingSentOutput]) -> Result<Amount> {
+        Amount::sum(outputs.iter().map(SaplingSentOutput::value))
+            .context("Disclosed output values overflow")
+    }
+}
+
+impl From<DisclosureBundle> for Envelope {
+    fn from(value: DisclosureBundle) -> Self {
+        let e = Envelope::new(value.txid)
+            .add_type("SaplingDisclosureBundle")
+            .add_assertion("total_value", value.total_value);
+        value
+            .outputs
+            .into_iter()
+            .fold(e, |e, output| e.add_assertion("output", output))
+    }
+}
+
+impl TryFrom<Envelope> for DisclosureBundle {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        envelope
+            .check_type_envelope("SaplingDisclosureBundle")
+            .context("SaplingDisclosureBundle")?;
+        let txid = envelope.extract_subject().context("txid")?;
+        let total_value = envelope
+            .extract_object_for_predicate("total_value")
+            .context("total_value")?;
+        let outputs: Vec<SaplingSentOutput> =
+            envelope_indexed_objects_for_predicate(&envelope, "output").context("outputs")?;
+        if outputs.is_empty() {
+            bail!("SaplingDisclosureBundle must have at least one output");
+        }
+        Ok(Self {
+            txid,
+            outputs,
+            total_value,
+        })
+    }
+}
+
+impl crate::EnvelopeSchema for DisclosureBundle {
+    const TYPE_NAME: &'static str = "SaplingDisclosureBundle";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["output", "total_value"]
+    }
+
+    fn check_nested_strict(envelope: &Envelope) -> Result<()> {
+        crate::check_objects_strict::<SaplingSentOutput>(envelope, "output")
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for DisclosureBundle {
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        let outputs: Vec<SaplingSentOutput> = (0..rand::Rng::gen_range(&mut rng, 1..=3))
+            .map(|index| {
+                let mut output = SaplingSentOutput::random();
+                output.set_index(index);
+                output.set_value(
+                    Amount::from_u64(rand::Rng::gen_range(&mut rng, 0..=crate::COIN)).unwrap(),
+                );
+                output
+            })
+            .collect();
+        Self::from_outputs(&outputs, TxId::random()).unwrap()
+    }
+}
+
+test_envelope_roundtrip_strict!(DisclosureBundle);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::DisclosureBundle;
+    use crate::{Amount, Indexed, RandomInstance, TxId, sapling::SaplingSentOutput};
+
+    fn output(index: usize, zats: u64) -> SaplingSentOutput {
+        let mut output = SaplingSentOutput::random();
+        output.set_index(index);
+        output.set_value(Amount::from_u64(zats).unwrap());
+        output
+    }
+
+    #[test]
+    fn test_bundle_roundtrip() {
+        let txid = TxId::from_bytes([7u8; 32]);
+        let outputs = [output(2, 300), output(0, 1000), output(1, 20)];
+        let bundle = DisclosureBundle::from_outputs(&outputs, txid).unwrap();
+        assert_eq!(bundle.txid(), txid);
+        assert_eq!(bundle.total_value(), Amount::from_u64(1320).unwrap());
+        let indexes: Vec<usize> = bundle.outputs().iter().map(Indexed::index).collect();
+        assert_eq!(indexes, [0, 1, 2]);
+        bundle.verify().unwrap();
+
+        let decoded = DisclosureBundle::try_from(Envelope::from(bundle.clone())).unwrap();
+        assert_eq!(decoded, bundle);
+        decoded.verify().unwrap();
+    }
+
+    #[test]
+    fn test_total_mismatch() {
+        let txid = TxId::from_bytes([7u8; 32]);
+        let bundle =
+            DisclosureBundle::from_outputs(&[output(0, 1000), output(1, 20)], txid).unwrap();
+
+        // A bundle whose stated total was altered still decodes, but fails to verify.
+        let envelope = Envelope::from(bundle)
+            .replace_assertion(
+                Envelope::new_assertion("total_value", Amount::from_u64(1020).unwrap()),
+                Envelope::new_assertion("total_value", Amount::from_u64(1021).unwrap()),
+            )
+            .unwrap();
+        let tampered = DisclosureBundle::try_from(envelope).unwrap();
+        let error = tampered.verify().unwrap_err();
+        assert!(error.to_string().contains("total of 1021 zats"));
+    }
+
+    #[test]
+    fn test_verify_rejects_repeated_output() {
+        let bundle = DisclosureBundle::from_outputs(
+            &[output(0, 1000), output(0, 20)],
+            TxId::from_bytes([7u8; 32]),
+        )
+        .unwrap();
+        assert!(bundle.verify().is_err());
+    }
+
+    #[test]
+    fn test_from_outputs_rejects_empty() {
+        let error = DisclosureBundle::from_outputs(&[], TxId::from_bytes([7u8; 32])).unwrap_err();
+        assert!(error.to_string().contains("has no outputs"));
+    }
+}
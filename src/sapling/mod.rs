@@ -18,6 +18,9 @@
 //!
 //! - [`SaplingWitness`]: Cryptographic witness proving a note commitment exists in the tree
 //! - [`SaplingSentOutput`]: Sender's record of note data for outgoing transactions
+//! - [`SaplingRseed`]: Note commitment randomness, stored as `rcm` or as a ZIP 212 `rseed`
+//! - [`DisclosureBundle`]: A selective disclosure of all the outputs sent in one transaction
+//! - [`RedJubjubSignature`]: A spend authorization or binding signature
 //!
 //! ## Protocol Characteristics
 //!
@@ -34,9 +37,12 @@
 use crate::mod_use;
 
 mod_use!(address);
+mod_use!(disclosure_bundle);
+mod_use!(red_jubjub_signature);
 mod_use!(sapling_anchor_witness);
 mod_use!(sapling_extended_spending_key);
 mod_use!(sapling_extended_full_viewing_key);
 mod_use!(sapling_incoming_viewing_key);
+mod_use!(sapling_rseed);
 mod_use!(sapling_sent_output);
 mod_use!(sapling_witness);
@@ -0,0 +1,35 @@
+use bc_envelope::prelude::*;
+
+use crate::{blob, blob_envelope, test_cbor_roundtrip};
+
+// A RedJubjub signature: the encoding of the point R followed by the scalar S,
+// 32 bytes each.
+//
+// Sapling transactions carry one as the spendAuthSig of each spend
+// description and one as the bundle's bindingSig. See section 5.4.7 of the
+// Zcash protocol specification.
+blob!(
+    RedJubjubSignature,
+    64,
+    "A 64-byte RedJubjub signature, as used for Sapling spend authorization and binding signatures"
+);
+
+blob_envelope!(RedJubjubSignature);
+
+impl From<RedJubjubSignature> for CBOR {
+    fn from(value: RedJubjubSignature) -> Self {
+        CBOR::to_byte_string(value)
+    }
+}
+
+impl TryFrom<CBOR> for RedJubjubSignature {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        let bytes = cbor.try_into_byte_string()?;
+        RedJubjubSignature::from_slice(&bytes)
+            .map_err(|e| dcbor::Error::Custom(format!("RedJubjubSignature: {e}")))
+    }
+}
+
+test_cbor_roundtrip!(RedJubjubSignature);
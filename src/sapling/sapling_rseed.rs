@@ -0,0 +1,61 @@
+use crate::SecretBlob;
+
+/// The randomness a Sapling note commitment is built from.
+///
+/// Before [ZIP 212](https://zips.z.cash/zip-0212) a Sapling note carried its
+/// commitment trapdoor `rcm` directly. From Canopy onward, notes instead carry a
+/// 32-byte seed `rseed` from which both `rcm` and the ephemeral secret key are
+/// derived. Wallet files written before and after the change store one or the
+/// other, and a sent output keeps whichever its source wallet recorded. Both
+/// are secret, so they are held in a [`SecretBlob`] that is scrubbed on drop.
+///
+/// # Examples
+/// ```
+/// # use zewif::{sapling::SaplingRseed, SecretBlob};
+/// let rseed = SaplingRseed::AfterZip212(SecretBlob::default());
+/// assert!(rseed.rcm().is_none());
+/// assert_eq!(rseed.rseed(), Some(&SecretBlob::default()));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SaplingRseed {
+    /// The note commitment trapdoor `rcm`, as stored for pre-ZIP 212 notes.
+    BeforeZip212(SecretBlob<32>),
+
+    /// The note seed `rseed`, as stored for ZIP 212 notes.
+    AfterZip212(SecretBlob<32>),
+}
+
+impl SaplingRseed {
+    /// Returns the stored `rcm`, if this is a pre-ZIP 212 note.
+    pub fn rcm(&self) -> Option<&SecretBlob<32>> {
+        match self {
+            SaplingRseed::BeforeZip212(rcm) => Some(rcm),
+            SaplingRseed::AfterZip212(_) => None,
+        }
+    }
+
+    /// Returns the stored `rseed`, if this is a ZIP 212 note.
+    pub fn rseed(&self) -> Option<&SecretBlob<32>> {
+        match self {
+            SaplingRseed::BeforeZip212(_) => None,
+            SaplingRseed::AfterZip212(rseed) => Some(rseed),
+        }
+    }
+}
+
+impl Default for SaplingRseed {
+    fn default() -> Self {
+        SaplingRseed::BeforeZip212(SecretBlob::default())
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for SaplingRseed {
+    fn random() -> Self {
+        if rand::random::<bool>() {
+            SaplingRseed::BeforeZip212(SecretBlob::random())
+        } else {
+            SaplingRseed::AfterZip212(SecretBlob::random())
+        }
+    }
+}
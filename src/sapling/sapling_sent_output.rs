@@ -1,8 +1,9 @@
-use anyhow::Context;
+use anyhow::{Context, bail};
 use bc_envelope::prelude::*;
-use crate::{test_envelope_roundtrip, Indexed};
+use crate::{test_envelope_roundtrip_strict, Indexed};
 
-use super::super::{u256, Amount, Blob};
+use super::super::{Amount, Blob, MAX_MONEY, Memo, SecretBlob};
+use super::SaplingRseed;
 
 /// Represents a sent output in a Sapling shielded transaction within a Zcash wallet.
 ///
@@ -22,7 +23,9 @@ use super::super::{u256, Amount, Blob};
 /// - Diversifier: Part of the recipient's shielded address derivation
 /// - Public key: The recipient's public key for the transaction
 /// - Value: The amount of ZEC transferred
-/// - Rcm: Random commitment material used to construct the note commitment
+/// - Rseed: Random commitment material used to construct the note commitment, stored
+///   either as `rcm` (before ZIP 212) or as the `rseed` it is derived from (after ZIP 212)
+/// - Memo: The 512-byte memo the sender attached, if the source wallet kept it
 ///
 /// # Data Preservation
 /// During wallet migration, sent output information must be preserved to maintain
@@ -74,8 +77,9 @@ pub struct SaplingSentOutput {
     ///
     /// This 32-byte value represents a point on the Jubjub curve, used in Sapling's
     /// cryptographic operations. It is part of the note plaintext and is needed to
-    /// verify the recipient of the sent funds during selective disclosure.
-    receipient_public_key: u256,
+    /// verify the recipient of the sent funds during selective disclosure. It is held
+    /// in a [`SecretBlob`] so that it is scrubbed from memory on drop.
+    receipient_public_key: SecretBlob<32>,
 
     /// The value of ZEC sent in this output, in zatoshis (1 ZEC = 10^8 zatoshis).
     ///
@@ -86,11 +90,18 @@ pub struct SaplingSentOutput {
 
     /// The random commitment material used in the note commitment.
     ///
-    /// This 32-byte value (256-bit scalar) is a randomly generated element used to
-    /// construct the note commitment on the blockchain, ensuring privacy by masking
-    /// the note's contents. It is stored here to allow reconstruction of the commitment
-    /// for proving purposes.
-    rcm: u256,
+    /// This is either the 32-byte trapdoor `rcm` itself, for notes created before
+    /// ZIP 212, or the 32-byte `rseed` it is derived from. It is stored here to
+    /// allow reconstruction of the commitment for proving purposes.
+    rseed: SaplingRseed,
+
+    /// The memo attached to the note, if the source wallet recorded it.
+    ///
+    /// The memo is part of the note plaintext, so it is needed to reconstruct the
+    /// outgoing note and to disclose the payment in full. A note sent without a
+    /// memo still carries the empty memo defined by ZIP 302; `None` means that the
+    /// memo is not known.
+    memo: Option<Memo>,
 }
 
 impl Indexed for SaplingSentOutput {
@@ -122,9 +133,10 @@ impl SaplingSentOutput {
         Self {
             index: 0,
             diversifier: Blob::default(),
-            receipient_public_key: u256::default(),
+            receipient_public_key: SecretBlob::default(),
             value: Amount::zero(),
-            rcm: u256::default(),
+            rseed: SaplingRseed::default(),
+            memo: None,
         }
     }
 
@@ -170,22 +182,22 @@ impl SaplingSentOutput {
     /// that the sender's wallet must store to enable selective disclosure.
     ///
     /// # Returns
-    /// A reference to the recipient's public key as a `u256`.
+    /// A reference to the recipient's public key as a `SecretBlob<32>`.
     ///
     /// # Examples
     /// ```
-    /// # use zewif::{sapling::SaplingSentOutput, u256};
+    /// # use zewif::sapling::SaplingSentOutput;
     /// let sent_output = SaplingSentOutput::new();
     /// let recipient_pk = sent_output.receipient_public_key();
     /// ```
-    pub fn receipient_public_key(&self) -> &u256 {
+    pub fn receipient_public_key(&self) -> &SecretBlob<32> {
         &self.receipient_public_key
     }
 
     /// Sets the recipient's public key.
     ///
     /// # Arguments
-    /// * `key` - The 32-byte recipient public key value
+    /// * `key` - The 32-byte recipient public key value, such as a `u256` or a byte array
     ///
     /// # Examples
     /// ```
@@ -194,8 +206,8 @@ impl SaplingSentOutput {
     /// let pk = u256::default();
     /// sent_output.set_receipient_public_key(pk);
     /// ```
-    pub fn set_receipient_public_key(&mut self, key: u256) {
-        self.receipient_public_key = key;
+    pub fn set_receipient_public_key(&mut self, key: impl Into<SecretBlob<32>>) {
+        self.receipient_public_key = key.into();
     }
 
     /// Returns the value (amount) of ZEC sent in this output.
@@ -224,7 +236,12 @@ impl SaplingSentOutput {
         self.value
     }
 
-    /// Sets the value (amount) of ZEC for this sent output.
+    /// Sets the value (amount) of ZEC for this sent output, without checking it.
+    ///
+    /// A note's value must lie in `0..=MAX_MONEY` zatoshis, and a negative
+    /// [`Amount`] set here yields a note that cannot be reconstructed. Use
+    /// [`try_set_value`](Self::try_set_value) for values that are not already
+    /// known to be valid.
     ///
     /// # Arguments
     /// * `value` - The amount of ZEC to set
@@ -244,30 +261,78 @@ impl SaplingSentOutput {
         self.value = value;
     }
 
-    /// Returns a reference to the random commitment material.
+    /// Sets the value of ZEC for this sent output, checking that it is a valid
+    /// note value.
+    ///
+    /// # Errors
+    /// Returns an error, leaving the value unchanged, if `value` is negative or
+    /// exceeds [`MAX_MONEY`](crate::MAX_MONEY).
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{sapling::SaplingSentOutput, Amount, MAX_MONEY};
+    /// let mut sent_output = SaplingSentOutput::new();
+    /// sent_output.try_set_value(Amount::from_u64(MAX_MONEY).unwrap()).unwrap();
+    /// assert!(sent_output.try_set_value(Amount::from_i64(-1).unwrap()).is_err());
+    /// assert_eq!(sent_output.value(), Amount::from_u64(MAX_MONEY).unwrap());
+    /// ```
+    pub fn try_set_value(&mut self, value: Amount) -> anyhow::Result<()> {
+        match u64::try_from(value) {
+            Ok(zats) if zats <= MAX_MONEY => {
+                self.value = value;
+                Ok(())
+            }
+            _ => bail!(
+                "SaplingSentOutput value of {} zats is outside 0..={}",
+                i64::from(value),
+                MAX_MONEY
+            ),
+        }
+    }
+
+    /// Returns the random commitment material, in the form the source wallet stored it.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{sapling::{SaplingRseed, SaplingSentOutput}, SecretBlob};
+    /// let sent_output = SaplingSentOutput::new();
+    /// assert_eq!(sent_output.rseed(), &SaplingRseed::BeforeZip212(SecretBlob::default()));
+    /// ```
+    pub fn rseed(&self) -> &SaplingRseed {
+        &self.rseed
+    }
+
+    /// Sets the random commitment material for this sent output.
+    pub fn set_rseed(&mut self, rseed: SaplingRseed) {
+        self.rseed = rseed;
+    }
+
+    /// Returns a reference to the random commitment material, if it was stored
+    /// directly as `rcm`.
     ///
     /// The rcm (random commitment material) is a 32-byte value used in constructing
     /// the note commitment on the blockchain. It ensures privacy by masking the
     /// note's contents. The sender must store this value to enable selective disclosure
-    /// or payment proofs.
+    /// or payment proofs. For a ZIP 212 note only the `rseed` it is derived from is
+    /// stored, and this returns `None`.
     ///
     /// # Returns
-    /// A reference to the random commitment material as a `u256`.
+    /// The random commitment material as a `SecretBlob<32>`, if stored directly.
     ///
     /// # Examples
     /// ```
-    /// # use zewif::{sapling::SaplingSentOutput, u256};
+    /// # use zewif::sapling::SaplingSentOutput;
     /// let sent_output = SaplingSentOutput::new();
     /// let rcm = sent_output.rcm();
     /// ```
-    pub fn rcm(&self) -> &u256 {
-        &self.rcm
+    pub fn rcm(&self) -> Option<&SecretBlob<32>> {
+        self.rseed.rcm()
     }
 
-    /// Sets the random commitment material for this sent output.
+    /// Sets the random commitment material for this sent output, as a pre-ZIP 212 `rcm`.
     ///
     /// # Arguments
-    /// * `rcm` - The 32-byte random commitment material
+    /// * `rcm` - The 32-byte random commitment material, such as a `u256` or a byte array
     ///
     /// # Examples
     /// ```
@@ -276,8 +341,28 @@ impl SaplingSentOutput {
     /// let rcm = u256::default();
     /// sent_output.set_rcm(rcm);
     /// ```
-    pub fn set_rcm(&mut self, rcm: u256) {
-        self.rcm = rcm;
+    pub fn set_rcm(&mut self, rcm: impl Into<SecretBlob<32>>) {
+        self.rseed = SaplingRseed::BeforeZip212(rcm.into());
+    }
+
+    /// Returns the memo attached to the note, if it is known.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{sapling::SaplingSentOutput, Memo};
+    /// let mut sent_output = SaplingSentOutput::new();
+    /// assert_eq!(sent_output.memo(), None);
+    ///
+    /// sent_output.set_memo(Some(Memo::from_text("Invoice 42").unwrap()));
+    /// assert_eq!(sent_output.memo().and_then(Memo::text), Some("Invoice 42"));
+    /// ```
+    pub fn memo(&self) -> Option<&Memo> {
+        self.memo.as_ref()
+    }
+
+    /// Sets the memo attached to the note, or `None` if it is not known.
+    pub fn set_memo(&mut self, memo: Option<Memo>) {
+        self.memo = memo;
     }
 }
 
@@ -289,12 +374,16 @@ impl Default for SaplingSentOutput {
 
 impl From<SaplingSentOutput> for Envelope {
     fn from(value: SaplingSentOutput) -> Self {
-        Envelope::new(value.index)
+        let e = Envelope::new(value.index)
             .add_type("SaplingSentOutput")
             .add_assertion("diversifier", value.diversifier)
             .add_assertion("receipient_public_key", value.receipient_public_key)
-            .add_assertion("value", value.value)
-            .add_assertion("rcm", value.rcm)
+            .add_assertion("value", value.value);
+        let e = match value.rseed {
+            SaplingRseed::BeforeZip212(rcm) => e.add_assertion("rcm", rcm),
+            SaplingRseed::AfterZip212(rseed) => e.add_assertion("rseed", rseed),
+        };
+        e.add_optional_assertion("memo", value.memo)
     }
 }
 
@@ -307,29 +396,130 @@ impl TryFrom<Envelope> for SaplingSentOutput {
         let diversifier = envelope.extract_object_for_predicate("diversifier").context("diversifier")?;
         let receipient_public_key = envelope.extract_object_for_predicate("receipient_public_key").context("receipient_public_key")?;
         let value = envelope.extract_object_for_predicate("value").context("value")?;
-        let rcm = envelope.extract_object_for_predicate("rcm").context("rcm")?;
+        let rcm = envelope.try_optional_object_for_predicate("rcm").context("rcm")?;
+        let rseed = envelope.try_optional_object_for_predicate("rseed").context("rseed")?;
+        let rseed = match (rcm, rseed) {
+            (Some(rcm), None) => SaplingRseed::BeforeZip212(rcm),
+            (None, Some(rseed)) => SaplingRseed::AfterZip212(rseed),
+            (Some(_), Some(_)) => bail!("SaplingSentOutput has both rcm and rseed"),
+            (None, None) => bail!("SaplingSentOutput must have either rcm or rseed"),
+        };
+        let memo = envelope.try_optional_object_for_predicate("memo").context("memo")?;
 
         Ok(SaplingSentOutput {
             index,
             diversifier,
             receipient_public_key,
             value,
-            rcm,
+            rseed,
+            memo,
         })
     }
 }
 
+impl crate::EnvelopeSchema for SaplingSentOutput {
+    const TYPE_NAME: &'static str = "SaplingSentOutput";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["diversifier", "receipient_public_key", "value"]
+    }
+
+    /// Exactly one of `rcm` and `rseed` is present, and `memo` when it is known.
+    fn optional_predicates() -> &'static [&'static str] {
+        &["rcm", "rseed", "memo"]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for SaplingSentOutput {
     fn random() -> Self {
         Self {
             index: 0,
             diversifier: Blob::random(),
-            receipient_public_key: u256::random(),
+            receipient_public_key: SecretBlob::random(),
             value: Amount::random(),
-            rcm: u256::random(),
+            rseed: SaplingRseed::random(),
+            memo: Memo::opt_random(),
         }
     }
 }
 
-test_envelope_roundtrip!(SaplingSentOutput);
+test_envelope_roundtrip_strict!(SaplingSentOutput);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::SaplingSentOutput;
+    use crate::{Amount, Blob, MAX_MONEY, Memo, SecretBlob, sapling::SaplingRseed, u256};
+
+    fn envelope(commitment_predicates: &[&str]) -> Envelope {
+        let e = Envelope::new(0)
+            .add_type("SaplingSentOutput")
+            .add_assertion("diversifier", Blob::<11>::new([1u8; 11]))
+            .add_assertion("receipient_public_key", u256::try_from(&[2u8; 32]).unwrap())
+            .add_assertion("value", Amount::from_u64(1000).unwrap());
+        commitment_predicates.iter().fold(e, |e, predicate| {
+            e.add_assertion(*predicate, u256::try_from(&[3u8; 32]).unwrap())
+        })
+    }
+
+    #[test]
+    fn test_decode_rcm_only() {
+        let output = SaplingSentOutput::try_from(envelope(&["rcm"])).unwrap();
+        let rcm = SecretBlob::new([3u8; 32]);
+        assert_eq!(output.rseed(), &SaplingRseed::BeforeZip212(rcm.clone()));
+        assert_eq!(output.rcm(), Some(&rcm));
+        assert_eq!(Envelope::from(output).format(), envelope(&["rcm"]).format());
+    }
+
+    #[test]
+    fn test_decode_rseed() {
+        let output = SaplingSentOutput::try_from(envelope(&["rseed"])).unwrap();
+        let rseed = SecretBlob::new([3u8; 32]);
+        assert_eq!(output.rseed(), &SaplingRseed::AfterZip212(rseed));
+        assert_eq!(output.rcm(), None);
+        assert_eq!(Envelope::from(output).format(), envelope(&["rseed"]).format());
+    }
+
+    #[test]
+    fn test_memo_roundtrip() {
+        for memo in [Memo::from_text("Invoice 42").unwrap(), Memo::empty()] {
+            let mut output = SaplingSentOutput::try_from(envelope(&["rseed"])).unwrap();
+            output.set_memo(Some(memo.clone()));
+            let decoded = SaplingSentOutput::try_from(Envelope::from(output.clone())).unwrap();
+            assert_eq!(decoded, output);
+            assert_eq!(decoded.memo(), Some(&memo));
+        }
+
+        // Without the assertion the memo is unknown.
+        let output = SaplingSentOutput::try_from(envelope(&["rseed"])).unwrap();
+        assert_eq!(output.memo(), None);
+    }
+
+    #[test]
+    fn test_try_set_value_bounds() {
+        let mut output = SaplingSentOutput::new();
+        let max = Amount::from_u64(MAX_MONEY).unwrap();
+        output.try_set_value(max).unwrap();
+        assert_eq!(output.value(), max);
+        output.try_set_value(Amount::zero()).unwrap();
+        assert_eq!(output.value(), Amount::zero());
+
+        // A negative value is rejected and the value is left unchanged.
+        output.try_set_value(max).unwrap();
+        let error = output.try_set_value(Amount::from_i64(-1).unwrap()).unwrap_err();
+        assert!(error.to_string().contains("outside"));
+        assert!(output.try_set_value(-max).is_err());
+        assert_eq!(output.value(), max);
+
+        // An amount above MAX_MONEY cannot be constructed in the first place.
+        assert!(Amount::from_u64(MAX_MONEY + 1).is_err());
+    }
+
+    #[test]
+    fn test_decode_requires_exactly_one_commitment_form() {
+        assert!(SaplingSentOutput::try_from(envelope(&[])).is_err());
+        assert!(SaplingSentOutput::try_from(envelope(&["rcm", "rseed"])).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+use std::fmt;
+
+use anyhow::{Context, Error, Result};
+use bc_envelope::prelude::*;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::{Blob, constant_time_eq, test_cbor_roundtrip, test_envelope_roundtrip, u256};
+
+/// A fixed-size byte array holding secret material, overwritten with zeros when
+/// dropped.
+///
+/// `SecretBlob<N>` is the counterpart of [`Blob<N>`] for values that should not
+/// outlive their use, such as note commitment randomness. Its backing array is
+/// scrubbed on drop, so copies made during a migration do not linger in freed
+/// memory, and its `Debug` and `Display` output is redacted so the bytes cannot
+/// leak into logs. Equality is checked with [`constant_time_eq`], so comparing
+/// two secrets does not reveal where they differ. It encodes to CBOR and
+/// envelopes exactly as a `Blob<N>` does.
+///
+/// Scrubbing only covers the `SecretBlob` itself: bytes copied out through
+/// [`as_bytes`](Self::as_bytes) or [`to_blob`](Self::to_blob) are the caller's
+/// responsibility.
+///
+/// # Examples
+/// ```
+/// # use zewif::SecretBlob;
+/// let secret = SecretBlob::new([7u8; 32]);
+/// assert_eq!(secret.as_bytes(), &[7u8; 32]);
+/// assert_eq!(format!("{:?}", secret), "Blob<32>(REDACTED)");
+/// ```
+#[derive(Clone)]
+pub struct SecretBlob<const N: usize>([u8; N]);
+
+impl<const N: usize> SecretBlob<N> {
+    /// Creates a new `SecretBlob` from the given bytes.
+    pub fn new(data: [u8; N]) -> Self {
+        Self(data)
+    }
+
+    /// Returns the length of the secret in bytes.
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    /// Returns `true` if the secret has zero length.
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    /// Returns a reference to the secret bytes.
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+
+    /// Returns the secret bytes as a slice.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+
+    /// Returns a copy of the secret as an ordinary, unscrubbed `Blob`.
+    pub fn to_blob(&self) -> Blob<N> {
+        Blob::new(self.0)
+    }
+
+    /// Compares two secrets in time that does not depend on their contents.
+    #[cfg(feature = "subtle")]
+    pub fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        subtle::ConstantTimeEq::ct_eq(&self.0[..], &other.0[..])
+    }
+}
+
+impl<const N: usize> PartialEq for SecretBlob<N> {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time_eq(&self.0, &other.0)
+    }
+}
+
+impl<const N: usize> Eq for SecretBlob<N> {}
+
+impl<const N: usize> std::hash::Hash for SecretBlob<N> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.0.hash(state);
+    }
+}
+
+impl<const N: usize> Default for SecretBlob<N> {
+    fn default() -> Self {
+        Self([0u8; N])
+    }
+}
+
+impl<const N: usize> Zeroize for SecretBlob<N> {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<const N: usize> Drop for SecretBlob<N> {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl<const N: usize> ZeroizeOnDrop for SecretBlob<N> {}
+
+impl<const N: usize> fmt::Debug for SecretBlob<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Blob<{}>(REDACTED)", N)
+    }
+}
+
+impl<const N: usize> fmt::Display for SecretBlob<N> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Blob<{}>(REDACTED)", N)
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for SecretBlob<N> {
+    fn from(data: [u8; N]) -> Self {
+        Self(data)
+    }
+}
+
+impl<const N: usize> From<Blob<N>> for SecretBlob<N> {
+    fn from(blob: Blob<N>) -> Self {
+        Self(blob.into())
+    }
+}
+
+impl From<u256> for SecretBlob<32> {
+    fn from(value: u256) -> Self {
+        Self(*AsRef::<[u8; 32]>::as_ref(&value))
+    }
+}
+
+impl<const N: usize> From<&SecretBlob<N>> for CBOR {
+    fn from(data: &SecretBlob<N>) -> Self {
+        CBOR::to_byte_string(data.0)
+    }
+}
+
+impl<const N: usize> From<SecretBlob<N>> for CBOR {
+    fn from(data: SecretBlob<N>) -> Self {
+        CBOR::from(&data)
+    }
+}
+
+impl<const N: usize> TryFrom<CBOR> for SecretBlob<N> {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        let mut bytes = cbor.try_into_byte_string()?;
+        let result = <[u8; N]>::try_from(bytes.as_slice())
+            .map(Self)
+            .map_err(|e| dcbor::Error::Custom(format!("SecretBlob: {e}")));
+        bytes.zeroize();
+        result
+    }
+}
+
+impl<const N: usize> From<SecretBlob<N>> for Envelope {
+    fn from(value: SecretBlob<N>) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl<const N: usize> TryFrom<Envelope> for SecretBlob<N> {
+    type Error = Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self> {
+        envelope.extract_subject().context("SecretBlob")
+    }
+}
+
+#[cfg(test)]
+impl<const N: usize> crate::RandomInstance for SecretBlob<N> {
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        Self(bc_rand::rng_random_array(&mut rng))
+    }
+}
+
+test_cbor_roundtrip!(SecretBlob<32>);
+test_envelope_roundtrip!(SecretBlob<32>);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+    use zeroize::Zeroize;
+
+    use super::SecretBlob;
+    use crate::Blob;
+
+    #[test]
+    fn test_redacted_formatting() {
+        let secret = SecretBlob::new([0xabu8; 16]);
+        assert_eq!(format!("{:?}", secret), "Blob<16>(REDACTED)");
+        assert_eq!(secret.to_string(), "Blob<16>(REDACTED)");
+        assert!(!format!("{:?}", Some(&secret)).contains("ab"));
+    }
+
+    #[test]
+    fn test_encodes_like_blob() {
+        let secret = SecretBlob::new([1u8; 32]);
+        let blob = Blob::new([1u8; 32]);
+        assert_eq!(CBOR::from(secret.clone()), CBOR::from(blob.clone()));
+        assert_eq!(
+            Envelope::from(secret.clone()).format(),
+            Envelope::from(blob.clone()).format()
+        );
+        assert_eq!(
+            SecretBlob::<32>::try_from(Envelope::from(blob)).unwrap(),
+            secret
+        );
+        assert!(SecretBlob::<16>::try_from(CBOR::from(secret)).is_err());
+    }
+
+    #[test]
+    fn test_zeroize() {
+        let mut secret = SecretBlob::new([9u8; 8]);
+        secret.zeroize();
+        assert_eq!(secret.as_bytes(), &[0u8; 8]);
+    }
+}
@@ -0,0 +1,111 @@
+use anyhow::Context;
+use bc_envelope::prelude::*;
+use std::fmt;
+
+use crate::{HexParseError, test_cbor_roundtrip, test_envelope_roundtrip};
+
+/// A 32-byte fingerprint identifying a seed and the key hierarchy derived from it.
+///
+/// `SeedFingerprint` lets a wallet that bundles several independent seeds say
+/// which seed each account was derived from, without exposing the seed itself.
+///
+/// # Zcash Concept Relation
+/// [ZIP 32](https://zips.z.cash/zip-0032#seed-fingerprints) defines the seed
+/// fingerprint as a BLAKE2b-256 hash of the seed, and wallets use it to tell
+/// seeds apart. ZeWIF stores the fingerprint as reported by the source wallet
+/// and does not recompute it.
+///
+/// # Examples
+/// ```
+/// # use zewif::SeedFingerprint;
+/// let fingerprint = SeedFingerprint::from_bytes([0xab; 32]);
+/// assert_eq!(SeedFingerprint::from_hex(&fingerprint.to_string()).unwrap(), fingerprint);
+/// ```
+#[derive(Clone, Copy, PartialOrd, Ord, PartialEq, Eq, Hash)]
+pub struct SeedFingerprint([u8; 32]);
+
+impl SeedFingerprint {
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    pub fn from_hex(hex: &str) -> Result<Self, HexParseError> {
+        let data = hex::decode(hex).map_err(HexParseError::HexInvalid)?;
+        Ok(Self(<[u8; 32]>::try_from(&data[..]).map_err(|_| {
+            HexParseError::SliceInvalid {
+                expected: 32,
+                actual: data.len(),
+            }
+        })?))
+    }
+}
+
+impl fmt::Debug for SeedFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SeedFingerprint({})", self)
+    }
+}
+
+impl fmt::Display for SeedFingerprint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl AsRef<[u8; 32]> for SeedFingerprint {
+    fn as_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl From<SeedFingerprint> for [u8; 32] {
+    fn from(value: SeedFingerprint) -> Self {
+        value.0
+    }
+}
+
+impl From<SeedFingerprint> for CBOR {
+    fn from(value: SeedFingerprint) -> Self {
+        CBOR::to_byte_string(value.0)
+    }
+}
+
+impl TryFrom<CBOR> for SeedFingerprint {
+    type Error = dcbor::Error;
+
+    fn try_from(cbor: CBOR) -> dcbor::Result<Self> {
+        let bytes = cbor.try_into_byte_string()?;
+        let bytes = <[u8; 32]>::try_from(&bytes[..]).map_err(|_| {
+            format!(
+                "Invalid SeedFingerprint length: expected 32 bytes, got {}",
+                bytes.len()
+            )
+        })?;
+        Ok(Self(bytes))
+    }
+}
+
+impl From<SeedFingerprint> for Envelope {
+    fn from(value: SeedFingerprint) -> Self {
+        Envelope::new(CBOR::from(value))
+    }
+}
+
+impl TryFrom<Envelope> for SeedFingerprint {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        envelope.extract_subject().context("SeedFingerprint")
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for SeedFingerprint {
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        Self(bc_rand::rng_random_array(&mut rng))
+    }
+}
+
+test_cbor_roundtrip!(SeedFingerprint);
+test_envelope_roundtrip!(SeedFingerprint);
@@ -1,4 +1,4 @@
-use super::{IncrementalWitness, u256};
+use super::{IncrementalWitness, MerkleHasher, u256};
 use crate::test_envelope_roundtrip;
 use anyhow::Context;
 use bc_envelope::prelude::*;
@@ -50,6 +50,49 @@ pub type SHA256Compress = u256;
 /// witness for a Merkle tree with 29 levels using SHA-256 compression as the hash function.
 pub type SproutWitness = IncrementalWitness<INCREMENTAL_MERKLE_TREE_DEPTH, SHA256Compress>;
 
+/// The hash of the Sprout note commitment tree.
+///
+/// A Sprout parent node is the SHA-256 compression function applied to the
+/// 64-byte concatenation of its children, starting from the SHA-256 initial
+/// state and without message padding. The hash is the same at every level,
+/// and an empty leaf is 32 zero bytes.
+///
+/// # Examples
+/// ```
+/// # use zewif::{MerkleHasher, SproutHasher};
+/// // The root of an empty Sprout tree, as zcashd displays it.
+/// assert_eq!(
+///     SproutHasher::empty_root(29).to_string(),
+///     "59d2cde5e65c1414c32ba54f0fe4bdb3d67618125286e6a191317917c812c6d7"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SproutHasher;
+
+impl MerkleHasher for SproutHasher {
+    fn empty_leaf() -> u256 {
+        u256::default()
+    }
+
+    fn combine(_level: usize, left: &u256, right: &u256) -> u256 {
+        const SHA256_IV: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        let mut block = [0u8; 64];
+        block[..32].copy_from_slice(left.as_ref());
+        block[32..].copy_from_slice(right.as_ref());
+        let mut state = SHA256_IV;
+        sha2::compress256(&mut state, &[block.into()]);
+
+        let mut bytes = [0u8; 32];
+        for (chunk, word) in bytes.chunks_exact_mut(4).zip(state) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        u256::try_from(&bytes).unwrap()
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for SproutWitness {
     fn random() -> Self {
@@ -82,3 +125,278 @@ impl TryFrom<Envelope> for SproutWitness {
 }
 
 test_envelope_roundtrip!(SproutWitness);
+
+#[cfg(test)]
+mod tests {
+    use super::{INCREMENTAL_MERKLE_TREE_DEPTH, SproutHasher, SproutWitness};
+    use crate::{IncrementalMerkleTree, IncrementalWitness, MerkleHasher, u256};
+
+    fn leaf(n: u8) -> u256 {
+        u256::try_from(&[n + 1; 32]).unwrap()
+    }
+
+    /// Computes the root of the subtree at `level` whose leaves are `leaves`,
+    /// padded with empty leaves, without any incremental state.
+    fn subtree_root(leaves: &[u256], level: usize) -> u256 {
+        if leaves.is_empty() {
+            SproutHasher::empty_root(level)
+        } else if level == 0 {
+            leaves[0]
+        } else {
+            let half = (1usize << (level - 1)).min(leaves.len());
+            SproutHasher::combine(
+                level - 1,
+                &subtree_root(&leaves[..half], level - 1),
+                &subtree_root(&leaves[half..], level - 1),
+            )
+        }
+    }
+
+    /// Returns the siblings of the leaf at `position` in the tree of `leaves`.
+    fn expected_path(leaves: &[u256], position: usize) -> Vec<(u256, bool)> {
+        (0..INCREMENTAL_MERKLE_TREE_DEPTH)
+            .map(|level| {
+                let sibling = (position >> level) ^ 1;
+                let start = (sibling << level).min(leaves.len());
+                let end = ((sibling + 1) << level).min(leaves.len());
+                let is_right = (position >> level) & 1 == 1;
+                (subtree_root(&leaves[start..end], level), is_right)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_roots() {
+        // The first entries of zcashd's Sprout `EmptyMerkleRoots`
+        // (src/zcash/IncrementalMerkleTree.cpp).
+        assert_eq!(SproutHasher::empty_root(0), u256::default());
+        assert_eq!(
+            SproutHasher::empty_root(1),
+            u256::from_hex("da5698be17b9b46962335799779fbeca8ce5d491c0d26243bafef9ea1837a9d8")
+                .unwrap()
+        );
+        assert_eq!(
+            SproutHasher::empty_root(2),
+            u256::from_hex("dc766fab492ccf3d1e49d4f374b5235fa56506aac2224d39f943fcd49202974c")
+                .unwrap()
+        );
+        assert_eq!(
+            SproutHasher::empty_root(3),
+            u256::from_hex("3f0a406181105968fdaee30679e3273c66b72bf9a7f5debbf3b5a0a26e359f92")
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_witness_path() {
+        // Five leaves have been appended to the tree.
+        let leaves: Vec<u256> = (0..5).map(leaf).collect();
+        let root = subtree_root(&leaves, INCREMENTAL_MERKLE_TREE_DEPTH);
+        let pair = SproutHasher::combine(0, &leaves[0], &leaves[1]);
+        let cursor = IncrementalMerkleTree::with_fields(Some(leaves[4]), None, Vec::new());
+
+        // A witness created after the third leaf was appended: the fourth
+        // leaf has been filled in, and the fifth started the cursor.
+        let tree = IncrementalMerkleTree::with_fields(Some(leaves[2]), None, vec![Some(pair)]);
+        let witness = SproutWitness::with_fields(tree, vec![leaves[3]], Some(cursor.clone()));
+        assert_eq!(witness.position(), Some(2));
+        let path = witness.path::<SproutHasher>().unwrap();
+        assert_eq!(path.position(), 2);
+        assert_eq!(path.auth_path(), expected_path(&leaves, 2));
+        assert_eq!(path.root::<SproutHasher>(leaves[2]), root);
+
+        // A witness created after the fourth leaf was appended.
+        let tree =
+            IncrementalMerkleTree::with_fields(Some(leaves[2]), Some(leaves[3]), vec![Some(pair)]);
+        let witness = SproutWitness::with_fields(tree, Vec::new(), Some(cursor));
+        let path = witness.path::<SproutHasher>().unwrap();
+        assert_eq!(path.position(), 3);
+        assert_eq!(path.auth_path(), expected_path(&leaves, 3));
+        assert_eq!(path.root::<SproutHasher>(leaves[3]), root);
+
+        // The full tree's root matches as well.
+        let tree = IncrementalMerkleTree::with_fields(
+            Some(leaves[4]),
+            None,
+            vec![
+                None,
+                Some(SproutHasher::combine(
+                    1,
+                    &pair,
+                    &SproutHasher::combine(0, &leaves[2], &leaves[3]),
+                )),
+            ],
+        );
+        assert_eq!(tree.size(), 5);
+        assert_eq!(
+            tree.root::<SproutHasher>(INCREMENTAL_MERKLE_TREE_DEPTH),
+            root
+        );
+    }
+
+    #[test]
+    fn test_append_block() {
+        let leaves: Vec<u256> = (0..40).map(leaf).collect();
+        for position in 0..7 {
+            let mut tree = IncrementalMerkleTree::new();
+            for leaf in &leaves[..=position] {
+                tree.append::<SproutHasher>(INCREMENTAL_MERKLE_TREE_DEPTH, *leaf)
+                    .unwrap();
+            }
+            let start = SproutWitness::with_fields(tree, Vec::new(), None);
+            for blocks in [&[33][..], &[1, 2, 3, 4, 5], &[7, 0, 9, 16]] {
+                let mut one_by_one = start.clone();
+                let mut batched = start.clone();
+                let mut end = position + 1;
+                for &len in blocks {
+                    let block = &leaves[end..end + len];
+                    for commitment in block {
+                        one_by_one.append::<SproutHasher>(*commitment).unwrap();
+                    }
+                    batched.append_block::<SproutHasher>(block).unwrap();
+                    end += len;
+
+                    assert_eq!(batched, one_by_one);
+                    let root = subtree_root(&leaves[..end], INCREMENTAL_MERKLE_TREE_DEPTH);
+                    assert_eq!(batched.root::<SproutHasher>(), root);
+                    let path = batched.path::<SproutHasher>().unwrap();
+                    assert_eq!(path.auth_path(), expected_path(&leaves[..end], position));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_append_block_full_tree() {
+        let leaves: Vec<u256> = (0..5).map(leaf).collect();
+        let tree = IncrementalMerkleTree::with_fields(Some(leaves[0]), None, Vec::new());
+        let start = IncrementalWitness::<2, u256>::with_fields(tree, Vec::new(), None);
+
+        let mut witness = start.clone();
+        assert!(witness.append_block::<SproutHasher>(&leaves[1..]).is_err());
+        assert_eq!(witness, start);
+
+        witness.append_block::<SproutHasher>(&leaves[1..4]).unwrap();
+        assert!(witness.append::<SproutHasher>(leaves[4]).is_err());
+        assert_eq!(
+            witness.root::<SproutHasher>(),
+            subtree_root(&leaves[..4], 2)
+        );
+    }
+
+    #[test]
+    fn test_roots() {
+        // The root of the empty Sprout tree, which is also the anchor of any
+        // tree whose leaves are all the empty leaf; from zcashd's
+        // `SproutMerkleTree::empty_root()` (src/zcash/IncrementalMerkleTree.cpp),
+        // as raw bytes.
+        let empty_anchor =
+            u256::from_hex("d7c612c817793191a1e68652121876d6b3bde40f4fa52bc314145ce6e5cdd259")
+                .unwrap();
+        assert_eq!(
+            IncrementalMerkleTree::new().root::<SproutHasher>(INCREMENTAL_MERKLE_TREE_DEPTH),
+            empty_anchor
+        );
+
+        let zero = u256::default();
+        let tree = IncrementalMerkleTree::with_fields(
+            Some(zero),
+            None,
+            vec![Some(SproutHasher::combine(0, &zero, &zero))],
+        );
+        assert_eq!(tree.size(), 3);
+        assert_eq!(
+            tree.root::<SproutHasher>(INCREMENTAL_MERKLE_TREE_DEPTH),
+            empty_anchor
+        );
+        let cursor = IncrementalMerkleTree::with_fields(Some(zero), None, Vec::new());
+        let witness = SproutWitness::with_fields(tree, vec![zero], Some(cursor));
+        assert_eq!(witness.root::<SproutHasher>(), empty_anchor);
+        let path = witness.path::<SproutHasher>().unwrap();
+        assert_eq!(path.root::<SproutHasher>(zero), empty_anchor);
+
+        // With distinct leaves, the incremental roots agree with the root
+        // computed directly from all of the leaves.
+        let leaves: Vec<u256> = (0..5).map(leaf).collect();
+        let pair = SproutHasher::combine(0, &leaves[0], &leaves[1]);
+        let tree = IncrementalMerkleTree::with_fields(Some(leaves[2]), None, vec![Some(pair)]);
+        let root_of_three = subtree_root(&leaves[..3], INCREMENTAL_MERKLE_TREE_DEPTH);
+        assert_eq!(
+            tree.root::<SproutHasher>(INCREMENTAL_MERKLE_TREE_DEPTH),
+            root_of_three
+        );
+
+        // A fresh witness authenticates against the root of its own tree.
+        let witness = SproutWitness::with_fields(tree.clone(), Vec::new(), None);
+        assert_eq!(witness.root::<SproutHasher>(), root_of_three);
+
+        // Once the fourth leaf is filled in and the fifth starts the cursor,
+        // the witness follows the tree of all five leaves.
+        let root_of_five = subtree_root(&leaves, INCREMENTAL_MERKLE_TREE_DEPTH);
+        let cursor = IncrementalMerkleTree::with_fields(Some(leaves[4]), None, Vec::new());
+        let witness = SproutWitness::with_fields(tree, vec![leaves[3]], Some(cursor));
+        assert_eq!(witness.root::<SproutHasher>(), root_of_five);
+        let path = witness.path::<SproutHasher>().unwrap();
+        assert_eq!(path.root::<SproutHasher>(leaves[2]), root_of_five);
+    }
+
+    #[test]
+    fn test_incomplete_witness_has_no_path() {
+        let empty = SproutWitness::with_fields(IncrementalMerkleTree::new(), Vec::new(), None);
+        assert_eq!(empty.position(), None);
+        assert!(empty.path::<SproutHasher>().is_none());
+
+        let too_deep = IncrementalMerkleTree::with_fields(
+            Some(leaf(0)),
+            None,
+            vec![None; INCREMENTAL_MERKLE_TREE_DEPTH],
+        );
+        let witness = SproutWitness::with_fields(too_deep, Vec::new(), None);
+        assert!(witness.path::<SproutHasher>().is_none());
+    }
+
+    /// Reports the time taken to compute the root of a Sprout witness near
+    /// the start of its tree, with and without cached empty roots. Run with
+    /// `cargo test --release -- --ignored --nocapture empty_root_cache_speed`.
+    #[test]
+    #[ignore]
+    fn test_empty_root_cache_speed() {
+        /// The Sprout hash, recomputing each empty root from the leaf.
+        struct UncachedSproutHasher;
+
+        impl MerkleHasher for UncachedSproutHasher {
+            fn empty_leaf() -> u256 {
+                SproutHasher::empty_leaf()
+            }
+
+            fn combine(level: usize, left: &u256, right: &u256) -> u256 {
+                SproutHasher::combine(level, left, right)
+            }
+
+            fn empty_root(level: usize) -> u256 {
+                (0..level).fold(Self::empty_leaf(), |root, level| {
+                    Self::combine(level, &root, &root)
+                })
+            }
+        }
+
+        let tree = IncrementalMerkleTree::with_fields(Some(leaf(0)), None, Vec::new());
+        let witness = SproutWitness::with_fields(tree, Vec::new(), None);
+        let time = |root: &dyn Fn() -> u256| {
+            let start = std::time::Instant::now();
+            for _ in 0..10_000 {
+                std::hint::black_box(root());
+            }
+            start.elapsed()
+        };
+        let uncached = time(&|| witness.root::<UncachedSproutHasher>());
+        let cached = time(&|| witness.root::<SproutHasher>());
+        assert_eq!(
+            witness.root::<SproutHasher>(),
+            witness.root::<UncachedSproutHasher>()
+        );
+
+        println!("10,000 roots: uncached {:?}, cached {:?}", uncached, cached);
+        assert!(cached < uncached);
+    }
+}
@@ -0,0 +1,149 @@
+use anyhow::Result;
+use bc_envelope::prelude::*;
+
+use crate::{LazyTransaction, Transaction, TxId};
+
+/// A transaction held by a [`Zewif`](crate::Zewif), either decoded or still in
+/// its envelope encoding.
+///
+/// A container holds each txid once, in one of the two forms. Transactions
+/// added by an importer are held decoded, and transactions read by
+/// [`Zewif::try_from_envelope_lazy`](crate::Zewif::try_from_envelope_lazy) are
+/// held lazily until [`get`](Self::get) or [`get_mut`](Self::get_mut) decodes
+/// them.
+///
+/// Two stored transactions are equal when they have the same content,
+/// whichever form each is held in.
+#[derive(Debug, Clone)]
+pub enum StoredTransaction {
+    /// A decoded transaction.
+    Eager(Transaction),
+
+    /// A transaction decoded on first access.
+    Lazy(LazyTransaction),
+}
+
+impl StoredTransaction {
+    /// Returns the txid recorded in the transaction itself.
+    pub fn txid(&self) -> TxId {
+        match self {
+            StoredTransaction::Eager(transaction) => transaction.txid(),
+            StoredTransaction::Lazy(lazy) => lazy.txid(),
+        }
+    }
+
+    /// Returns `true` if the transaction is available without decoding.
+    pub fn is_decoded(&self) -> bool {
+        match self {
+            StoredTransaction::Eager(_) => true,
+            StoredTransaction::Lazy(lazy) => lazy.is_decoded(),
+        }
+    }
+
+    /// Returns the transaction, decoding it first if it is held lazily.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode.
+    pub fn get(&self) -> Result<&Transaction> {
+        match self {
+            StoredTransaction::Eager(transaction) => Ok(transaction),
+            StoredTransaction::Lazy(lazy) => lazy.get(),
+        }
+    }
+
+    /// Returns the transaction for modification, decoding it first if it is
+    /// held lazily.
+    ///
+    /// A lazily held transaction is decoded before it is replaced by its
+    /// decoded form, so that it is re-encoded with any changes. If decoding
+    /// fails, the entry is left as it was.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode.
+    pub fn get_mut(&mut self) -> Result<&mut Transaction> {
+        if let StoredTransaction::Lazy(lazy) = self {
+            *self = StoredTransaction::Eager(lazy.get()?.clone());
+        }
+        match self {
+            StoredTransaction::Eager(transaction) => Ok(transaction),
+            StoredTransaction::Lazy(_) => unreachable!("lazy entry was just decoded"),
+        }
+    }
+
+    /// Consumes the entry, returning the decoded transaction.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode.
+    pub fn into_transaction(self) -> Result<Transaction> {
+        match self {
+            StoredTransaction::Eager(transaction) => Ok(transaction),
+            StoredTransaction::Lazy(lazy) => lazy.into_transaction(),
+        }
+    }
+}
+
+impl PartialEq for StoredTransaction {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (StoredTransaction::Eager(a), StoredTransaction::Eager(b)) => a == b,
+            (StoredTransaction::Lazy(a), StoredTransaction::Lazy(b)) => a == b,
+            (StoredTransaction::Eager(eager), StoredTransaction::Lazy(lazy))
+            | (StoredTransaction::Lazy(lazy), StoredTransaction::Eager(eager)) => {
+                Envelope::from(eager.clone()).digest() == lazy.envelope().digest()
+            }
+        }
+    }
+}
+
+impl From<Transaction> for StoredTransaction {
+    fn from(transaction: Transaction) -> Self {
+        StoredTransaction::Eager(transaction)
+    }
+}
+
+impl From<LazyTransaction> for StoredTransaction {
+    fn from(lazy: LazyTransaction) -> Self {
+        StoredTransaction::Lazy(lazy)
+    }
+}
+
+impl From<StoredTransaction> for Envelope {
+    fn from(value: StoredTransaction) -> Self {
+        match value {
+            StoredTransaction::Eager(transaction) => Envelope::from(transaction),
+            StoredTransaction::Lazy(lazy) => Envelope::from(lazy),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use super::StoredTransaction;
+    use crate::{LazyTransaction, RandomInstance, Transaction};
+
+    #[test]
+    fn test_eager_equals_lazy() {
+        let transaction = Transaction::random();
+        let eager = StoredTransaction::from(transaction.clone());
+        let lazy = StoredTransaction::from(
+            LazyTransaction::new(Envelope::from(transaction.clone())).unwrap(),
+        );
+        assert!(!lazy.is_decoded());
+        assert_eq!(eager, lazy);
+        assert_eq!(lazy, eager);
+        assert_eq!(lazy.get().unwrap(), &transaction);
+    }
+
+    #[test]
+    fn test_get_mut_keeps_undecodable_entry() {
+        // A Transaction envelope whose body does not decode.
+        let transaction = Transaction::random();
+        let broken = Envelope::from(transaction.clone()).add_assertion("mined_height", "high");
+        let mut entry = StoredTransaction::from(LazyTransaction::new(broken).unwrap());
+        assert!(entry.get_mut().is_err());
+        assert!(matches!(entry, StoredTransaction::Lazy(_)));
+        assert_eq!(entry.txid(), transaction.txid());
+    }
+}
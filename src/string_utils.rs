@@ -26,12 +26,24 @@
 /// assert_eq!(format_with_underscores(1_u64), "1");
 /// ```
 pub fn format_with_underscores(amount: impl Into<u64>) -> String {
-    let s = amount.into().to_string();
+    group_digits(&amount.into().to_string(), '_')
+}
+
+/// Inserts `separator` between each group of three digits in a string of decimal digits.
+///
+/// # Examples
+/// ```
+/// # use zewif::group_digits;
+/// #
+/// assert_eq!(group_digits("1234567", ','), "1,234,567");
+/// assert_eq!(group_digits("123", ','), "123");
+/// ```
+pub fn group_digits(digits: &str, separator: char) -> String {
     let mut result = String::new();
 
-    for (i, c) in s.chars().rev().enumerate() {
+    for (i, c) in digits.chars().rev().enumerate() {
         if i > 0 && i % 3 == 0 {
-            result.push('_');
+            result.push(separator);
         }
         result.push(c);
     }
@@ -55,3 +55,21 @@ macro_rules! test_cbor_roundtrip {
         }
     };
 }
+
+/// Like `test_envelope_roundtrip!`, but also checks the type's `EnvelopeSchema`:
+/// assertion order does not matter, each required predicate is enforced, and
+/// unknown predicates are ignored (or preserved, for types with attachments) by
+/// ordinary decoding but rejected by strict decoding.
+#[macro_export]
+macro_rules! test_envelope_roundtrip_strict {
+    ($type:ty) => {
+        test_envelope_roundtrip_strict!($type, 20);
+    };
+
+    ($type:ty, $iterations:expr) => {
+        #[test]
+        fn test_envelope_strict() {
+            $crate::test_envelope_roundtrip_strict::<$type>($iterations);
+        }
+    };
+}
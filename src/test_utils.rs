@@ -1,5 +1,7 @@
 use bc_envelope::prelude::*;
-use std::collections::{HashMap, HashSet};
+
+use crate::EnvelopeSchema;
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 pub trait RandomInstance {
     fn random() -> Self;
@@ -49,6 +51,13 @@ impl RandomInstance for u32 {
     }
 }
 
+impl RandomInstance for u64 {
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        u64::from_le_bytes(bc_rand::rng_random_array(&mut rng))
+    }
+}
+
 impl RandomInstance for usize {
     fn random() -> Self {
         let mut rng = bc_rand::thread_rng();
@@ -96,6 +105,18 @@ where
     }
 }
 
+impl<K, V> RandomInstance for BTreeMap<K, V>
+where
+    K: RandomInstance + Ord,
+    V: RandomInstance,
+{
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        let len = rand::Rng::gen_range(&mut rng, 1..=10);
+        (0..len).map(|_| (K::random(), V::random())).collect()
+    }
+}
+
 impl<T> RandomInstance for HashSet<T>
 where
     T: RandomInstance + std::hash::Hash + Eq + Clone,
@@ -172,6 +193,71 @@ where
     }
 }
 
+/// Extends [`test_envelope_roundtrip`] with checks that decoding depends only on
+/// the set of assertions present.
+///
+/// For each random value this checks that:
+/// - an envelope rebuilt with its assertions added in a shuffled order decodes
+///   to the same value,
+/// - removing any one of the schema's required predicates makes decoding fail,
+/// - an unrecognized extra predicate is ignored, or preserved for re-encoding
+///   by types with attachments, but rejected by
+///   [`EnvelopeSchema::decode_strict`].
+pub fn test_envelope_roundtrip_strict<T>(iterations: usize)
+where
+    T: RandomInstance
+        + EnvelopeSchema
+        + Into<Envelope>
+        + TryFrom<Envelope, Error = anyhow::Error>
+        + Clone
+        + std::fmt::Debug
+        + PartialEq,
+{
+    test_envelope_roundtrip::<T>(iterations, false);
+    let mut rng = bc_rand::thread_rng();
+    for _ in 0..iterations {
+        let value = T::random();
+        let envelope: Envelope = value.clone().into();
+
+        let mut assertions = envelope.assertions();
+        rand::seq::SliceRandom::shuffle(assertions.as_mut_slice(), &mut rng);
+        let reordered = envelope
+            .subject()
+            .add_assertion_envelopes(&assertions)
+            .unwrap();
+        assert_eq!(T::try_from(reordered).unwrap(), value);
+        assert_eq!(T::decode_strict(envelope.clone()).unwrap(), value);
+
+        for predicate in T::required_predicates() {
+            let matching = envelope.assertions_with_predicate(*predicate);
+            assert!(
+                !matching.is_empty(),
+                "required predicate {} is not encoded",
+                predicate
+            );
+            let missing = matching.into_iter().fold(envelope.clone(), |e, assertion| {
+                e.remove_assertion(assertion)
+            });
+            assert!(
+                T::try_from(missing).is_err(),
+                "decoding succeeded without required predicate {}",
+                predicate
+            );
+        }
+
+        // Types with attachments preserve unrecognized assertions for
+        // re-encoding, while the others ignore them.
+        let extended = envelope.add_assertion("unrecognized_predicate", "preserved");
+        let decoded = T::try_from(extended.clone()).unwrap();
+        if T::ATTACHMENTS {
+            assert!(decoded.into().is_identical_to(&extended));
+        } else {
+            assert_eq!(decoded, value);
+        }
+        assert!(T::decode_strict(extended).is_err());
+    }
+}
+
 impl RandomInstance for Attachments {
     fn random() -> Self {
         let mut attachments = Attachments::new();
@@ -1,5 +1,8 @@
 use super::{BlockHeight, Data, TxId};
-use crate::TxBlockPosition;
+use crate::{
+    Anchor, TxBlockPosition, TxHeader, decode_attachments, encode_attachments, hash256,
+    parser::prelude::*,
+};
 use anyhow::{Context, Result};
 use bc_envelope::prelude::*;
 
@@ -34,6 +37,7 @@ use bc_envelope::prelude::*;
 /// - **Blockchain Context**: Block height, timestamp, block hash when available
 /// - **Status Information**: Whether the transaction is pending, confirmed, failed, or abandoned
 /// - **Raw Transaction**: Optional full binary transaction data
+/// - **Label**: Any user-assigned label for the transaction
 /// - **Protocol-Specific Components**:
 ///   - Transparent inputs and outputs
 ///   - Sapling spends and outputs
@@ -67,6 +71,12 @@ pub struct Transaction {
     /// The hash of the block containing the transaction and the index of the transaction within
     /// the block, if known.
     block_position: Option<TxBlockPosition>,
+    /// A user-assigned label for the transaction, if any.
+    label: Option<String>,
+    /// The anchor each Sapling spend proved membership against, in spend order.
+    sapling_anchors: Vec<Anchor>,
+    /// The anchor shared by all Orchard actions in the transaction, if any.
+    orchard_anchor: Option<Anchor>,
     /// Additional arbitrary metadata related to the transaction.
     attachments: Attachments,
 }
@@ -81,6 +91,9 @@ impl Transaction {
             target_height: None,
             mined_height: None,
             block_position: None,
+            label: None,
+            sapling_anchors: Vec::new(),
+            orchard_anchor: None,
             attachments: Attachments::new(),
         }
     }
@@ -124,18 +137,99 @@ impl Transaction {
     pub fn set_block_position(&mut self, block_position: Option<TxBlockPosition>) {
         self.block_position = block_position;
     }
+
+    pub fn label(&self) -> Option<&str> {
+        self.label.as_deref()
+    }
+
+    pub fn set_label(&mut self, label: impl Into<String>) {
+        self.label = Some(label.into());
+    }
+
+    /// Returns the anchors of the transaction's Sapling spends, in spend order.
+    ///
+    /// Transactions before v5 record an anchor for every spend, while v5
+    /// transactions share one anchor across all of their Sapling spends; a
+    /// migration records the anchor once per spend in either case.
+    pub fn sapling_anchors(&self) -> &[Anchor] {
+        &self.sapling_anchors
+    }
+
+    /// Appends the anchor of the transaction's next Sapling spend.
+    pub fn add_sapling_anchor(&mut self, anchor: Anchor) {
+        self.sapling_anchors.push(anchor);
+    }
+
+    /// Returns the anchor of the transaction's Orchard actions, if it has any.
+    pub fn orchard_anchor(&self) -> Option<&Anchor> {
+        self.orchard_anchor.as_ref()
+    }
+
+    pub fn set_orchard_anchor(&mut self, anchor: Option<Anchor>) {
+        self.orchard_anchor = anchor;
+    }
+
+    /// Returns `true` if any of the transaction's shielded spends proved
+    /// membership against `anchor`.
+    ///
+    /// This lets tooling check a witness held by a wallet against the tree
+    /// states the wallet's transactions actually spent from.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Anchor, Transaction, TxId, u256};
+    /// let anchor = Anchor::from(u256::try_from(&[1u8; 32]).unwrap());
+    /// let mut tx = Transaction::new(TxId::from_bytes([0u8; 32]));
+    /// assert!(!tx.spends_from_anchor(&anchor));
+    /// tx.add_sapling_anchor(anchor);
+    /// assert!(tx.spends_from_anchor(&anchor));
+    /// ```
+    pub fn spends_from_anchor(&self, anchor: &Anchor) -> bool {
+        self.sapling_anchors.contains(anchor) || self.orchard_anchor.as_ref() == Some(anchor)
+    }
+
+    /// Reads the header of a serialized transaction of `len` bytes without
+    /// decoding its body.
+    ///
+    /// The transaction starts at the parser's current offset, and its length
+    /// must be known to the caller, as it is for a wallet record holding one
+    /// transaction, since finding it would mean decoding the body. On success
+    /// the parser is advanced by exactly `len` bytes, past the transaction and
+    /// onto whatever follows it.
+    ///
+    /// For v1 through v4 transactions the txid is the double SHA-256 of the
+    /// serialized bytes and is computed here. The txid of a v5 transaction is
+    /// defined by ZIP 244 as a BLAKE2b digest tree over the decoded body, so it
+    /// is left as `None`.
+    ///
+    /// # Errors
+    /// Returns an error if fewer than `len` bytes remain, or if the header is
+    /// malformed or does not fit in `len` bytes.
+    pub fn parse_header(p: &mut Parser, len: usize) -> Result<TxHeader> {
+        let bytes = p.next(len).context("serialized transaction")?;
+        let mut header = TxHeader::parse(&mut Parser::new(&bytes)).context("transaction header")?;
+        if header.version() < 5 {
+            let digest = hash256(bytes);
+            header.set_txid(TxId::from_bytes(*digest.as_ref()));
+        }
+        Ok(header)
+    }
 }
 
 #[rustfmt::skip]
 impl From<Transaction> for Envelope {
     fn from(value: Transaction) -> Self {
+        let sapling_anchors = (!value.sapling_anchors.is_empty()).then_some(value.sapling_anchors);
         let e = Envelope::new(value.txid)
             .add_type("Transaction")
             .add_optional_assertion("raw", value.raw)
             .add_optional_assertion("target_height", value.target_height)
             .add_optional_assertion("mined_height", value.mined_height)
-            .add_optional_assertion("block_position", value.block_position.map(CBOR::from));
-        value.attachments.add_to_envelope(e)
+            .add_optional_assertion("block_position", value.block_position)
+            .add_optional_assertion("label", value.label)
+            .add_optional_assertion("sapling_anchors", sapling_anchors)
+            .add_optional_assertion("orchard_anchor", value.orchard_anchor);
+        encode_attachments(&value.attachments, e)
     }
 }
 
@@ -157,7 +251,16 @@ impl TryFrom<Envelope> for Transaction {
         let block_position = envelope
             .try_optional_object_for_predicate("block_position")
             .context("block_position")?;
-        let attachments = Attachments::try_from_envelope(&envelope).context("attachments")?;
+        let label = envelope
+            .try_optional_object_for_predicate("label")
+            .context("label")?;
+        let sapling_anchors: Option<Vec<Anchor>> = envelope
+            .extract_optional_object_for_predicate("sapling_anchors")
+            .context("sapling_anchors")?;
+        let orchard_anchor = envelope
+            .try_optional_object_for_predicate("orchard_anchor")
+            .context("orchard_anchor")?;
+        let attachments = decode_attachments::<Self>(&envelope).context("attachments")?;
 
         Ok(Self {
             txid,
@@ -165,11 +268,35 @@ impl TryFrom<Envelope> for Transaction {
             target_height,
             mined_height,
             block_position,
+            label,
+            sapling_anchors: sapling_anchors.unwrap_or_default(),
+            orchard_anchor,
             attachments,
         })
     }
 }
 
+impl crate::EnvelopeSchema for Transaction {
+    const TYPE_NAME: &'static str = "Transaction";
+    const ATTACHMENTS: bool = true;
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &[
+            "raw",
+            "target_height",
+            "mined_height",
+            "block_position",
+            "label",
+            "sapling_anchors",
+            "orchard_anchor",
+        ]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for Transaction {
     fn random() -> Self {
@@ -179,6 +306,11 @@ impl crate::RandomInstance for Transaction {
             target_height: BlockHeight::opt_random(),
             mined_height: BlockHeight::opt_random(),
             block_position: TxBlockPosition::opt_random(),
+            label: String::opt_random(),
+            sapling_anchors: (0..rand::Rng::gen_range(&mut rand::thread_rng(), 0..=2))
+                .map(|_| Anchor::random())
+                .collect(),
+            orchard_anchor: Anchor::opt_random(),
             attachments: Attachments::random(),
         }
     }
@@ -191,3 +323,102 @@ mod test_envelope {
 
     test_envelope_roundtrip!(Transaction);
 }
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Anchor, RandomInstance, TxId,
+        parser::prelude::*,
+        sapling::{SaplingAnchorWitness, SaplingWitness},
+    };
+
+    use super::Transaction;
+
+    // The coinbase transaction of the Bitcoin genesis block, which shares the
+    // Zcash v1 transaction format.
+    const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+    const GENESIS_COINBASE_TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+
+    #[test]
+    fn test_parse_header_txid() {
+        let bytes = hex::decode(GENESIS_COINBASE).unwrap();
+        let mut p = Parser::new(&bytes);
+        let header = Transaction::parse_header(&mut p, bytes.len()).unwrap();
+        assert!(!header.is_overwintered());
+        assert_eq!(header.version(), 1);
+        assert_eq!(header.version_group_id(), None);
+        assert_eq!(header.txid(), Some(TxId::from_hex(GENESIS_COINBASE_TXID).unwrap()));
+        p.check_finished().unwrap();
+    }
+
+    #[test]
+    fn test_parse_header_leaves_following_data() {
+        let transaction = hex::decode(GENESIS_COINBASE).unwrap();
+        let trailer = [0xaa, 0xbb, 0xcc];
+        let bytes = [&transaction[..], &transaction, &trailer].concat();
+        let txid = TxId::from_hex(GENESIS_COINBASE_TXID).unwrap();
+
+        let mut p = Parser::new(&bytes);
+        for _ in 0..2 {
+            let header = Transaction::parse_header(&mut p, transaction.len()).unwrap();
+            assert_eq!(header.txid(), Some(txid));
+        }
+        assert_eq!(p.remaining(), trailer.len());
+
+        // A length running past the end of the buffer is rejected.
+        let mut p = Parser::new(&transaction);
+        assert!(Transaction::parse_header(&mut p, transaction.len() + 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_header_v5() {
+        // v5 header, NU5 version group, NU5 branch ID, lock time, expiry height
+        let bytes = hex::decode("050000800a27a726b4d0d6c20000000000000000").unwrap();
+        let mut p = Parser::new(&bytes);
+        let header = Transaction::parse_header(&mut p, bytes.len()).unwrap();
+        assert!(header.is_overwintered());
+        assert_eq!(header.version(), 5);
+        assert_eq!(header.version_group_id(), Some(0x26A7270A));
+        assert_eq!(u32::from(header.consensus_branch_id().unwrap()), 0xC2D6D0B4);
+        assert_eq!(header.txid(), None);
+    }
+
+    #[test]
+    fn test_anchors_roundtrip() {
+        let mut tx = Transaction::new(TxId::from_bytes([3u8; 32]));
+        let first = Anchor::random();
+        let second = Anchor::random();
+        tx.add_sapling_anchor(first);
+        tx.add_sapling_anchor(second);
+        tx.add_sapling_anchor(first);
+        tx.set_orchard_anchor(Some(second));
+
+        let decoded = Transaction::try_from(Envelope::from(tx.clone())).unwrap();
+        assert_eq!(decoded.sapling_anchors(), &[first, second, first]);
+        assert_eq!(decoded.orchard_anchor(), Some(&second));
+        assert_eq!(decoded, tx);
+
+        // A transaction without shielded spends records no anchors.
+        let empty = Transaction::new(TxId::from_bytes([3u8; 32]));
+        let envelope = Envelope::from(empty.clone());
+        assert!(
+            envelope
+                .assertions_with_predicate("sapling_anchors")
+                .is_empty()
+        );
+        assert_eq!(Transaction::try_from(envelope).unwrap(), empty);
+    }
+
+    #[test]
+    fn test_witness_anchor_match() {
+        let witness = SaplingAnchorWitness::new(Anchor::random(), SaplingWitness::random());
+        let mut tx = Transaction::new(TxId::from_bytes([3u8; 32]));
+        tx.add_sapling_anchor(Anchor::random());
+        assert!(!tx.spends_from_anchor(witness.anchor()));
+
+        tx.add_sapling_anchor(*witness.anchor());
+        assert!(tx.spends_from_anchor(witness.anchor()));
+    }
+}
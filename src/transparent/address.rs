@@ -1,8 +1,9 @@
-use crate::DerivationInfo;
+use crate::{DerivationInfo, Network};
 
 use super::TransparentSpendAuthority;
-use anyhow::Context;
+use anyhow::{Context, Result, bail};
 use bc_envelope::prelude::*;
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
 
 /// A transparent address on the Zcash network.
 ///
@@ -62,6 +63,9 @@ pub struct Address {
     /// Optional HD wallet derivation information
     /// When present, this contains the path information for HD wallets
     derivation_info: Option<DerivationInfo>,
+
+    /// The 20-byte hash decoded from `address`, or empty if it does not decode.
+    raw: Vec<u8>,
 }
 
 impl Address {
@@ -71,6 +75,10 @@ impl Address {
     /// address string. Spending authority and derivation information can
     /// be added later if available.
     ///
+    /// The string is not checked: it is stored as given even if it is not a
+    /// valid transparent address. Use [`decode`](Self::decode) to validate an
+    /// address against the network it is expected to belong to.
+    ///
     /// # Arguments
     /// * `address` - The transparent address string (e.g., "t1...")
     ///
@@ -81,13 +89,94 @@ impl Address {
     /// assert_eq!(address.address(), "t1exampleaddress");
     /// ```
     pub fn new(address: impl Into<String>) -> Self {
+        let address = address.into();
         Address {
-            address: address.into(),
+            raw: decode_hash(&address),
+            address,
             spend_authority: None,
             derivation_info: None,
         }
     }
 
+    /// Creates a P2PKH transparent address by Base58Check-encoding a public key hash.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, transparent};
+    /// let address = transparent::Address::from_pubkey_hash(Network::Main, &[0u8; 20]);
+    /// assert!(address.address().starts_with("t1"));
+    /// ```
+    pub fn from_pubkey_hash(network: Network, hash: &[u8; 20]) -> Self {
+        let prefix = NetworkType::from(network).b58_pubkey_address_prefix();
+        Self::new(base58check_encode(&prefix, hash))
+    }
+
+    /// Creates a P2SH transparent address by Base58Check-encoding a script hash.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, transparent};
+    /// let address = transparent::Address::from_script_hash(Network::Main, &[0u8; 20]);
+    /// assert!(address.address().starts_with("t3"));
+    /// ```
+    pub fn from_script_hash(network: Network, hash: &[u8; 20]) -> Self {
+        let prefix = NetworkType::from(network).b58_script_address_prefix();
+        Self::new(base58check_encode(&prefix, hash))
+    }
+
+    /// Decodes and validates a Base58Check transparent address for `network`.
+    ///
+    /// The two version bytes that begin the payload identify both the address
+    /// kind (P2PKH or P2SH) and the network, so an address is rejected unless
+    /// its version bytes are those `network` uses. Testnet and regtest share
+    /// their version bytes, so each accepts the other's addresses.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not valid Base58Check, does not carry a
+    /// 20-byte hash, or has version bytes that belong to another network or to
+    /// no known network.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, transparent};
+    /// let encoded = "t1Hsc1LR8yKnbbe3twRp88p6vFfC5t7DLbs";
+    /// let address = transparent::Address::decode(encoded, Network::Main).unwrap();
+    /// assert_eq!(address.hash(), Some([0u8; 20]));
+    /// assert!(transparent::Address::decode(encoded, Network::Test).is_err());
+    /// ```
+    pub fn decode(address: &str, network: Network) -> Result<Self> {
+        let decoded = bs58::decode(address)
+            .with_check(None)
+            .into_vec()
+            .context("transparent address Base58Check encoding")?;
+        if decoded.len() != 22 {
+            bail!(
+                "Transparent address payload must be 22 bytes, got {}",
+                decoded.len()
+            );
+        }
+        let prefix = [decoded[0], decoded[1]];
+        if !is_transparent_prefix(network, &prefix) {
+            match [Network::Main, Network::Test]
+                .into_iter()
+                .find(|other| is_transparent_prefix(*other, &prefix))
+            {
+                Some(other) => bail!(
+                    "Transparent address {} is for the {} network, not {}",
+                    address,
+                    String::from(other),
+                    String::from(network)
+                ),
+                None => bail!(
+                    "Transparent address {} has unknown version bytes {}",
+                    address,
+                    hex::encode(prefix)
+                ),
+            }
+        }
+        Ok(Self::new(address))
+    }
+
     /// Returns the transparent address string.
     ///
     /// # Returns
@@ -96,6 +185,31 @@ impl Address {
         &self.address
     }
 
+    /// Decodes the 20-byte public key or script hash carried by this address.
+    ///
+    /// # Returns
+    /// - `Some([u8; 20])` if the address is a well-formed Base58Check transparent address
+    /// - `None` if the string cannot be decoded
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, transparent};
+    /// let address = transparent::Address::from_script_hash(Network::Test, &[7u8; 20]);
+    /// assert_eq!(address.hash(), Some([7u8; 20]));
+    /// assert_eq!(transparent::Address::new("not an address").hash(), None);
+    /// ```
+    pub fn hash(&self) -> Option<[u8; 20]> {
+        self.raw.as_slice().try_into().ok()
+    }
+
+    /// Returns the 20-byte hash carried by this address, without its version
+    /// bytes, or an empty slice if the string cannot be decoded.
+    ///
+    /// The hash is decoded once, when the address is created.
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
     /// Returns the spending authority for this address, if available.
     ///
     /// The spending authority contains the information needed to spend
@@ -121,6 +235,11 @@ impl Address {
         self.spend_authority = Some(spend_authority);
     }
 
+    /// Removes the spending authority, leaving the address watch-only.
+    pub fn clear_spend_authority(&mut self) {
+        self.spend_authority = None;
+    }
+
     /// Returns the HD wallet derivation information for this address, if available.
     ///
     /// For addresses derived from an HD wallet seed, this provides the path
@@ -145,6 +264,26 @@ impl Address {
     }
 }
 
+fn is_transparent_prefix(network: Network, prefix: &[u8; 2]) -> bool {
+    let network = NetworkType::from(network);
+    *prefix == network.b58_pubkey_address_prefix() || *prefix == network.b58_script_address_prefix()
+}
+
+fn base58check_encode(prefix: &[u8; 2], hash: &[u8; 20]) -> String {
+    bs58::encode([&prefix[..], &hash[..]].concat())
+        .with_check()
+        .into_string()
+}
+
+/// Decodes the hash from a Base58Check transparent address, whatever its
+/// version bytes, returning an empty vector if it is malformed.
+fn decode_hash(address: &str) -> Vec<u8> {
+    match bs58::decode(address).with_check(None).into_vec() {
+        Ok(mut decoded) if decoded.len() == 22 => decoded.split_off(2),
+        _ => Vec::new(),
+    }
+}
+
 impl From<Address> for Envelope {
     fn from(value: Address) -> Self {
         Envelope::new(value.address)
@@ -161,7 +300,7 @@ impl TryFrom<Envelope> for Address {
         envelope
             .check_type_envelope("TransparentAddress")
             .context("TransparentAddress")?;
-        let address = envelope.extract_subject().context("address")?;
+        let address: String = envelope.extract_subject().context("address")?;
         let spend_authority = envelope
             .try_optional_object_for_predicate("spend_authority")
             .context("spend_authority")?;
@@ -169,6 +308,7 @@ impl TryFrom<Envelope> for Address {
             .try_optional_object_for_predicate("derivation_info")
             .context("derivation_info")?;
         Ok(Address {
+            raw: decode_hash(&address),
             address,
             spend_authority,
             derivation_info,
@@ -176,13 +316,25 @@ impl TryFrom<Envelope> for Address {
     }
 }
 
+impl crate::EnvelopeSchema for Address {
+    const TYPE_NAME: &'static str = "TransparentAddress";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["spend_authority", "derivation_info"]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for Address {
     fn random() -> Self {
         Self {
-            address: String::random(),
             spend_authority: TransparentSpendAuthority::opt_random(),
             derivation_info: DerivationInfo::opt_random(),
+            ..Self::new(String::random())
         }
     }
 }
@@ -190,7 +342,42 @@ impl crate::RandomInstance for Address {
 #[cfg(test)]
 mod tests {
     use super::Address;
-    use crate::test_envelope_roundtrip;
+    use crate::{Network, test_envelope_roundtrip};
 
     test_envelope_roundtrip!(Address);
+
+    #[test]
+    fn test_decode_matching_network() {
+        for network in [Network::Main, Network::Test, Network::Regtest] {
+            for address in [
+                Address::from_pubkey_hash(network, &[1u8; 20]),
+                Address::from_script_hash(network, &[2u8; 20]),
+            ] {
+                let decoded = Address::decode(address.address(), network).unwrap();
+                assert_eq!(decoded, address);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_network_mismatch() {
+        let testnet = Address::from_pubkey_hash(Network::Test, &[1u8; 20]);
+        let error = Address::decode(testnet.address(), Network::Main).unwrap_err();
+        assert!(
+            error
+                .to_string()
+                .contains("is for the test network, not main")
+        );
+
+        let mainnet = Address::from_script_hash(Network::Main, &[2u8; 20]);
+        assert!(Address::decode(mainnet.address(), Network::Test).is_err());
+        assert!(Address::decode(mainnet.address(), Network::Regtest).is_err());
+
+        // Valid Base58Check with version bytes no Zcash network uses.
+        let bitcoin = bs58::encode([&[0x00][..], &[0u8; 21]].concat())
+            .with_check()
+            .into_string();
+        assert!(Address::decode(&bitcoin, Network::Main).is_err());
+        assert!(Address::decode("t1notbase58check", Network::Main).is_err());
+    }
 }
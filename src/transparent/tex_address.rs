@@ -0,0 +1,234 @@
+use anyhow::{Context, Result, bail};
+use bc_envelope::prelude::*;
+use bech32::{Bech32m, Hrp, primitives::decode::CheckedHrpstring};
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
+
+use crate::{DerivationInfo, Network};
+
+/// A transparent-source-only (TEX) address, as defined by
+/// [ZIP 320](https://zips.z.cash/zip-0320).
+///
+/// A TEX address carries the same 20-byte public key hash as a P2PKH
+/// [`Address`](super::Address), but Bech32m-encoded with the `tex` HRP (`textest`
+/// on testnet, `texregtest` on regtest). Its holder asks that funds sent to it
+/// come only from transparent inputs, which exchanges use to keep the origin of
+/// deposits visible. A wallet that sends to such an address must honour that
+/// restriction, so ZeWIF keeps TEX addresses distinct from ordinary transparent
+/// ones instead of transcoding them to the P2PKH form.
+///
+/// # Data Preservation
+/// The address string is stored exactly as read, together with any HD
+/// derivation information for an address the wallet derived itself.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Network, transparent::{self, TexAddress}};
+/// let tex = TexAddress::decode("tex1s2rt77ggv6q989lr49rkgzmh5slsksa9khdgte", Network::Main).unwrap();
+/// let p2pkh = transparent::Address::decode("t1VmmGiyjVNeCjxDZzg7vZmd99WyzVby9yC", Network::Main).unwrap();
+/// assert_eq!(tex.pubkey_hash(), p2pkh.hash());
+/// assert_eq!(TexAddress::from_pubkey_hash(Network::Main, &p2pkh.hash().unwrap()), tex);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TexAddress {
+    /// The Bech32m-encoded address string, used as a unique identifier within
+    /// the wallet.
+    address: String,
+
+    /// Optional HD wallet derivation information.
+    derivation_info: Option<DerivationInfo>,
+
+    /// The 20-byte hash decoded from `address`, or empty if it does not decode.
+    raw: Vec<u8>,
+}
+
+impl TexAddress {
+    /// Creates a TEX address from its string, without checking it.
+    ///
+    /// Use [`decode`](Self::decode) to validate an address against the network
+    /// it is expected to belong to.
+    pub fn new(address: impl Into<String>) -> Self {
+        let address = address.into();
+        Self {
+            raw: decode_hash(&address),
+            address,
+            derivation_info: None,
+        }
+    }
+
+    /// Creates a TEX address by Bech32m-encoding a public key hash.
+    pub fn from_pubkey_hash(network: Network, hash: &[u8; 20]) -> Self {
+        let hrp =
+            Hrp::parse(NetworkType::from(network).hrp_tex_address()).expect("TEX HRPs are valid");
+        let address = bech32::encode::<Bech32m>(hrp, hash)
+            .expect("TEX addresses are within the length limit");
+        Self::new(address)
+    }
+
+    /// Decodes and validates a TEX address for `network`.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not valid Bech32m, has the HRP of
+    /// another network, or does not carry a 20-byte public key hash.
+    pub fn decode(address: &str, network: Network) -> Result<Self> {
+        let checked =
+            CheckedHrpstring::new::<Bech32m>(address).context("TEX address Bech32m encoding")?;
+        let expected = NetworkType::from(network).hrp_tex_address();
+        if checked.hrp().to_lowercase() != expected {
+            bail!(
+                "TEX address {} has HRP \"{}\", but the {} network uses \"{}\"",
+                address,
+                checked.hrp(),
+                String::from(network),
+                expected
+            );
+        }
+        let len = checked.byte_iter().len();
+        if len != 20 {
+            bail!("TEX address payload must be 20 bytes, got {}", len);
+        }
+        Ok(Self::new(address))
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Decodes the 20-byte public key hash carried by this address.
+    ///
+    /// # Returns
+    /// - `Some([u8; 20])` if the address is a well-formed TEX address
+    /// - `None` if the string cannot be decoded
+    pub fn pubkey_hash(&self) -> Option<[u8; 20]> {
+        self.raw.as_slice().try_into().ok()
+    }
+
+    /// Returns the 20-byte public key hash carried by this address, or an
+    /// empty slice if the string cannot be decoded.
+    ///
+    /// The hash is decoded once, when the address is created.
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
+    /// Returns the network named by the address's HRP, if it is a TEX HRP.
+    pub fn network(&self) -> Option<Network> {
+        let hrp = CheckedHrpstring::new::<Bech32m>(&self.address)
+            .ok()?
+            .hrp()
+            .to_lowercase();
+        [Network::Main, Network::Test, Network::Regtest]
+            .into_iter()
+            .find(|network| NetworkType::from(*network).hrp_tex_address() == hrp)
+    }
+
+    pub fn derivation_info(&self) -> Option<&DerivationInfo> {
+        self.derivation_info.as_ref()
+    }
+
+    pub fn set_derivation_info(&mut self, derivation_info: DerivationInfo) {
+        self.derivation_info = Some(derivation_info);
+    }
+}
+
+/// Decodes the public key hash from a Bech32m TEX address, returning an empty
+/// vector if it is malformed.
+fn decode_hash(address: &str) -> Vec<u8> {
+    CheckedHrpstring::new::<Bech32m>(address)
+        .map(|checked| checked.byte_iter().collect::<Vec<u8>>())
+        .ok()
+        .filter(|hash| hash.len() == 20)
+        .unwrap_or_default()
+}
+
+impl From<TexAddress> for Envelope {
+    fn from(value: TexAddress) -> Self {
+        Envelope::new(value.address)
+            .add_type("TexAddress")
+            .add_optional_assertion("derivation_info", value.derivation_info)
+    }
+}
+
+impl TryFrom<Envelope> for TexAddress {
+    type Error = anyhow::Error;
+
+    fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        envelope
+            .check_type_envelope("TexAddress")
+            .context("TexAddress")?;
+        let address: String = envelope.extract_subject().context("address")?;
+        let derivation_info = envelope
+            .try_optional_object_for_predicate("derivation_info")
+            .context("derivation_info")?;
+        Ok(Self {
+            raw: decode_hash(&address),
+            address,
+            derivation_info,
+        })
+    }
+}
+
+impl crate::EnvelopeSchema for TexAddress {
+    const TYPE_NAME: &'static str = "TexAddress";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["derivation_info"]
+    }
+}
+
+#[cfg(test)]
+impl crate::RandomInstance for TexAddress {
+    fn random() -> Self {
+        let mut rng = bc_rand::thread_rng();
+        let network = [Network::Main, Network::Test, Network::Regtest]
+            [rand::Rng::gen_range(&mut rand::thread_rng(), 0..3)];
+        Self {
+            derivation_info: DerivationInfo::opt_random(),
+            ..Self::from_pubkey_hash(network, &bc_rand::rng_random_array(&mut rng))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TexAddress;
+    use crate::{Network, test_envelope_roundtrip, transparent};
+
+    test_envelope_roundtrip!(TexAddress);
+
+    // The ZIP 320 example, from the zcash_address test suite.
+    const TEX: &str = "tex1s2rt77ggv6q989lr49rkgzmh5slsksa9khdgte";
+    const P2PKH: &str = "t1VmmGiyjVNeCjxDZzg7vZmd99WyzVby9yC";
+
+    #[test]
+    fn test_canonical_tex_roundtrip() {
+        let tex = TexAddress::decode(TEX, Network::Main).unwrap();
+        let hash = tex.pubkey_hash().unwrap();
+        assert_eq!(
+            TexAddress::from_pubkey_hash(Network::Main, &hash).address(),
+            TEX
+        );
+        assert_eq!(tex.network(), Some(Network::Main));
+
+        let p2pkh = transparent::Address::decode(P2PKH, Network::Main).unwrap();
+        assert_eq!(p2pkh.hash(), Some(hash));
+        assert_eq!(
+            transparent::Address::from_pubkey_hash(Network::Main, &hash).address(),
+            P2PKH
+        );
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        assert!(TexAddress::decode(TEX, Network::Test).is_err());
+        let testnet = TexAddress::from_pubkey_hash(Network::Test, &[1u8; 20]);
+        assert!(testnet.address().starts_with("textest1"));
+        assert_eq!(testnet.network(), Some(Network::Test));
+        assert!(TexAddress::decode(testnet.address(), Network::Test).is_ok());
+        assert!(TexAddress::decode(P2PKH, Network::Main).is_err());
+        assert!(TexAddress::decode("tex1invalid", Network::Main).is_err());
+    }
+}
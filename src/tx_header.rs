@@ -0,0 +1,107 @@
+use anyhow::{Result, bail};
+
+use crate::{BranchId, TxId, parse, parser::prelude::*};
+
+/// The fixed-layout header fields at the start of a serialized Zcash transaction.
+///
+/// `TxHeader` captures only the fields needed to classify a transaction (its
+/// version, version group, and for v5 its consensus branch), allowing indexers
+/// to avoid decoding the transaction body.
+///
+/// # Zcash Concept Relation
+/// Every Zcash transaction begins with a 32-bit header whose high bit is the
+/// `fOverwintered` flag and whose low 31 bits are the transaction version.
+/// Overwintered transactions (v3 and later) are followed by a version group ID;
+/// v5 transactions additionally commit to the consensus branch ID, lock time and
+/// expiry height up front.
+///
+/// The txid is only known when the header is read via
+/// [`Transaction::parse_header`](crate::Transaction::parse_header), which has
+/// access to the complete serialized transaction, and only for v1 through v4
+/// transactions; see [`txid`](Self::txid).
+///
+/// # Examples
+/// ```
+/// # use zewif::{TxHeader, parser::prelude::*};
+/// // A Sapling (v4) transaction header
+/// let bytes = [0x04, 0x00, 0x00, 0x80, 0x85, 0x20, 0x2f, 0x89];
+/// let mut p = Parser::new(&bytes);
+/// let header = TxHeader::parse(&mut p).unwrap();
+/// assert!(header.is_overwintered());
+/// assert_eq!(header.version(), 4);
+/// assert_eq!(header.version_group_id(), Some(0x892F2085));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TxHeader {
+    overwintered: bool,
+    version: u32,
+    version_group_id: Option<u32>,
+    consensus_branch_id: Option<BranchId>,
+    txid: Option<TxId>,
+}
+
+impl TxHeader {
+    /// The `fOverwintered` flag in the raw 32-bit header.
+    pub const OVERWINTERED_FLAG: u32 = 1 << 31;
+
+    pub fn is_overwintered(&self) -> bool {
+        self.overwintered
+    }
+
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+
+    pub fn version_group_id(&self) -> Option<u32> {
+        self.version_group_id
+    }
+
+    /// The consensus branch ID, present only in v5 and later transactions.
+    pub fn consensus_branch_id(&self) -> Option<BranchId> {
+        self.consensus_branch_id
+    }
+
+    /// The transaction ID, if it was computed while reading the header.
+    ///
+    /// This is `None` for a header read on its own with [`Parse`], and for a v5
+    /// or later transaction, whose ZIP 244 txid is computed from the decoded
+    /// body rather than from the serialized bytes.
+    pub fn txid(&self) -> Option<TxId> {
+        self.txid
+    }
+
+    pub(crate) fn set_txid(&mut self, txid: TxId) {
+        self.txid = Some(txid);
+    }
+}
+
+impl Parse for TxHeader {
+    fn parse(p: &mut Parser) -> Result<Self> {
+        let header = parse!(p, u32, "transaction header")?;
+        let overwintered = header & Self::OVERWINTERED_FLAG != 0;
+        let version = header & !Self::OVERWINTERED_FLAG;
+        if overwintered && version < 3 {
+            bail!("Overwintered transaction with invalid version {}", version);
+        }
+
+        let version_group_id = if overwintered {
+            Some(parse!(p, u32, "version group ID")?)
+        } else {
+            None
+        };
+
+        let consensus_branch_id = if overwintered && version >= 5 {
+            Some(parse!(p, BranchId, "consensus branch ID")?)
+        } else {
+            None
+        };
+
+        Ok(Self {
+            overwintered,
+            version,
+            version_group_id,
+            consensus_branch_id,
+            txid: None,
+        })
+    }
+}
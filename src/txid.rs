@@ -5,6 +5,7 @@ use bc_envelope::prelude::*;
 use std::{
     fmt,
     io::{self, Read, Write},
+    str::FromStr,
 };
 
 /// A transaction identifier (TxId) represented as a 32-byte hash.
@@ -58,6 +59,15 @@ impl fmt::Display for TxId {
     }
 }
 
+impl FromStr for TxId {
+    type Err = HexParseError;
+
+    /// Parses a `TxId` from its displayed form, as [`TxId::from_hex`] does.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::from_hex(s)
+    }
+}
+
 impl AsRef<[u8; 32]> for TxId {
     fn as_ref(&self) -> &[u8; 32] {
         &self.0
@@ -95,7 +105,9 @@ impl TxId {
     /// Creates a new `TxId` from a 32-byte array.
     ///
     /// This is the primary constructor for `TxId` when you have the raw transaction
-    /// hash available.
+    /// hash available. `bytes` are in internal order, the order in which the hash
+    /// is computed and serialized in transactions and wallet files, which is the
+    /// reverse of the order in which the `TxId` is displayed.
     ///
     /// # Examples
     /// ```
@@ -110,6 +122,12 @@ impl TxId {
 
     /// Parses a `TxId` from a canonically-encoded (byte-reversed) hexadecimal string.
     ///
+    /// This is the inverse of the `Display` implementation, and accepts txids as
+    /// shown by block explorers and RPC methods. The decoded bytes are reversed
+    /// into internal order, so `TxId::from_hex(h)` is `TxId::from_bytes(b)` for
+    /// the array `b` of the bytes of `h` in reverse. The same parsing is available
+    /// through `FromStr`.
+    ///
     /// # Examples
     /// ```
     /// # use zewif::TxId;
@@ -121,13 +139,13 @@ impl TxId {
     /// assert_eq!(blob.as_ref(), &expected);
     /// ```
     pub fn from_hex(hex: &str) -> Result<Self, HexParseError> {
-        let mut data = hex::decode(hex).map_err(|e| crate::HexParseError::HexInvalid(e))?;
+        let mut data = hex::decode(hex).map_err(crate::HexParseError::HexInvalid)?;
         data.reverse();
 
         Ok(Self(<[u8; 32]>::try_from(&data[..]).map_err(|_| {
             crate::HexParseError::SliceInvalid {
-                expected: 64,
-                actual: hex.len(),
+                expected: 32,
+                actual: data.len(),
             }
         })?))
     }
@@ -247,3 +265,29 @@ impl crate::RandomInstance for TxId {
 
 test_cbor_roundtrip!(TxId);
 test_envelope_roundtrip!(TxId);
+
+#[cfg(test)]
+mod tests {
+    use super::TxId;
+
+    #[test]
+    fn test_display_order() {
+        // The coinbase transaction of the Zcash mainnet genesis block.
+        let hex = "c4eaa58879081de3c24a7b117ed2b28300e7ec4c4c1dff1d3f1268b7857a4cf2";
+        let txid: TxId = hex.parse().unwrap();
+        assert_eq!(txid.to_string(), hex);
+        assert_eq!(TxId::from_hex(hex).unwrap(), txid);
+
+        let mut bytes: [u8; 32] = hex::decode(hex).unwrap().try_into().unwrap();
+        bytes.reverse();
+        assert_eq!(TxId::from_bytes(bytes), txid);
+        assert_eq!(txid.as_ref()[0], 0xf2);
+        assert_eq!(txid.as_ref()[31], 0xc4);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert!("c4eaa588".parse::<TxId>().is_err());
+        assert!(TxId::from_hex(&"zz".repeat(32)).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use anyhow::{Result, bail};
+
+use super::{
+    ParseOptions, Receiver, check_receiver_len, check_receivers,
+    decode_unified_address_with_options, encode_unified_address,
+};
+use crate::{Network, ReceiverType};
+
+/// A Unified Address decoded into its network and receivers.
+///
+/// [`UnifiedAddress`](crate::UnifiedAddress) stores a Unified Address as the
+/// string a wallet recorded, together with its derivation metadata. This type
+/// is the decoded form of such a string: Bech32m decoding, F4Jumble unpacking
+/// and removal of the HRP padding yield the typecode/length/value items, each
+/// kept as a [`ReceiverType`] with its raw bytes. Receivers of unknown types
+/// are preserved as [`ReceiverType::Unknown`] unless the [`ParseOptions`] ask
+/// for them to be rejected.
+///
+/// Wallet data should keep using `UnifiedAddress`, which is what ZeWIF
+/// serializes. Use this type only to work with the receivers of an address,
+/// and encode it back to a string to store the result.
+///
+/// # Examples
+/// ```
+/// # use zewif::{Network, ReceiverType, unified};
+/// let encoded = unified::encode_unified_address(
+///     Network::Main,
+///     &[(ReceiverType::P2PKH, vec![1u8; 20]), (ReceiverType::Orchard, vec![2u8; 43])],
+/// ).unwrap();
+///
+/// let address = unified::Address::parse(&encoded).unwrap();
+/// assert_eq!(address.network(), Network::Main);
+/// assert_eq!(address.receiver_types(), vec![ReceiverType::P2PKH, ReceiverType::Orchard]);
+/// assert_eq!(address.receiver(ReceiverType::Orchard), Some(&[2u8; 43][..]));
+/// assert_eq!(address.encode().unwrap(), encoded);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Address {
+    network: Network,
+    receivers: Vec<Receiver>,
+}
+
+impl Address {
+    /// Decodes a Unified Address string, preserving receivers of unknown types.
+    ///
+    /// # Errors
+    /// Returns an error if the string is not a well-formed Unified Address; see
+    /// [`decode_unified_address`](super::decode_unified_address).
+    pub fn parse(s: &str) -> Result<Self> {
+        Self::parse_with_options(s, &ParseOptions::default())
+    }
+
+    /// Decodes a Unified Address string, handling receivers of unknown types
+    /// as `options` direct.
+    pub fn parse_with_options(s: &str, options: &ParseOptions) -> Result<Self> {
+        let (network, receivers) = decode_unified_address_with_options(s, options)?;
+        Ok(Self { network, receivers })
+    }
+
+    /// Assembles an address for `network` from its receivers, given in any
+    /// order.
+    ///
+    /// The receivers are sorted into the canonical order given by
+    /// [`ReceiverType::cmp_priority`], so the result always encodes.
+    ///
+    /// # Errors
+    /// Returns an error if there are no receivers, if the receivers break a
+    /// ZIP 316 rule on which types may appear together (reported as a
+    /// [`UnifiedAddressError`](super::UnifiedAddressError)), or if a receiver's
+    /// data has the wrong length for its type.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, ReceiverType, unified};
+    /// let address = unified::Address::from_receivers(
+    ///     Network::Main,
+    ///     vec![(ReceiverType::Orchard, vec![2u8; 43]), (ReceiverType::P2PKH, vec![1u8; 20])],
+    /// ).unwrap();
+    /// assert_eq!(address.receiver_types(), vec![ReceiverType::P2PKH, ReceiverType::Orchard]);
+    /// assert!(address.encode().is_ok());
+    /// ```
+    pub fn from_receivers(network: Network, mut receivers: Vec<Receiver>) -> Result<Self> {
+        if receivers.is_empty() {
+            bail!("A Unified Address must contain at least one receiver");
+        }
+        receivers.sort_by(|(a, _), (b, _)| a.cmp_priority(b));
+        check_receivers(receivers.iter().map(|(receiver_type, _)| *receiver_type))?;
+        for (receiver_type, data) in &receivers {
+            check_receiver_len(*receiver_type, data)?;
+        }
+        Ok(Self { network, receivers })
+    }
+
+    /// Returns the network named by the address's human-readable part.
+    pub fn network(&self) -> Network {
+        self.network
+    }
+
+    /// Returns the receivers in the order they were encoded.
+    pub fn receivers(&self) -> &[Receiver] {
+        &self.receivers
+    }
+
+    /// Returns the types of the receivers in the order they were encoded.
+    pub fn receiver_types(&self) -> Vec<ReceiverType> {
+        self.receivers
+            .iter()
+            .map(|(receiver_type, _)| *receiver_type)
+            .collect()
+    }
+
+    /// Returns the raw bytes of the receiver of the given type, if present.
+    pub fn receiver(&self, receiver_type: ReceiverType) -> Option<&[u8]> {
+        self.receivers
+            .iter()
+            .find(|(t, _)| *t == receiver_type)
+            .map(|(_, data)| data.as_slice())
+    }
+
+    /// Encodes the address back into its string form.
+    ///
+    /// # Errors
+    /// Returns an error if the receivers break a ZIP 316 rule on which types
+    /// may appear together or in what order, which decoding does not check.
+    pub fn encode(&self) -> Result<String> {
+        encode_unified_address(self.network, &self.receivers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Address;
+    use crate::{
+        Network, ReceiverType,
+        unified::{ParseOptions, UnifiedAddressError, UnknownReceivers},
+    };
+
+    // Taken from the ZIP 316 test vectors in zcash-test-vectors'
+    // unified_address.py (account 1, diversifier index 3).
+    const MAINNET_UA: &str = "u1pg2aaph7jp8rpf6yhsza25722sg5fcn3vaca6ze27hqjw7jvvhhuxkpcg0ge9xh6drsgdkda8qjq5chpehkcpxf87rnjryjqwymdheptpvnljqqrjqzjwkc2ma6hcq666kgwfytxwac8eyex6ndgr6ezte66706e3vaqrd25dzvzkc69kw0jgywtd0cmq52q5lkw6uh7hyvzjse8ksx";
+    const P2PKH: &str = "cad268758c5e71493066446b98e71df9d1d6a5ca";
+    const SAPLING: &str =
+        "9f6e0bf90a18fc0b9b83ae9f23ad4358648638482b5def8975635b66fd8a708335f9235a3186ec0f033f84";
+    const ORCHARD: &str =
+        "cecbe5e689a453a3fe10ccf7617e6c1fb382819d7fc9200a1f42092ac84a30378f8c1fb90dff71a6d5042d";
+
+    fn bytes(hex: &str) -> Vec<u8> {
+        hex::decode(hex).unwrap()
+    }
+
+    #[test]
+    fn test_parse_mainnet_vector() {
+        let address = Address::parse(MAINNET_UA).unwrap();
+        assert_eq!(address.network(), Network::Main);
+        assert_eq!(
+            address.receiver_types(),
+            vec![
+                ReceiverType::P2PKH,
+                ReceiverType::Sapling,
+                ReceiverType::Orchard
+            ]
+        );
+        assert_eq!(
+            address.receiver(ReceiverType::P2PKH),
+            Some(&bytes(P2PKH)[..])
+        );
+        assert_eq!(
+            address.receiver(ReceiverType::Sapling),
+            Some(&bytes(SAPLING)[..])
+        );
+        assert_eq!(
+            address.receiver(ReceiverType::Orchard),
+            Some(&bytes(ORCHARD)[..])
+        );
+        assert_eq!(address.receiver(ReceiverType::P2SH), None);
+        assert_eq!(address.encode().unwrap(), MAINNET_UA);
+    }
+
+    #[test]
+    fn test_parse_unknown_receiver() {
+        let receivers = vec![
+            (ReceiverType::Orchard, vec![1u8; 43]),
+            (ReceiverType::Unknown(0x30), vec![2u8; 7]),
+        ];
+        let encoded = crate::unified::encode_unified_address(Network::Test, &receivers).unwrap();
+
+        let address = Address::parse(&encoded).unwrap();
+        assert_eq!(address.network(), Network::Test);
+        assert_eq!(address.receivers(), receivers.as_slice());
+        assert_eq!(
+            address.receiver(ReceiverType::Unknown(0x30)),
+            Some(&[2u8; 7][..])
+        );
+
+        let strict = ParseOptions::default().with_unknown_receivers(UnknownReceivers::Reject);
+        assert!(Address::parse_with_options(&encoded, &strict).is_err());
+        assert!(Address::parse("u1invalid").is_err());
+    }
+
+    #[test]
+    fn test_from_receivers_reorders() {
+        let address = Address::from_receivers(
+            Network::Main,
+            vec![
+                (ReceiverType::Orchard, bytes(ORCHARD)),
+                (ReceiverType::P2PKH, bytes(P2PKH)),
+                (ReceiverType::Sapling, bytes(SAPLING)),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            address.receiver_types(),
+            vec![
+                ReceiverType::P2PKH,
+                ReceiverType::Sapling,
+                ReceiverType::Orchard
+            ]
+        );
+        assert_eq!(address.encode().unwrap(), MAINNET_UA);
+        assert_eq!(address, Address::parse(MAINNET_UA).unwrap());
+    }
+
+    #[test]
+    fn test_from_receivers_rejects_duplicates() {
+        let error = Address::from_receivers(
+            Network::Main,
+            vec![
+                (ReceiverType::Sapling, bytes(SAPLING)),
+                (ReceiverType::P2PKH, bytes(P2PKH)),
+                (ReceiverType::Sapling, bytes(SAPLING)),
+            ],
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<UnifiedAddressError>(),
+            Some(&UnifiedAddressError::DuplicateReceiver(
+                ReceiverType::Sapling
+            ))
+        );
+
+        assert!(Address::from_receivers(Network::Main, Vec::new()).is_err());
+        assert!(
+            Address::from_receivers(Network::Main, vec![(ReceiverType::P2PKH, vec![0u8; 19])])
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,347 @@
+use anyhow::{Context, Result, bail};
+use bech32::{
+    Hrp,
+    primitives::{checksum::Checksum, decode::CheckedHrpstring},
+};
+use zcash_protocol::consensus::{NetworkConstants, NetworkType};
+
+use super::{ParseOptions, check_receivers};
+use crate::{CompactSize, Network, ReceiverType, parse, parser::prelude::*};
+
+/// A receiver's type together with its raw encoding.
+pub type Receiver = (ReceiverType, Vec<u8>);
+
+/// Length of the HRP padding appended to a Unified Address payload before jumbling.
+pub(crate) const PADDING_LEN: usize = 16;
+
+/// The Bech32m checksum with the extended code length permitted by ZIP 316.
+///
+/// Unified Addresses are routinely longer than the 1023 characters the `bech32`
+/// crate allows by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Bech32mZip316 {}
+
+impl Checksum for Bech32mZip316 {
+    type MidstateRepr = u32;
+    const CODE_LENGTH: usize = 4194368;
+    const CHECKSUM_LENGTH: usize = 6;
+    const GENERATOR_SH: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+    const TARGET_RESIDUE: u32 = 0x2bc830a3;
+}
+
+/// Returns the padding bytes for the given human-readable part: the HRP itself,
+/// zero-padded to 16 bytes.
+pub(crate) fn hrp_padding(hrp: &str) -> Result<[u8; PADDING_LEN]> {
+    if hrp.len() > PADDING_LEN {
+        bail!("HRP {} is too long for Unified Address padding", hrp);
+    }
+    let mut padding = [0u8; PADDING_LEN];
+    padding[..hrp.len()].copy_from_slice(hrp.as_bytes());
+    Ok(padding)
+}
+
+/// Encodes a Unified Address from its receivers.
+///
+/// Each receiver is validated to have the byte length required by its type, and
+/// the receivers must be distinct, must not include both transparent types or
+/// only transparent types, and must be given in ascending typecode order; a
+/// violation is reported as a [`UnifiedAddressError`](super::UnifiedAddressError).
+pub fn encode_unified_address(
+    network: Network,
+    receivers: &[(ReceiverType, Vec<u8>)],
+) -> Result<String> {
+    if receivers.is_empty() {
+        bail!("A Unified Address must contain at least one receiver");
+    }
+    check_receivers(receivers.iter().map(|(receiver_type, _)| *receiver_type))?;
+    let hrp = NetworkType::from(network).hrp_unified_address();
+
+    let mut payload = Vec::new();
+    for (receiver_type, data) in receivers {
+        check_receiver_len(*receiver_type, data)?;
+        payload.extend(CompactSize::new(receiver_type.typecode() as usize).to_bytes());
+        payload.extend(CompactSize::new(data.len()).to_bytes());
+        payload.extend(data);
+    }
+    payload.extend(hrp_padding(hrp)?);
+
+    let jumbled =
+        f4jumble::f4jumble(&payload).map_err(|e| anyhow::anyhow!("F4Jumble failed: {}", e))?;
+    let hrp = Hrp::parse(hrp).context("Unified Address HRP")?;
+    Ok(bech32::encode::<Bech32mZip316>(hrp, &jumbled)?)
+}
+
+/// Decodes a Unified Address string into its network and receivers, in encoded order.
+///
+/// Decoding is strict: after removing the HRP padding, the payload must consist
+/// of exactly a sequence of typecode/length/value items. A declared receiver
+/// length that runs past the end of the payload, a receiver whose length is
+/// wrong for its type, or trailing bytes that do not form a complete item are
+/// all errors, so a damaged address is never decoded into a partial one.
+///
+/// Receivers of types this crate does not recognize are preserved as
+/// [`ReceiverType::Unknown`]; use [`decode_unified_address_with_options`] to
+/// reject them instead.
+pub fn decode_unified_address(encoded: &str) -> Result<(Network, Vec<Receiver>)> {
+    decode_unified_address_with_options(encoded, &ParseOptions::default())
+}
+
+/// Decodes a Unified Address string like [`decode_unified_address`], handling
+/// receivers of unknown types as `options` direct.
+pub fn decode_unified_address_with_options(
+    encoded: &str,
+    options: &ParseOptions,
+) -> Result<(Network, Vec<Receiver>)> {
+    let (network, items) = unjumble_unified_address(encoded)?;
+    Ok((network, decode_receivers(&items, options)?))
+}
+
+/// Returns the network of a Unified Address string and its typecode/length/value
+/// items, with the Bech32m encoding, F4Jumble and HRP padding all removed.
+///
+/// An all-uppercase string is accepted, as Bech32 allows.
+pub(crate) fn unjumble_unified_address(encoded: &str) -> Result<(Network, Vec<u8>)> {
+    let checked = CheckedHrpstring::new::<Bech32mZip316>(encoded)
+        .context("Unified Address Bech32m encoding")?;
+    let hrp = checked.hrp().to_lowercase();
+    let jumbled: Vec<u8> = checked.byte_iter().collect();
+    let network = [Network::Main, Network::Test, Network::Regtest]
+        .into_iter()
+        .find(|network| NetworkType::from(*network).hrp_unified_address() == hrp)
+        .with_context(|| format!("Unknown Unified Address HRP {}", hrp))?;
+
+    let mut payload =
+        f4jumble::f4jumble_inv(&jumbled).map_err(|e| anyhow::anyhow!("F4Jumble failed: {}", e))?;
+    if payload.len() < PADDING_LEN {
+        bail!("Unified Address payload is too short");
+    }
+    let padding = payload.split_off(payload.len() - PADDING_LEN);
+    if padding != hrp_padding(&hrp)? {
+        bail!("Unified Address padding does not match HRP {}", hrp);
+    }
+    Ok((network, payload))
+}
+
+/// Decodes the typecode/length/value items of an unpadded Unified Address payload.
+fn decode_receivers(items: &[u8], options: &ParseOptions) -> Result<Vec<Receiver>> {
+    let mut p = Parser::new(&items);
+    let mut receivers = Vec::new();
+    while p.remaining() > 0 {
+        let receiver_type =
+            ReceiverType::parse_with_options(&mut p, options).context("receiver typecode")?;
+        let len = *parse!(&mut p, CompactSize, "receiver length")?;
+        if len > p.remaining() {
+            bail!(
+                "Declared {} receiver length {} exceeds the {} remaining bytes",
+                String::from(receiver_type),
+                len,
+                p.remaining()
+            );
+        }
+        let data = p.next(len)?;
+        check_receiver_len(receiver_type, data)?;
+        receivers.push((receiver_type, data.to_vec()));
+    }
+    if receivers.is_empty() {
+        bail!("A Unified Address must contain at least one receiver");
+    }
+    Ok(receivers)
+}
+
+/// Checks that `data` has the length required for a receiver of type `receiver_type`.
+///
+/// The data of an unknown receiver type may have any length.
+pub fn check_receiver_len(receiver_type: ReceiverType, data: &[u8]) -> Result<()> {
+    let Some(expected) = receiver_type.receiver_len() else {
+        return Ok(());
+    };
+    if data.len() != expected {
+        bail!(
+            "Invalid {} receiver length: expected {} bytes, got {}",
+            String::from(receiver_type),
+            expected,
+            data.len()
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use bech32::Hrp;
+
+    use super::{
+        Bech32mZip316, decode_receivers, decode_unified_address,
+        decode_unified_address_with_options, encode_unified_address, hrp_padding,
+    };
+    use crate::{
+        Network, ReceiverType,
+        unified::{ParseOptions, UnifiedAddressError, UnknownReceivers},
+    };
+
+    /// Bech32m-encodes a raw, unjumbled payload the way a Unified Address would be.
+    fn encode_raw(hrp: &str, items: &[u8]) -> String {
+        let payload = [items, &hrp_padding(hrp).unwrap()].concat();
+        let jumbled = f4jumble::f4jumble(&payload).unwrap();
+        bech32::encode::<Bech32mZip316>(Hrp::parse(hrp).unwrap(), &jumbled).unwrap()
+    }
+
+    #[test]
+    fn test_decode_roundtrip() -> Result<()> {
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+            (ReceiverType::Sapling, vec![2u8; 43]),
+            (ReceiverType::Orchard, vec![3u8; 43]),
+        ];
+        let encoded = encode_unified_address(Network::Test, &receivers)?;
+        let (network, decoded) = decode_unified_address(&encoded)?;
+        assert_eq!(network, Network::Test);
+        assert_eq!(decoded, receivers);
+
+        // Generated independently with the `zcash_address` crate.
+        let (network, decoded) = decode_unified_address(
+            "u1feukxdlmv7ancxyz7afwza6tz0edrqjvte3kwy0mhjuzz2ggmexjwlsze3qy8vku75ngtf94la6ucu4vvk3h2z6c6d0zmeqahv4cpxteyns4mxx6yrctummd05qede2y8c63kuwraqz",
+        )?;
+        assert_eq!(network, Network::Main);
+        assert_eq!(
+            decoded,
+            vec![
+                (ReceiverType::P2PKH, vec![1u8; 20]),
+                (ReceiverType::Orchard, vec![2u8; 43]),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_declared_length_exceeds_remaining() {
+        // An Orchard item declaring 43 bytes but carrying only 30.
+        let items = [&[0x03, 43][..], &[0u8; 30]].concat();
+        let error = decode_receivers(&items, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().contains("exceeds the 30 remaining bytes"));
+
+        // The same truncated stream, fully encoded, is rejected rather than
+        // decoded into a partial address.
+        let mut items = [&[0x00, 20][..], &[0u8; 20]].concat();
+        items.extend([0x03, 43]);
+        items.extend([0u8; 30]);
+        assert!(decode_unified_address(&encode_raw("u", &items)).is_err());
+    }
+
+    #[test]
+    fn test_rejects_malformed_items() {
+        // Receiver length does not match the receiver type.
+        let items = [&[0x00, 19][..], &[0u8; 19]].concat();
+        assert!(decode_receivers(&items, &ParseOptions::default()).is_err());
+
+        // Trailing typecode with no length.
+        let items = [&[0x02, 43][..], &[0u8; 43], &[0x03]].concat();
+        assert!(decode_receivers(&items, &ParseOptions::default()).is_err());
+
+        // No receivers at all.
+        assert!(decode_receivers(&[], &ParseOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_encode_rejects_duplicate_receivers() {
+        let receivers = vec![
+            (ReceiverType::Sapling, vec![1u8; 43]),
+            (ReceiverType::Orchard, vec![2u8; 43]),
+            (ReceiverType::Orchard, vec![3u8; 43]),
+        ];
+        let error = encode_unified_address(Network::Main, &receivers).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<UnifiedAddressError>(),
+            Some(&UnifiedAddressError::DuplicateReceiver(
+                ReceiverType::Orchard
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_non_adjacent_duplicate_receivers() {
+        let receivers = vec![
+            (ReceiverType::Sapling, vec![1u8; 43]),
+            (ReceiverType::Orchard, vec![2u8; 43]),
+            (ReceiverType::Sapling, vec![3u8; 43]),
+        ];
+        let error = encode_unified_address(Network::Main, &receivers).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<UnifiedAddressError>(),
+            Some(&UnifiedAddressError::DuplicateReceiver(
+                ReceiverType::Sapling
+            ))
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_both_transparent_receivers() {
+        let receivers = vec![
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+            (ReceiverType::P2SH, vec![2u8; 20]),
+            (ReceiverType::Orchard, vec![3u8; 43]),
+        ];
+        let error = encode_unified_address(Network::Main, &receivers).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<UnifiedAddressError>(),
+            Some(&UnifiedAddressError::BothTransparentReceivers)
+        );
+    }
+
+    #[test]
+    fn test_encode_rejects_only_transparent_receivers() {
+        for receiver_type in [ReceiverType::P2PKH, ReceiverType::P2SH] {
+            let receivers = vec![(receiver_type, vec![1u8; 20])];
+            let error = encode_unified_address(Network::Main, &receivers).unwrap_err();
+            assert_eq!(
+                error.downcast_ref::<UnifiedAddressError>(),
+                Some(&UnifiedAddressError::OnlyTransparentReceivers)
+            );
+        }
+    }
+
+    #[test]
+    fn test_encode_rejects_out_of_order_receivers() {
+        let receivers = vec![
+            (ReceiverType::Orchard, vec![2u8; 43]),
+            (ReceiverType::P2PKH, vec![1u8; 20]),
+        ];
+        let error = encode_unified_address(Network::Main, &receivers).unwrap_err();
+        assert_eq!(
+            error.downcast_ref::<UnifiedAddressError>(),
+            Some(&UnifiedAddressError::OutOfOrder {
+                previous: ReceiverType::Orchard,
+                next: ReceiverType::P2PKH,
+            })
+        );
+        assert_eq!(
+            error.to_string(),
+            "P2PKH receiver (typecode 0x00) must precede Orchard receiver (typecode 0x03)"
+        );
+    }
+
+    #[test]
+    fn test_unknown_receiver_handling() -> Result<()> {
+        // A P2PKH receiver followed by one with the unassigned typecode 0x05.
+        let items = [&[0x00, 20][..], &[1u8; 20], &[0x05, 10], &[9u8; 10]].concat();
+        let encoded = encode_raw("u", &items);
+
+        let (network, receivers) = decode_unified_address(&encoded)?;
+        assert_eq!(network, Network::Main);
+        assert_eq!(
+            receivers,
+            vec![
+                (ReceiverType::P2PKH, vec![1u8; 20]),
+                (ReceiverType::Unknown(0x05), vec![9u8; 10]),
+            ]
+        );
+        // A preserved receiver re-encodes to the original address.
+        assert_eq!(encode_unified_address(network, &receivers)?, encoded);
+
+        let strict = ParseOptions::default().with_unknown_receivers(UnknownReceivers::Reject);
+        let error = decode_unified_address_with_options(&encoded, &strict).unwrap_err();
+        assert!(format!("{:#}", error).contains("Unknown receiver typecode 0x05"));
+        Ok(())
+    }
+}
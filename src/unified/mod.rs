@@ -0,0 +1,18 @@
+//! # Unified Addresses
+//!
+//! Unified Addresses (ZIP 316) bundle receivers for several Zcash protocols into a
+//! single string. The encoding is a sequence of typecode/length/value items, padded
+//! with the human-readable part, passed through the F4Jumble permutation and finally
+//! Bech32m-encoded.
+//!
+//! This module contains the codec used to move between raw receiver bytes and the
+//! encoded address strings stored in [`UnifiedAddress`](crate::UnifiedAddress), the
+//! decoded [`Address`] form of such a string, and
+//! the [`ParseOptions`] that control how receivers of unknown types are decoded.
+
+use crate::mod_use;
+
+mod_use!(address);
+mod_use!(encoding);
+mod_use!(parse_options);
+mod_use!(unified_address_error);
@@ -0,0 +1,42 @@
+/// How a decoder treats a receiver whose typecode it does not recognize.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum UnknownReceivers {
+    /// Fail to decode the address.
+    Reject,
+
+    /// Keep the receiver as [`ReceiverType::Unknown`](crate::ReceiverType::Unknown)
+    /// with its data unchanged.
+    #[default]
+    Preserve,
+}
+
+/// Options controlling how strictly Unified Addresses and their receiver types
+/// are decoded.
+///
+/// ZIP 316 lets new receiver types be defined after a wallet is written, and
+/// consumers differ on how to treat them: a migration tool wants to carry them
+/// over untouched, while a strict importer may prefer to refuse an address it
+/// cannot fully interpret. The default options preserve unknown receivers.
+///
+/// # Examples
+/// ```
+/// # use zewif::unified::{ParseOptions, UnknownReceivers};
+/// assert_eq!(ParseOptions::default().unknown_receivers, UnknownReceivers::Preserve);
+///
+/// let strict = ParseOptions::default().with_unknown_receivers(UnknownReceivers::Reject);
+/// assert_eq!(strict.unknown_receivers, UnknownReceivers::Reject);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct ParseOptions {
+    /// How receivers with unrecognized typecodes are handled.
+    pub unknown_receivers: UnknownReceivers,
+}
+
+impl ParseOptions {
+    /// Returns these options with receivers of unrecognized typecodes
+    /// handled as `unknown_receivers` directs.
+    pub fn with_unknown_receivers(mut self, unknown_receivers: UnknownReceivers) -> Self {
+        self.unknown_receivers = unknown_receivers;
+        self
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::HashSet;
+use std::fmt;
+
+use crate::ReceiverType;
+
+/// Errors arising from an invalid set of Unified Address receivers.
+///
+/// ZIP 316 requires each receiver type to appear at most once, at most one of
+/// the two transparent receiver types, at least one receiver that is not
+/// transparent, and the receivers to be encoded in ascending typecode order.
+/// These errors name the offending receiver types so callers can correct the
+/// set they supplied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnifiedAddressError {
+    /// The receiver type appears more than once.
+    DuplicateReceiver(ReceiverType),
+    /// Both a P2PKH and a P2SH receiver are present.
+    BothTransparentReceivers,
+    /// Every receiver is transparent.
+    OnlyTransparentReceivers,
+    /// `next` was given after `previous`, but has a lower typecode.
+    OutOfOrder {
+        previous: ReceiverType,
+        next: ReceiverType,
+    },
+}
+
+impl fmt::Display for UnifiedAddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UnifiedAddressError::DuplicateReceiver(receiver_type) => write!(
+                f,
+                "Duplicate {} receiver (typecode 0x{:02x})",
+                String::from(*receiver_type),
+                receiver_type.typecode()
+            ),
+            UnifiedAddressError::BothTransparentReceivers => {
+                write!(f, "A Unified Address cannot have both P2PKH and P2SH receivers")
+            }
+            UnifiedAddressError::OnlyTransparentReceivers => write!(
+                f,
+                "A Unified Address must have at least one receiver that is not transparent"
+            ),
+            UnifiedAddressError::OutOfOrder { previous, next } => write!(
+                f,
+                "{} receiver (typecode 0x{:02x}) must precede {} receiver (typecode 0x{:02x})",
+                String::from(*next),
+                next.typecode(),
+                String::from(*previous),
+                previous.typecode()
+            ),
+        }
+    }
+}
+
+impl std::error::Error for UnifiedAddressError {}
+
+/// Checks that `receiver_types` form a receiver set ZIP 316 allows, in the
+/// order it requires.
+///
+/// Duplicates are reported before the transparent receiver rules, and those
+/// before ordering, so a repeated receiver type is reported as a duplicate
+/// wherever it appears. An empty set is not checked here.
+pub fn check_receivers(
+    receiver_types: impl IntoIterator<Item = ReceiverType>,
+) -> Result<(), UnifiedAddressError> {
+    let receiver_types: Vec<ReceiverType> = receiver_types.into_iter().collect();
+
+    let mut seen = HashSet::new();
+    if let Some(duplicate) = receiver_types
+        .iter()
+        .find(|receiver_type| !seen.insert(receiver_type.typecode()))
+    {
+        return Err(UnifiedAddressError::DuplicateReceiver(*duplicate));
+    }
+
+    let is_transparent =
+        |receiver_type: &ReceiverType| matches!(receiver_type, ReceiverType::P2PKH | ReceiverType::P2SH);
+    if receiver_types.contains(&ReceiverType::P2PKH) && receiver_types.contains(&ReceiverType::P2SH) {
+        return Err(UnifiedAddressError::BothTransparentReceivers);
+    }
+    if !receiver_types.is_empty() && receiver_types.iter().all(is_transparent) {
+        return Err(UnifiedAddressError::OnlyTransparentReceivers);
+    }
+
+    for pair in receiver_types.windows(2) {
+        let (previous, next) = (pair[0], pair[1]);
+        if next.typecode() < previous.typecode() {
+            return Err(UnifiedAddressError::OutOfOrder { previous, next });
+        }
+    }
+    Ok(())
+}
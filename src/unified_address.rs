@@ -1,4 +1,4 @@
-use crate::{Blob, test_envelope_roundtrip};
+use crate::{Blob, test_envelope_roundtrip, unified};
 use anyhow::Context;
 use bc_envelope::prelude::*;
 
@@ -39,6 +39,12 @@ use bc_envelope::prelude::*;
 /// - Information about which receiver types to include
 /// - References to the keys used to derive the addresses
 ///
+/// # Choosing Between Address Types
+/// Use `UnifiedAddress` for an address as a wallet records it, which is the
+/// form ZeWIF stores and exports. To inspect or rebuild the receivers inside
+/// the string, decode it into a [`unified::Address`](crate::unified::Address),
+/// which carries no derivation metadata.
+///
 /// # Examples
 /// ```
 /// # use zewif::{UnifiedAddress, ReceiverType, Blob};
@@ -60,6 +66,9 @@ pub struct UnifiedAddress {
 
     /// HD derivation path if this address was derived using HD wallet techniques
     hd_derivation_path: Option<String>,
+
+    /// The receiver items decoded from `address`, or empty if it does not decode.
+    raw: Vec<u8>,
 }
 
 impl std::fmt::Debug for UnifiedAddress {
@@ -76,6 +85,7 @@ impl UnifiedAddress {
     /// Create a new UnifiedAddress with the given address string
     pub fn new(address: String) -> Self {
         UnifiedAddress {
+            raw: decode_raw(&address),
             address,
             diversifier_index: None,
             hd_derivation_path: None,
@@ -89,9 +99,21 @@ impl UnifiedAddress {
 
     /// Set the unified address string
     pub fn set_address(&mut self, address: String) {
+        self.raw = decode_raw(&address);
         self.address = address;
     }
 
+    /// Returns the encoded receivers carried by this address, or an empty
+    /// slice if the string cannot be decoded.
+    ///
+    /// These are the typecode/length/value items of the address, with the
+    /// F4Jumble and HRP padding removed. Use
+    /// [`unified::decode_unified_address`](crate::unified::decode_unified_address)
+    /// to split them into receivers.
+    pub fn raw_bytes(&self) -> &[u8] {
+        &self.raw
+    }
+
     /// Get the diversifier index if available
     pub fn diversifier_index(&self) -> Option<&Blob<11>> {
         self.diversifier_index.as_ref()
@@ -113,6 +135,14 @@ impl UnifiedAddress {
     }
 }
 
+/// Decodes the receiver items from a Unified Address string, returning an
+/// empty vector if it is malformed.
+fn decode_raw(address: &str) -> Vec<u8> {
+    unified::unjumble_unified_address(address)
+        .map(|(_, items)| items)
+        .unwrap_or_default()
+}
+
 impl From<UnifiedAddress> for Envelope {
     fn from(value: UnifiedAddress) -> Self {
         Envelope::new(value.address)
@@ -129,7 +159,7 @@ impl TryFrom<Envelope> for UnifiedAddress {
         envelope
             .check_type_envelope("UnifiedAddress")
             .context("UnifiedAddress")?;
-        let address = envelope.extract_subject().context("address")?;
+        let address: String = envelope.extract_subject().context("address")?;
         let diversifier_index = envelope
             .try_optional_object_for_predicate("diversifier_index")
             .context("diversifier_index")?;
@@ -138,6 +168,7 @@ impl TryFrom<Envelope> for UnifiedAddress {
             .context("hd_derivation_path")?;
 
         Ok(UnifiedAddress {
+            raw: decode_raw(&address),
             address,
             diversifier_index,
             hd_derivation_path,
@@ -145,13 +176,25 @@ impl TryFrom<Envelope> for UnifiedAddress {
     }
 }
 
+impl crate::EnvelopeSchema for UnifiedAddress {
+    const TYPE_NAME: &'static str = "UnifiedAddress";
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["diversifier_index", "hd_derivation_path"]
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for UnifiedAddress {
     fn random() -> Self {
         Self {
-            address: String::random(),
             diversifier_index: Blob::opt_random(),
             hd_derivation_path: String::opt_random(),
+            ..Self::new(String::random())
         }
     }
 }
@@ -0,0 +1,73 @@
+use std::fmt;
+
+use crate::{BlockHeight, TxId};
+
+/// A referential inconsistency found by [`Zewif::validate`](crate::Zewif::validate).
+///
+/// Importers build a [`Zewif`](crate::Zewif) piece by piece, and the format does not
+/// stop them from recording a transaction under the wrong txid, or from
+/// referencing a transaction they never added. Each variant names one such
+/// problem and where it was found, so it can be fixed before the container is
+/// serialized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValidationIssue {
+    /// More than one wallet has the given index.
+    DuplicateWalletIndex(usize),
+
+    /// An account lists a relevant transaction that the container does not
+    /// hold.
+    MissingTransaction {
+        wallet: usize,
+        account: usize,
+        txid: TxId,
+    },
+
+    /// A lazily held transaction could not be decoded, so its own checks
+    /// were not made. The message is the decoding error.
+    UndecodableTransaction(TxId, String),
+
+    /// A transaction is stored under a txid other than its own.
+    TransactionKeyMismatch { key: TxId, txid: TxId },
+
+    /// A transaction is recorded as mined below the height it was constructed
+    /// for.
+    MinedBeforeTarget {
+        txid: TxId,
+        target_height: BlockHeight,
+        mined_height: BlockHeight,
+    },
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValidationIssue::DuplicateWalletIndex(index) => {
+                write!(f, "More than one wallet has index {}", index)
+            }
+            ValidationIssue::MissingTransaction {
+                wallet,
+                account,
+                txid,
+            } => write!(
+                f,
+                "Account {} of wallet {} references missing transaction {}",
+                account, wallet, txid
+            ),
+            ValidationIssue::UndecodableTransaction(txid, error) => {
+                write!(f, "Transaction {} could not be decoded: {}", txid, error)
+            }
+            ValidationIssue::TransactionKeyMismatch { key, txid } => {
+                write!(f, "Transaction {} is stored under txid {}", txid, key)
+            }
+            ValidationIssue::MinedBeforeTarget {
+                txid,
+                target_height,
+                mined_height,
+            } => write!(
+                f,
+                "Transaction {} was mined at height {}, below its target height {}",
+                txid, mined_height, target_height
+            ),
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::collections::BTreeMap;
+
+use crate::{ProtocolAddress, Zewif};
+
+/// Aggregate, anonymized statistics about the contents of a [`Zewif`] container.
+///
+/// `WalletStats` is meant to be shared as opt-in telemetry: it is made only of
+/// counts and coarse histograms, and carries no addresses, txids, memos or
+/// exact amounts. Values are reduced to their decimal order of magnitude and
+/// mined heights to buckets of [`HEIGHT_BUCKET_SIZE`](Self::HEIGHT_BUCKET_SIZE)
+/// blocks.
+///
+/// The interchange format records no transaction timestamps, so transaction
+/// activity is reported by mined height rather than by calendar month. Values
+/// are taken from the sent outputs recorded by each account, since received
+/// notes are not part of the format.
+///
+/// # Examples
+/// ```
+/// # use zewif::{WalletStats, Zewif};
+/// let stats = Zewif::new().statistics();
+/// assert_eq!(stats, WalletStats::default());
+/// assert_eq!(WalletStats::value_bucket(12_345), 10_000);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WalletStats {
+    /// The number of wallets in the container.
+    pub wallets: usize,
+
+    /// The number of accounts across all wallets.
+    pub accounts: usize,
+
+    /// The number of transparent addresses across all accounts.
+    pub transparent_addresses: usize,
+
+    /// The number of TEX addresses across all accounts.
+    pub tex_addresses: usize,
+
+    /// The number of Sapling addresses across all accounts.
+    pub sapling_addresses: usize,
+
+    /// The number of unified addresses across all accounts.
+    pub unified_addresses: usize,
+
+    /// The number of transactions in the container.
+    pub transactions: usize,
+
+    /// The number of transactions with no known mined height, including any
+    /// lazily held transaction that fails to decode.
+    pub unmined_transactions: usize,
+
+    /// Mined transactions, keyed by the first height of each bucket of
+    /// [`HEIGHT_BUCKET_SIZE`](Self::HEIGHT_BUCKET_SIZE) blocks.
+    pub transactions_by_height: BTreeMap<u32, usize>,
+
+    /// The number of Sapling outputs sent by all accounts.
+    pub sapling_sent_outputs: usize,
+
+    /// The number of Orchard outputs sent by all accounts.
+    pub orchard_sent_outputs: usize,
+
+    /// Sent output values, keyed by [`value_bucket`](Self::value_bucket).
+    pub sent_output_values: BTreeMap<u64, usize>,
+}
+
+impl WalletStats {
+    /// The number of blocks covered by each bucket of
+    /// [`transactions_by_height`](Self::transactions_by_height).
+    pub const HEIGHT_BUCKET_SIZE: u32 = 10_000;
+
+    /// Returns the histogram bucket for a value in zatoshis: the largest power
+    /// of ten not exceeding it, or zero for a zero value.
+    pub fn value_bucket(zats: u64) -> u64 {
+        if zats == 0 {
+            0
+        } else {
+            10u64.pow(zats.ilog10())
+        }
+    }
+
+    /// Returns the fractions of sent shielded outputs that used the Sapling and
+    /// Orchard pools, or `None` if no shielded outputs were sent.
+    pub fn sent_output_pool_shares(&self) -> Option<(f64, f64)> {
+        let total = self.sapling_sent_outputs + self.orchard_sent_outputs;
+        (total > 0).then(|| {
+            (
+                self.sapling_sent_outputs as f64 / total as f64,
+                self.orchard_sent_outputs as f64 / total as f64,
+            )
+        })
+    }
+}
+
+impl From<&Zewif> for WalletStats {
+    fn from(zewif: &Zewif) -> Self {
+        let mut stats = WalletStats {
+            wallets: zewif.wallets_len(),
+            ..Default::default()
+        };
+
+        for account in zewif.wallets().iter().flat_map(|wallet| wallet.accounts()) {
+            stats.accounts += 1;
+            for address in account.addresses() {
+                match address.address() {
+                    ProtocolAddress::Transparent(_) => stats.transparent_addresses += 1,
+                    ProtocolAddress::Tex(_) => stats.tex_addresses += 1,
+                    ProtocolAddress::Sapling(_) => stats.sapling_addresses += 1,
+                    ProtocolAddress::Unified(_) => stats.unified_addresses += 1,
+                }
+            }
+            stats.sapling_sent_outputs += account.sapling_sent_outputs_len();
+            stats.orchard_sent_outputs += account.orchard_sent_outputs_len();
+            let values = account
+                .sapling_sent_outputs()
+                .iter()
+                .map(|output| output.value())
+                .chain(
+                    account
+                        .orchard_sent_outputs()
+                        .iter()
+                        .map(|output| output.value()),
+                );
+            for value in values {
+                if let Ok(zats) = u64::try_from(value) {
+                    *stats
+                        .sent_output_values
+                        .entry(Self::value_bucket(zats))
+                        .or_default() += 1;
+                }
+            }
+        }
+
+        let mined_heights = zewif
+            .transactions()
+            .values()
+            .map(|transaction| {
+                transaction
+                    .get()
+                    .ok()
+                    .and_then(|transaction| transaction.mined_height().copied())
+            });
+        for mined_height in mined_heights {
+            stats.transactions += 1;
+            match mined_height {
+                Some(height) => {
+                    let height = u32::from(height);
+                    let bucket = height - height % Self::HEIGHT_BUCKET_SIZE;
+                    *stats.transactions_by_height.entry(bucket).or_default() += 1;
+                }
+                None => stats.unmined_transactions += 1,
+            }
+        }
+
+        stats
+    }
+}
@@ -1,8 +1,17 @@
 use anyhow::{Context, Result, bail};
-use bc_components::{ARID, SymmetricKey};
-use bc_crypto::pbkdf2_hmac_sha256;
+use bc_components::{ARID, EncryptedKey, KeyDerivationMethod, Nonce, Salt, SymmetricKey};
+use bc_crypto::{pbkdf2_hmac_sha256, scrypt_opt};
 use bc_envelope::prelude::*;
 
+/// The scrypt cost, as log2(N), used by [`ZewifEnvelope::lock`]. This is the
+/// bc-components default, lowered in tests to keep them fast.
+#[cfg(not(test))]
+const SCRYPT_LOG_N: u8 = 15;
+#[cfg(test)]
+const SCRYPT_LOG_N: u8 = 8;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
 #[derive(Debug, Clone)]
 pub struct ZewifEnvelope {
     id: ARID,
@@ -45,7 +54,17 @@ impl ZewifEnvelope {
 
     pub fn is_encrypted(&self) -> bool {
         self.obscured_content()
-            .is_some_and(|content| content.is_encrypted())
+            .is_some_and(|content| content.subject().is_encrypted())
+    }
+
+    /// Returns `true` if the content was encrypted by [`lock`](Self::lock),
+    /// and so can be opened with a passphrase.
+    pub fn is_locked(&self) -> bool {
+        self.obscured_content().is_some_and(|content| {
+            !content
+                .assertions_with_predicate(known_values::HAS_SECRET)
+                .is_empty()
+        })
     }
 
     pub fn can_compress(&self) -> bool {
@@ -64,6 +83,10 @@ impl ZewifEnvelope {
         self.is_encrypted()
     }
 
+    pub fn can_unlock(&self) -> bool {
+        self.is_locked()
+    }
+
     pub fn compress(&mut self) -> Result<()> {
         if self.can_compress() {
             let content = self.envelope.wrap_envelope().compress()?;
@@ -117,6 +140,71 @@ impl ZewifEnvelope {
         }
         Ok(())
     }
+
+    /// Encrypts the envelope like [`encrypt`](Self::encrypt), but under a
+    /// random content key that is itself locked with a key derived from
+    /// `passphrase` by scrypt.
+    ///
+    /// The locked key is attached to the content as a `'hasSecret'`
+    /// assertion, as made by Gordian Envelope's `Envelope::lock`, and carries
+    /// the scrypt salt and cost parameters, so [`unlock`](Self::unlock) needs
+    /// nothing but the passphrase.
+    pub fn lock(&mut self, passphrase: impl AsRef<str>) -> Result<()> {
+        if self.can_encrypt() {
+            let content_key = SymmetricKey::new();
+            let encrypted_key = scrypt_lock(passphrase.as_ref(), &content_key)?;
+            let content = self
+                .envelope
+                .wrap_envelope()
+                .encrypt_subject(&content_key)?
+                .add_assertion(known_values::HAS_SECRET, encrypted_key);
+            self.envelope = Envelope::new(self.id)
+                .add_type("Zewif")
+                .add_assertion("content", content);
+        } else {
+            bail!("Cannot lock a Zewif that has already been encrypted");
+        }
+        Ok(())
+    }
+
+    pub fn unlock(&mut self, passphrase: impl AsRef<str>) -> Result<()> {
+        if self.can_unlock() {
+            self.envelope = self
+                .envelope
+                .object_for_predicate("content")?
+                .unlock(passphrase.as_ref())
+                .context("Unlocking the Zewif content; the passphrase may be wrong")?;
+        } else {
+            bail!("Cannot unlock a Zewif that has not been locked with a passphrase");
+        }
+        Ok(())
+    }
+}
+
+/// Locks `content_key` with a key derived from `passphrase` by scrypt.
+///
+/// The result is what `EncryptedKey::lock` makes with
+/// `KeyDerivationMethod::Scrypt`, which has no way to choose the cost, so
+/// that [`SCRYPT_LOG_N`] applies.
+fn scrypt_lock(passphrase: &str, content_key: &SymmetricKey) -> Result<EncryptedKey> {
+    let salt = Salt::new_with_len(16)?;
+    let derived_key = SymmetricKey::from_data_ref(scrypt_opt(
+        passphrase,
+        &salt,
+        SymmetricKey::SYMMETRIC_KEY_SIZE,
+        SCRYPT_LOG_N,
+        SCRYPT_R,
+        SCRYPT_P,
+    ))?;
+    let params = CBOR::from(vec![
+        CBOR::from(KeyDerivationMethod::Scrypt.index()),
+        CBOR::from(salt),
+        CBOR::from(SCRYPT_LOG_N),
+        CBOR::from(SCRYPT_R),
+        CBOR::from(SCRYPT_P),
+    ]);
+    let locked = derived_key.encrypt(content_key, Some(params.to_cbor_data()), None::<Nonce>);
+    Ok(EncryptedKey::from_untagged_cbor(locked.into())?)
 }
 
 #[cfg(test)]
@@ -217,4 +305,32 @@ mod tests {
         // Check that the reconstructed Zewif instance matches the original
         assert_eq!(zewif, zewif2);
     }
+
+    #[test]
+    fn test_lock_and_unlock() {
+        let zewif = Zewif::random();
+        let mut ze = ZewifEnvelope::new(Envelope::from(zewif.clone())).unwrap();
+        assert!(!ze.can_unlock());
+        assert!(ze.unlock("password").is_err());
+
+        ze.compress().unwrap();
+        ze.lock("password").unwrap();
+        assert_eq!(ze.id(), zewif.id());
+        assert!(ze.is_encrypted());
+        assert!(ze.is_locked());
+        assert!(!ze.can_encrypt());
+        assert!(ze.lock("password").is_err());
+
+        // The scrypt parameters travel with the envelope.
+        let envelope = Envelope::try_from_cbor_data(ze.envelope().to_cbor_data()).unwrap();
+        let mut ze = ZewifEnvelope::new(envelope).unwrap();
+        let mut wrong = ze.clone();
+        assert!(wrong.unlock("Password").is_err());
+
+        ze.unlock("password").unwrap();
+        assert!(!ze.is_locked());
+        assert!(ze.is_compressed());
+        ze.uncompress().unwrap();
+        assert_eq!(Zewif::try_from(ze.envelope().clone()).unwrap(), zewif);
+    }
 }
@@ -1,9 +1,16 @@
-use anyhow::Context;
-use bc_components::ARID;
+use anyhow::{Context, Result, bail};
+use bc_components::{ARID, Signature, Signer, SigningPrivateKey, SigningPublicKey, Verifier};
 use bc_envelope::prelude::*;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Write;
+use std::sync::OnceLock;
 
-use crate::{Indexed, envelope_indexed_objects_for_predicate, test_envelope_roundtrip};
+use crate::{
+    Anchor, Blob, BlockHeight, BloomFilter, CompressionLevel, Indexed, LazyTransaction,
+    MergeReport, ProtocolAddress, SetIndexes, StoredTransaction, TxBlockPosition, ValidationIssue,
+    WalletStats, ZewifEnvelope, compress_container, decode_attachments, decompress_container,
+    encode_attachments, envelope_indexed_objects_for_predicate, test_envelope_roundtrip,
+};
 
 use super::{Transaction, TxId, ZewifWallet};
 
@@ -54,58 +61,1056 @@ use super::{Transaction, TxId, ZewifWallet};
 /// // Access transactions
 /// let tx_count = zewif.transactions().len();
 /// ```
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone)]
 pub struct Zewif {
     id: ARID,
     wallets: Vec<ZewifWallet>,
-    transactions: HashMap<TxId, Transaction>,
+    transactions: HashMap<TxId, StoredTransaction>,
+    export_height: Option<BlockHeight>,
     attachments: Attachments,
+    /// The [`content_digest`](Self::content_digest), once computed. Every
+    /// method that takes `&mut self` clears it.
+    content_digest: OnceLock<Digest>,
 }
 
-bc_envelope::impl_attachable!(Zewif);
+impl PartialEq for Zewif {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.wallets == other.wallets
+            && self.transactions == other.transactions
+            && self.export_height == other.export_height
+            && self.attachments == other.attachments
+    }
+}
+
+impl Attachable for Zewif {
+    fn attachments(&self) -> &Attachments {
+        &self.attachments
+    }
+
+    fn attachments_mut(&mut self) -> &mut Attachments {
+        self.content_changed();
+        &mut self.attachments
+    }
+}
 
 impl Zewif {
     pub fn new() -> Self {
+        Self::with_id(ARID::new())
+    }
+
+    /// Creates an empty container with the given identifier.
+    pub fn with_id(id: ARID) -> Self {
         Self {
-            id: ARID::new(),
+            id,
             wallets: Vec::new(),
             transactions: HashMap::new(),
+            export_height: None,
             attachments: Attachments::new(),
+            content_digest: OnceLock::new(),
         }
     }
 
+    /// Decodes a container from its envelope, deferring the decoding of each
+    /// transaction until it is first accessed.
+    ///
+    /// Wallets and attachments are decoded immediately. Transactions are held
+    /// as [`StoredTransaction::Lazy`] entries of
+    /// [`transactions`](Self::transactions), and are decoded by
+    /// [`load_transaction`](Self::load_transaction) or
+    /// [`load_transaction_mut`](Self::load_transaction_mut). Malformed
+    /// transactions are only reported when accessed.
+    pub fn try_from_envelope_lazy(envelope: Envelope) -> Result<Self> {
+        Self::decode_envelope(envelope, true)
+    }
+
     pub fn id(&self) -> ARID {
         self.id
     }
 
+    /// Returns the block height the source wallet had scanned to when it was
+    /// exported, if known.
+    pub fn export_height(&self) -> Option<BlockHeight> {
+        self.export_height
+    }
+
+    pub fn set_export_height(&mut self, height: BlockHeight) {
+        self.content_changed();
+        self.export_height = Some(height);
+    }
+
     pub fn wallets(&self) -> &Vec<ZewifWallet> {
         &self.wallets
     }
 
+    pub fn wallets_mut(&mut self) -> &mut [ZewifWallet] {
+        self.content_changed();
+        &mut self.wallets
+    }
+
     pub fn wallets_len(&self) -> usize {
         self.wallets.len()
     }
 
     pub fn add_wallet(&mut self, mut wallet: ZewifWallet) {
+        self.content_changed();
         wallet.set_index(self.wallets_len());
         self.wallets.push(wallet);
     }
 
-    pub fn transactions(&self) -> &HashMap<TxId, Transaction> {
+    /// Returns the wallet at `index`, or `None` if there is no such wallet.
+    pub fn wallet(&self, index: usize) -> Option<&ZewifWallet> {
+        self.wallets.get(index)
+    }
+
+    pub fn wallet_mut(&mut self, index: usize) -> Option<&mut ZewifWallet> {
+        self.content_changed();
+        self.wallets.get_mut(index)
+    }
+
+    /// Removes and returns the wallet at `index`, or returns `None` if there is
+    /// no such wallet.
+    ///
+    /// The wallets after the removed one move down by one and are re-indexed,
+    /// so that indexes stay contiguous from zero. Any index held from before
+    /// the removal may therefore refer to a different wallet afterwards. The
+    /// returned wallet keeps its old index.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Indexed, Network, Zewif, ZewifWallet};
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_wallet(ZewifWallet::new(Network::Main));
+    /// zewif.add_wallet(ZewifWallet::new(Network::Test));
+    ///
+    /// let removed = zewif.remove_wallet(0).unwrap();
+    /// assert_eq!(removed.network(), Network::Main);
+    /// assert_eq!(zewif.wallet(0).unwrap().network(), Network::Test);
+    /// assert_eq!(zewif.wallet(0).unwrap().index(), 0);
+    /// ```
+    pub fn remove_wallet(&mut self, index: usize) -> Option<ZewifWallet> {
+        if index >= self.wallets.len() {
+            return None;
+        }
+        self.content_changed();
+        let wallet = self.wallets.remove(index);
+        for (index, wallet) in self.wallets.iter_mut().enumerate().skip(index) {
+            wallet.set_index(index);
+        }
+        Some(wallet)
+    }
+
+    /// Merges the wallets and transactions of `other` into this container.
+    ///
+    /// Wallets of `other` are appended and re-indexed after the existing ones,
+    /// except that a wallet identical to one already present (apart from its
+    /// index) is skipped. Transactions are merged by txid: a new txid is added,
+    /// an identical copy of a held transaction is skipped, and a differing copy
+    /// is reported as a conflict while the held transaction is kept. The
+    /// higher of the two export heights is kept. Attachments and the
+    /// identifier of `other` are not carried over.
+    ///
+    /// # Errors
+    /// Returns an error, leaving this container unchanged, if a lazily held
+    /// transaction of either container fails to decode during comparison.
+    pub fn merge(&mut self, other: Zewif) -> Result<MergeReport> {
+        let mut report = MergeReport::default();
+
+        let mut incoming: Vec<(TxId, StoredTransaction)> = other.transactions.into_iter().collect();
+        incoming.sort_by_key(|(txid, _)| *txid);
+
+        let mut added = Vec::new();
+        for (txid, transaction) in incoming {
+            let Some(existing) = self.transactions.get(&txid) else {
+                added.push((txid, transaction));
+                continue;
+            };
+            let existing = existing.get()?;
+            let transaction = transaction
+                .get()
+                .with_context(|| format!("merged transaction {}", txid))?;
+            if existing == transaction {
+                report.duplicate_transactions += 1;
+            } else {
+                report.conflicting_transactions.push(txid);
+            }
+        }
+        report.transactions_added = added.len();
+        self.content_changed();
+        self.transactions.extend(added);
+        self.export_height = self.export_height.max(other.export_height);
+
+        for mut wallet in other.wallets {
+            let duplicate = self.wallets.iter().any(|existing| {
+                wallet.set_index(existing.index());
+                wallet == *existing
+            });
+            if duplicate {
+                report.duplicate_wallets += 1;
+            } else {
+                self.add_wallet(wallet);
+                report.wallets_added += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Returns `true` if no wallet in the container can spend.
+    ///
+    /// See [`ZewifWallet::is_watch_only`].
+    pub fn is_watch_only(&self) -> bool {
+        self.wallets.iter().all(ZewifWallet::is_watch_only)
+    }
+
+    /// Returns a copy of this container with all seeds and spending keys removed.
+    ///
+    /// The copy keeps viewing keys, addresses, sent outputs and transaction
+    /// history, so it can be shared with an auditor or accountant without
+    /// granting spending authority. Attachments are dropped at every level,
+    /// along with the assertions preserved from a newer format version, since
+    /// their contents are opaque to this crate and may hold key material.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode, since
+    /// its attachments cannot otherwise be removed.
+    pub fn watch_only_export(&self) -> Result<Zewif> {
+        let mut export = self.clone();
+        export.content_changed();
+        export.attachments.clear();
+        for wallet in &mut export.wallets {
+            wallet.clear_spending_material();
+            wallet.attachments_mut().clear();
+            for account in wallet.accounts_mut() {
+                account.attachments_mut().clear();
+                for address in account.addresses_mut() {
+                    address.attachments_mut().clear();
+                }
+            }
+        }
+        for transaction in export.transactions.values_mut() {
+            transaction.get_mut()?.attachments_mut().clear();
+        }
+        Ok(export)
+    }
+
+    /// Reassigns contiguous indexes, in storage order, to every indexed
+    /// collection in the container.
+    ///
+    /// Containers that were edited by hand or only partially imported may have
+    /// missing, duplicated or out-of-order indexes. Since decoding orders
+    /// indexed items by their index, renumbering them keeps the encoding
+    /// deterministic.
+    pub fn reindex_all(&mut self) {
+        self.content_changed();
+        self.wallets = std::mem::take(&mut self.wallets).set_indexes();
+        for wallet in &mut self.wallets {
+            wallet.reindex_all();
+        }
+    }
+
+    /// Calls `f` with every fixed-size `Blob<N>` in the container.
+    ///
+    /// This is a building block for audits and transformations that need to
+    /// reach all binary values of one size, such as scanning every 32-byte value
+    /// for known key material. Within each wallet the visited fields are, in
+    /// order:
+    ///
+    /// - raw seeds, the wallet's own seed material before the seeds keyed by
+    ///   fingerprint;
+    /// - for each account, its address keys (transparent spending keys and
+    ///   Sapling incoming viewing, full viewing and spending keys), address
+    ///   diversifier indexes, and then the diversifiers of its Sapling and
+    ///   Orchard sent outputs;
+    /// - the components of the wallet's unified full viewing keys.
+    ///
+    /// Fixed-width integers such as `u256`, txids and seed fingerprints are not
+    /// blobs and are not visited, and neither are attachments, whose contents
+    /// are opaque to this crate.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Network, Seed, SeedMaterial, Zewif, ZewifWallet};
+    /// let mut wallet = ZewifWallet::new(Network::Main);
+    /// wallet.set_seed_material(SeedMaterial::Seed(Seed::new([7u8; 32])));
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_wallet(wallet);
+    ///
+    /// let mut count = 0;
+    /// zewif.visit_blobs::<32, _>(|blob| {
+    ///     assert_eq!(blob.as_slice(), &[7u8; 32]);
+    ///     count += 1;
+    /// });
+    /// assert_eq!(count, 1);
+    /// ```
+    pub fn visit_blobs<const N: usize, F: FnMut(&Blob<N>)>(&self, mut f: F) {
+        for wallet in &self.wallets {
+            wallet.visit_blobs(&mut f);
+        }
+    }
+
+    /// Returns every transaction in the container, whether decoded or held
+    /// lazily.
+    pub fn transactions(&self) -> &HashMap<TxId, StoredTransaction> {
         &self.transactions
     }
 
+    /// Adds a transaction, replacing any transaction held under the same txid.
     pub fn add_transaction(&mut self, txid: TxId, transaction: Transaction) {
-        self.transactions.insert(txid, transaction);
+        self.content_changed();
+        self.transactions.insert(txid, transaction.into());
     }
 
-    pub fn get_transaction(&self, txid: TxId) -> Option<&Transaction> {
+    /// Returns the transaction held under `txid`, in whichever form it is held.
+    ///
+    /// Use [`load_transaction`](Self::load_transaction) to get it decoded.
+    pub fn get_transaction(&self, txid: TxId) -> Option<&StoredTransaction> {
         self.transactions.get(&txid)
     }
 
     pub fn set_transactions(&mut self, transactions: HashMap<TxId, Transaction>) {
-        self.transactions = transactions;
+        self.content_changed();
+        self.transactions = transactions
+            .into_iter()
+            .map(|(txid, transaction)| (txid, transaction.into()))
+            .collect();
+    }
+
+    /// Returns every transaction in chronological order.
+    ///
+    /// Mined transactions come first, ordered by mined height and then by
+    /// their index within the block where it is known, followed by unmined
+    /// transactions. Remaining ties are broken by txid, so the order is the
+    /// same on every call for the same content. Lazily held transactions are
+    /// decoded and included.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode, since
+    /// its place in the order cannot be known.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{BlockHeight, Transaction, TxId, Zewif};
+    /// let mut zewif = Zewif::new();
+    /// for (byte, height) in [(1u8, None), (2, Some(200u32)), (3, Some(100))] {
+    ///     let txid = TxId::from_bytes([byte; 32]);
+    ///     let mut transaction = Transaction::new(txid);
+    ///     if let Some(height) = height {
+    ///         transaction.set_mined_height(BlockHeight::from(height));
+    ///     }
+    ///     zewif.add_transaction(txid, transaction);
+    /// }
+    /// let order: Vec<u8> = zewif
+    ///     .transactions_by_height()
+    ///     .unwrap()
+    ///     .map(|transaction| transaction.txid().as_ref()[0])
+    ///     .collect();
+    /// assert_eq!(order, [3, 2, 1]);
+    /// ```
+    pub fn transactions_by_height(&self) -> Result<impl Iterator<Item = &Transaction>> {
+        let mut transactions: Vec<&Transaction> = self
+            .transactions
+            .iter()
+            .map(|(txid, transaction)| {
+                transaction
+                    .get()
+                    .with_context(|| format!("transaction {}", txid))
+            })
+            .collect::<Result<_>>()?;
+        transactions.sort_by_key(|transaction| {
+            let height = transaction.mined_height().map(|height| u32::from(*height));
+            let index = transaction.block_position().map(TxBlockPosition::index);
+            (
+                height.is_none(),
+                height,
+                index.unwrap_or(u32::MAX),
+                transaction.txid(),
+            )
+        });
+        Ok(transactions.into_iter())
+    }
+
+    /// Adds a transaction to be decoded on first access, replacing any
+    /// transaction held under the same txid.
+    pub fn add_lazy_transaction(&mut self, transaction: LazyTransaction) {
+        self.content_changed();
+        self.transactions
+            .insert(transaction.txid(), transaction.into());
+    }
+
+    /// Returns the transaction with the given txid, decoding it first if it is
+    /// held lazily.
+    pub fn load_transaction(&self, txid: TxId) -> Result<Option<&Transaction>> {
+        self.transactions
+            .get(&txid)
+            .map(StoredTransaction::get)
+            .transpose()
+    }
+
+    /// Returns the transaction with the given txid for modification.
+    ///
+    /// See [`StoredTransaction::get_mut`].
+    pub fn load_transaction_mut(&mut self, txid: TxId) -> Result<Option<&mut Transaction>> {
+        self.content_changed();
+        self.transactions
+            .get_mut(&txid)
+            .map(StoredTransaction::get_mut)
+            .transpose()
+    }
+
+    /// Returns every wallet with at least one account that lists `txid` among
+    /// its relevant transactions.
+    ///
+    /// Transactions are stored once in the global history, but a single
+    /// transaction may be relevant to several wallets (for example, a transfer
+    /// between two wallets exported into the same interchange file). This is the
+    /// reverse lookup from a transaction to the wallets that reference it.
+    pub fn wallets_referencing(&self, txid: TxId) -> Vec<&ZewifWallet> {
+        self.wallets
+            .iter()
+            .filter(|wallet| {
+                wallet
+                    .accounts()
+                    .iter()
+                    .any(|account| account.relevant_transactions().contains(&txid))
+            })
+            .collect()
+    }
+
+    /// Builds a bloom filter over the hashes of every transparent address in
+    /// every wallet, sized for the given target false-positive rate.
+    ///
+    /// Each item inserted is the 20-byte public key or script hash decoded from
+    /// a transparent address, which is the form a light client matches against
+    /// transaction outputs. Only standalone transparent and TEX addresses are
+    /// covered: Sapling, Unified and other shielded addresses are not
+    /// included, and address strings that fail to decode are skipped.
+    pub fn address_bloom_filter(&self, false_positive_rate: f64) -> BloomFilter {
+        let hashes: Vec<[u8; 20]> = self
+            .wallets
+            .iter()
+            .flat_map(|wallet| wallet.accounts())
+            .flat_map(|account| account.addresses())
+            .filter_map(|address| match address.address() {
+                ProtocolAddress::Transparent(address) => address.hash(),
+                ProtocolAddress::Tex(address) => address.pubkey_hash(),
+                _ => None,
+            })
+            .collect();
+        let mut filter = BloomFilter::new(hashes.len(), false_positive_rate);
+        for hash in &hashes {
+            filter.insert(hash);
+        }
+        filter
+    }
+
+    /// Checks the container for referential inconsistencies.
+    ///
+    /// This is meant to be run by an importer before serializing, and reports
+    /// wallets that share an index, transactions referenced by an account but
+    /// not held by the container, lazily held transactions that fail to
+    /// decode, transactions stored under a txid other than their own, and
+    /// transactions mined below their target height. Issues are listed in that
+    /// order, with transaction issues ordered by txid; an empty result means
+    /// no issue was found.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{Account, Network, TxId, ValidationIssue, Zewif, ZewifWallet};
+    /// let txid = TxId::from_bytes([1u8; 32]);
+    /// let mut account = Account::new();
+    /// account.add_relevant_transaction(txid);
+    /// let mut wallet = ZewifWallet::new(Network::Main);
+    /// wallet.add_account(account);
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_wallet(wallet);
+    ///
+    /// assert_eq!(
+    ///     zewif.validate(),
+    ///     vec![ValidationIssue::MissingTransaction { wallet: 0, account: 0, txid }]
+    /// );
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+
+        let mut indexes = HashSet::new();
+        let duplicate_indexes: BTreeSet<usize> = self
+            .wallets
+            .iter()
+            .map(Indexed::index)
+            .filter(|index| !indexes.insert(*index))
+            .collect();
+        issues.extend(
+            duplicate_indexes
+                .into_iter()
+                .map(ValidationIssue::DuplicateWalletIndex),
+        );
+
+        for wallet in &self.wallets {
+            for account in wallet.accounts() {
+                let mut missing: Vec<TxId> = account
+                    .relevant_transactions()
+                    .iter()
+                    .filter(|txid| !self.transactions.contains_key(txid))
+                    .copied()
+                    .collect();
+                missing.sort();
+                issues.extend(missing.into_iter().map(|txid| {
+                    ValidationIssue::MissingTransaction {
+                        wallet: wallet.index(),
+                        account: account.index(),
+                        txid,
+                    }
+                }));
+            }
+        }
+
+        let held: BTreeSet<TxId> = self.transactions.keys().copied().collect();
+        for key in held {
+            let transaction = match self.transactions[&key].get() {
+                Ok(transaction) => transaction,
+                Err(error) => {
+                    issues.push(ValidationIssue::UndecodableTransaction(
+                        key,
+                        format!("{:#}", error),
+                    ));
+                    continue;
+                }
+            };
+            if transaction.txid() != key {
+                issues.push(ValidationIssue::TransactionKeyMismatch {
+                    key,
+                    txid: transaction.txid(),
+                });
+            }
+            if let (Some(target_height), Some(mined_height)) =
+                (transaction.target_height(), transaction.mined_height())
+                && u32::from(*mined_height) < u32::from(*target_height)
+            {
+                issues.push(ValidationIssue::MinedBeforeTarget {
+                    txid: key,
+                    target_height: *target_height,
+                    mined_height: *mined_height,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Returns the shielded spends whose anchor is not among `known_roots`.
+    ///
+    /// Every Sapling spend and Orchard bundle commits to the note commitment
+    /// tree root it proved membership against. Given the historical roots of
+    /// both trees, as reported by a full node, a spend whose anchor is not
+    /// among them points to a transaction that was recorded wrongly or damaged
+    /// in migration. Each such spend is returned with its transaction's id,
+    /// ordered by txid and then by spend order within the transaction. Lazily
+    /// held transactions are decoded for the check.
+    ///
+    /// # Errors
+    /// Returns an error if a lazily held transaction fails to decode, since
+    /// its anchors cannot be checked.
+    ///
+    /// # Examples
+    /// ```
+    /// # use std::collections::HashSet;
+    /// # use zewif::{Anchor, Transaction, TxId, Zewif, u256};
+    /// let known = Anchor::from(u256::try_from(&[1u8; 32]).unwrap());
+    /// let unknown = Anchor::from(u256::try_from(&[2u8; 32]).unwrap());
+    /// let txid = TxId::from_bytes([3u8; 32]);
+    /// let mut transaction = Transaction::new(txid);
+    /// transaction.add_sapling_anchor(known);
+    /// transaction.set_orchard_anchor(Some(unknown));
+    ///
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_transaction(txid, transaction);
+    /// let known_roots = HashSet::from([known]);
+    /// assert_eq!(zewif.verify_spend_anchors(&known_roots).unwrap(), vec![(txid, unknown)]);
+    /// ```
+    pub fn verify_spend_anchors(
+        &self,
+        known_roots: &HashSet<Anchor>,
+    ) -> Result<Vec<(TxId, Anchor)>> {
+        let transactions = self
+            .transactions
+            .iter()
+            .map(|(txid, transaction)| {
+                transaction
+                    .get()
+                    .with_context(|| format!("transaction {}", txid))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut unknown: Vec<(TxId, Anchor)> = transactions
+            .into_iter()
+            .flat_map(|transaction| {
+                transaction
+                    .sapling_anchors()
+                    .iter()
+                    .chain(transaction.orchard_anchor())
+                    .filter(|anchor| !known_roots.contains(*anchor))
+                    .map(|anchor| (transaction.txid(), *anchor))
+            })
+            .collect();
+        unknown.sort_by_key(|(txid, _)| *txid);
+        Ok(unknown)
+    }
+
+    /// Returns a digest of the container's content, leaving out its
+    /// identifier.
+    ///
+    /// The digest covers every wallet, transaction and attachment, and does
+    /// not depend on the order in which they were added, so it identifies the
+    /// container's content independently of how it was built or stored. It is
+    /// made from the digests of the assertions of the container's envelope,
+    /// which the envelope keeps in digest order, but not from its subject, so
+    /// two exports of the same data under different [`id`](Self::id)s have the
+    /// same content digest. This makes it suitable for caching and
+    /// deduplicating exports, where `id` tells apart exports that are
+    /// otherwise identical.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bc_components::ARID;
+    /// # use zewif::{Transaction, TxId, Zewif};
+    /// let txid = TxId::from_bytes([1u8; 32]);
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_transaction(txid, Transaction::new(txid));
+    ///
+    /// let mut copy = Zewif::with_id(ARID::new());
+    /// copy.add_transaction(txid, Transaction::new(txid));
+    /// assert_ne!(copy.id(), zewif.id());
+    /// assert_eq!(copy.content_digest(), zewif.content_digest());
+    /// ```
+    pub fn content_digest(&self) -> Digest {
+        self.content_digest
+            .get_or_init(|| {
+                let assertion_digests: Vec<Digest> = Envelope::from(self.clone())
+                    .assertions()
+                    .iter()
+                    .map(|assertion| assertion.digest().into_owned())
+                    .collect();
+                Digest::from_digests(&assertion_digests)
+            })
+            .clone()
+    }
+
+    /// Returns `true` if `self == other`, checking cheap properties and
+    /// content digests before any deep comparison.
+    ///
+    /// Containers with different identifiers, numbers of wallets or numbers of
+    /// transactions are rejected at once, and containers with different
+    /// [`content_digest`](Self::content_digest)s are rejected next. A digest
+    /// match is confirmed with `==`, so a digest collision cannot make unequal
+    /// containers compare equal.
+    ///
+    /// The digest is cached on each container until it is next modified.
+    /// Computing it encodes the whole container, which costs far more than a
+    /// single `==`, so this pays off only when the same containers are
+    /// compared many times, as in a merge or deduplication loop. Once cached,
+    /// containers with differing content are told apart without looking at
+    /// any wallet or transaction.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bc_envelope::prelude::*;
+    /// # use zewif::{Transaction, TxId, Zewif};
+    /// let txid = TxId::from_bytes([1u8; 32]);
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_transaction(txid, Transaction::new(txid));
+    ///
+    /// let lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif.clone())).unwrap();
+    /// assert!(zewif.content_eq_fast(&lazy));
+    /// assert!(!zewif.content_eq_fast(&Zewif::with_id(zewif.id())));
+    /// ```
+    pub fn content_eq_fast(&self, other: &Zewif) -> bool {
+        self.id == other.id
+            && self.wallets.len() == other.wallets.len()
+            && self.transactions.len() == other.transactions.len()
+            && self.content_digest() == other.content_digest()
+            && self == other
+    }
+
+    /// Clears the cached content digest, ahead of a change to the content.
+    fn content_changed(&mut self) {
+        self.content_digest.take();
+    }
+
+    /// Writes the container's envelope encoding to `w`, one assertion at a
+    /// time.
+    ///
+    /// The bytes written are identical to
+    /// `Envelope::from(zewif).to_cbor_data()`, so any reader of that encoding
+    /// reads them unchanged, but the full envelope tree is never built. An
+    /// envelope stores its assertions in digest order, so the writer makes two
+    /// passes: the first encodes each wallet and transaction only to record
+    /// its digest, and the second encodes them again in digest order and
+    /// writes each one before encoding the next. Beyond the container itself,
+    /// memory use is one digest and key per assertion plus the largest single
+    /// assertion, at the cost of encoding every assertion twice.
+    ///
+    /// # Errors
+    /// Returns any error reported by `w`.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bc_envelope::prelude::*;
+    /// # use zewif::{Transaction, TxId, Zewif};
+    /// let txid = TxId::from_bytes([1u8; 32]);
+    /// let mut zewif = Zewif::new();
+    /// zewif.add_transaction(txid, Transaction::new(txid));
+    ///
+    /// let mut bytes = Vec::new();
+    /// zewif.write_envelope(&mut bytes).unwrap();
+    /// assert_eq!(bytes, Envelope::from(zewif).to_cbor_data());
+    /// ```
+    pub fn write_envelope<W: Write>(&self, w: &mut W) -> Result<()> {
+        let header = encode_attachments(
+            &self.attachments,
+            Envelope::new(self.id)
+                .add_type("Zewif")
+                .add_optional_assertion("export_height", self.export_height),
+        );
+        let header_assertions = header.assertions();
+
+        let mut entries: Vec<(Digest, AssertionSource)> = (0..header_assertions.len())
+            .map(AssertionSource::Header)
+            .chain((0..self.wallets.len()).map(AssertionSource::Wallet))
+            .chain(
+                self.transactions
+                    .keys()
+                    .copied()
+                    .map(AssertionSource::Transaction),
+            )
+            .map(|source| {
+                let digest = self
+                    .source_assertion(&header_assertions, &source)
+                    .digest()
+                    .into_owned();
+                (digest, source)
+            })
+            .collect();
+        // Envelopes hold each distinct assertion once, in digest order.
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        entries.dedup_by(|a, b| a.0 == b.0);
+
+        write_cbor_head(w, 6, bc_components::tags::TAG_ENVELOPE)?;
+        write_cbor_head(w, 4, 1 + entries.len() as u64)?;
+        w.write_all(&header.subject().untagged_cbor().to_cbor_data())?;
+        for (_, source) in entries {
+            let assertion = self.source_assertion(&header_assertions, &source);
+            w.write_all(&assertion.untagged_cbor().to_cbor_data())?;
+        }
+        Ok(())
+    }
+
+    fn source_assertion(
+        &self,
+        header_assertions: &[Envelope],
+        source: &AssertionSource,
+    ) -> Envelope {
+        match source {
+            AssertionSource::Header(index) => header_assertions[*index].clone(),
+            AssertionSource::Wallet(index) => {
+                Envelope::new_assertion("wallet", self.wallets[*index].clone())
+            }
+            AssertionSource::Transaction(txid) => {
+                Envelope::new_assertion("transaction", self.transactions[txid].clone())
+            }
+        }
+    }
+
+    /// Signs the digest of the container's envelope, so that a recipient can
+    /// check that an export came from the holder of `key`.
+    ///
+    /// Unlike [`content_digest`](Self::content_digest), the signed digest
+    /// covers the container's identifier as well as its content.
+    ///
+    /// The signature is detached: it is not stored in the container, and must
+    /// be passed to the recipient alongside it.
+    ///
+    /// # Errors
+    /// Returns an error if `key` cannot produce signatures.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bc_components::{ECPrivateKey, SigningPrivateKey};
+    /// # use zewif::Zewif;
+    /// let key = SigningPrivateKey::new_schnorr(ECPrivateKey::new());
+    /// let zewif = Zewif::new();
+    /// let signature = zewif.sign(&key).unwrap();
+    /// assert!(zewif.verify_signature(&signature, &key.public_key().unwrap()));
+    /// ```
+    pub fn sign(&self, key: &SigningPrivateKey) -> Result<Signature> {
+        key.sign(Envelope::from(self.clone()).digest().as_ref())
+            .context("Signing the Zewif content digest")
+    }
+
+    /// Returns `true` if `signature` was made by [`sign`](Self::sign) over this
+    /// container's content with the private key matching `key`.
+    pub fn verify_signature(&self, signature: &Signature, key: &SigningPublicKey) -> bool {
+        key.verify(signature, Envelope::from(self.clone()).digest().as_ref())
+    }
+
+    /// Returns the envelope encoding of this container, compressed and then
+    /// locked with `passphrase`.
+    ///
+    /// This is [`ZewifEnvelope::compress`] followed by
+    /// [`ZewifEnvelope::lock`]: the result is a `Zewif` envelope that keeps
+    /// the container's ARID in the clear, with the protected envelope as its
+    /// `content`. The content is encrypted with a random key, which is itself
+    /// locked with a key derived from `passphrase` by scrypt. The scrypt salt
+    /// and cost parameters travel with the locked key, so
+    /// [`from_encrypted_envelope`](Self::from_encrypted_envelope) needs
+    /// nothing but the passphrase. Decoding the result as a plaintext `Zewif`
+    /// fails rather than yielding an empty container.
+    ///
+    /// # Errors
+    /// Returns an error if the envelope cannot be compressed.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// # use zewif::Zewif;
+    /// let zewif = Zewif::new();
+    /// let envelope = zewif.to_encrypted_envelope("correct horse battery staple").unwrap();
+    /// let decrypted =
+    ///     Zewif::from_encrypted_envelope(envelope, "correct horse battery staple").unwrap();
+    /// assert_eq!(decrypted, zewif);
+    /// ```
+    pub fn to_encrypted_envelope(&self, passphrase: &str) -> Result<Envelope> {
+        let mut envelope = ZewifEnvelope::new(Envelope::from(self.clone()))?;
+        envelope
+            .compress()
+            .context("Compressing the Zewif envelope")?;
+        envelope.lock(passphrase)?;
+        Ok(envelope.envelope().clone())
+    }
+
+    /// Unlocks and decodes an envelope made by
+    /// [`to_encrypted_envelope`](Self::to_encrypted_envelope), or by
+    /// [`ZewifEnvelope::lock`] with or without compression.
+    ///
+    /// # Errors
+    /// Returns an error if `envelope` is not a Zewif envelope locked with a
+    /// passphrase, if `passphrase` does not unlock it, or if the decrypted
+    /// container does not decode or has a different ARID from the one in the
+    /// clear.
+    pub fn from_encrypted_envelope(envelope: Envelope, passphrase: &str) -> Result<Self> {
+        let mut envelope = ZewifEnvelope::new(envelope)?;
+        let id = envelope.id();
+        envelope.unlock(passphrase)?;
+        if envelope.can_uncompress() {
+            envelope
+                .uncompress()
+                .context("Uncompressing the Zewif content")?;
+        }
+        let zewif = Zewif::try_from(envelope.envelope().clone())?;
+        if zewif.id != id {
+            bail!(
+                "Encrypted Zewif content has ARID {}, but the envelope is labelled {}",
+                zewif.id,
+                id
+            );
+        }
+        Ok(zewif)
+    }
+
+    /// Returns the CBOR encoding of this container's envelope, compressed at
+    /// `level`.
+    ///
+    /// The result opens with a small header of magic bytes, a version, the
+    /// compression algorithm and the uncompressed length, so
+    /// [`from_compressed_cbor`](Self::from_compressed_cbor) can read it back
+    /// without being told how it was made. The payload is compressed with
+    /// zstd when the `zstd` feature is enabled, and with DEFLATE, the
+    /// algorithm used by gzip, otherwise.
+    ///
+    /// # Errors
+    /// Returns an error if the container cannot be encoded or compressed.
+    ///
+    /// # Examples
+    /// ```
+    /// # use zewif::{CompressionLevel, Zewif};
+    /// let zewif = Zewif::new();
+    /// let bytes = zewif.to_compressed_cbor(CompressionLevel::Best).unwrap();
+    /// assert_eq!(Zewif::from_compressed_cbor(&bytes).unwrap(), zewif);
+    /// ```
+    pub fn to_compressed_cbor(&self, level: CompressionLevel) -> Result<Vec<u8>> {
+        let mut cbor = Vec::new();
+        self.write_envelope(&mut cbor)?;
+        compress_container(&cbor, level)
     }
+
+    /// Decodes a container made by [`to_compressed_cbor`](Self::to_compressed_cbor).
+    ///
+    /// DEFLATE containers are always readable; zstd containers need the
+    /// `zstd` feature.
+    ///
+    /// # Errors
+    /// Returns an error if the header is malformed or names an unsupported
+    /// algorithm, if the payload does not decompress to the length the header
+    /// declares, or if the decompressed CBOR is not a Zewif envelope.
+    pub fn from_compressed_cbor(data: &[u8]) -> Result<Self> {
+        let cbor = decompress_container(data)?;
+        let envelope =
+            Envelope::try_from_cbor_data(cbor).context("Decoding the decompressed envelope")?;
+        Zewif::try_from(envelope)
+    }
+
+    /// Returns the envelope encoding of this container, wrapped and signed by
+    /// `signer`.
+    ///
+    /// Unlike [`sign`](Self::sign), the signature travels inside the result:
+    /// the container's envelope is wrapped, so the signature covers its
+    /// assertions as well as its subject, and the wrapper carries a `'signed'`
+    /// assertion in the form made by Gordian Envelope's `Envelope::sign`.
+    /// `signer` may be a `PrivateKeyBase`, or any other `Signer`.
+    ///
+    /// # Errors
+    /// Returns an error if `signer` cannot produce a signature.
+    ///
+    /// # Examples
+    /// ```
+    /// # use bc_components::PrivateKeyBase;
+    /// # use zewif::Zewif;
+    /// let key = PrivateKeyBase::new();
+    /// let zewif = Zewif::new();
+    /// let envelope = zewif.to_signed_envelope(&key).unwrap();
+    /// let verified = Zewif::from_signed_envelope(envelope, &key.schnorr_public_keys()).unwrap();
+    /// assert_eq!(verified, zewif);
+    /// ```
+    pub fn to_signed_envelope(&self, signer: &dyn Signer) -> Result<Envelope> {
+        let wrapped = Envelope::from(self.clone()).wrap_envelope();
+        let signature = signer
+            .sign(wrapped.subject().digest().as_ref())
+            .context("Signing the Zewif envelope")?;
+        Ok(wrapped.add_assertion(known_values::SIGNED, signature))
+    }
+
+    /// Checks the signature on an envelope made by
+    /// [`to_signed_envelope`](Self::to_signed_envelope) with Gordian
+    /// Envelope's `Envelope::verify`, then decodes the container inside it.
+    ///
+    /// # Errors
+    /// Returns an error if `envelope` has no valid signature from `verifier`,
+    /// which is also the case if it was altered after signing, or if the
+    /// signed content does not decode as a Zewif container.
+    pub fn from_signed_envelope(envelope: Envelope, verifier: &dyn Verifier) -> Result<Self> {
+        let content = envelope
+            .verify(verifier)
+            .context("Verifying the signature on the Zewif envelope")?;
+        Zewif::try_from(content)
+    }
+
+    /// Returns anonymized aggregate statistics about this container.
+    ///
+    /// See [`WalletStats`] for what is collected.
+    pub fn statistics(&self) -> WalletStats {
+        WalletStats::from(self)
+    }
+
+    /// Splits the envelope encoding of this container into multipart UR
+    /// fragments for transfer as an animated QR code.
+    ///
+    /// Each part is a `ur:envelope/...` string whose fragment payload is at most
+    /// `max_fragment_len` bytes, so smaller values yield more parts that each fit
+    /// a lower-density QR code. The parts are the fountain-coded sequence from
+    /// [`MultipartEncoder`], starting with the fragments needed for complete
+    /// reassembly.
+    pub fn to_ur_fragments(&self, max_fragment_len: usize) -> Result<Vec<String>> {
+        let envelope = Envelope::from(self.clone());
+        let ur = UR::new("envelope", envelope.untagged_cbor())?;
+        let mut encoder = MultipartEncoder::new(&ur, max_fragment_len)?;
+        (0..encoder.parts_count())
+            .map(|_| Ok(encoder.next_part()?))
+            .collect()
+    }
+
+    /// Reassembles a container from the parts produced by
+    /// [`to_ur_fragments`](Self::to_ur_fragments).
+    ///
+    /// Parts may arrive in any order and may be repeated, as happens when
+    /// scanning an animated QR code.
+    pub fn from_ur_fragments(parts: &[String]) -> Result<Self> {
+        let mut decoder = MultipartDecoder::new();
+        for part in parts {
+            decoder.receive(part).context("UR fragment")?;
+            if decoder.is_complete() {
+                break;
+            }
+        }
+        let Some(ur) = decoder.message()? else {
+            bail!("Incomplete UR fragments: more parts are needed to reassemble the container");
+        };
+        ur.check_type("envelope")?;
+        let envelope = Envelope::from_untagged_cbor(ur.cbor())?;
+        Zewif::try_from(envelope)
+    }
+
+    /// Checks that the container survives a round trip through its envelope
+    /// encoding unchanged.
+    ///
+    /// The container is encoded, decoded and encoded again. The decoded
+    /// container must equal this one, and the two encodings must be identical
+    /// byte for byte. A failure means some field is lost or altered by the
+    /// encoding, which makes this a useful final check for a migration tool
+    /// before it writes its output.
+    pub fn assert_roundtrip_identity(&self) -> Result<()> {
+        check_roundtrip_identity("Zewif", self)
+    }
+}
+
+/// An assertion of the container's envelope, identified without encoding it.
+enum AssertionSource {
+    /// The type assertion or an attachment, by position in the header envelope.
+    Header(usize),
+    Wallet(usize),
+    Transaction(TxId),
+}
+
+/// Writes the head of a CBOR data item in its shortest form, as dCBOR requires.
+fn write_cbor_head<W: Write>(w: &mut W, major_type: u8, value: u64) -> std::io::Result<()> {
+    let major_type = major_type << 5;
+    match value {
+        0..=23 => w.write_all(&[major_type | value as u8]),
+        24..=0xff => w.write_all(&[major_type | 24, value as u8]),
+        0x100..=0xffff => {
+            w.write_all(&[major_type | 25])?;
+            w.write_all(&(value as u16).to_be_bytes())
+        }
+        0x1_0000..=0xffff_ffff => {
+            w.write_all(&[major_type | 26])?;
+            w.write_all(&(value as u32).to_be_bytes())
+        }
+        _ => {
+            w.write_all(&[major_type | 27])?;
+            w.write_all(&value.to_be_bytes())
+        }
+    }
+}
+
+/// Encodes `value`, decodes it and encodes the result again, failing unless
+/// the encodings are identical and the decoded value equals `value`.
+fn check_roundtrip_identity<T>(name: &str, value: &T) -> Result<()>
+where
+    T: Clone + PartialEq + Into<Envelope> + TryFrom<Envelope, Error = anyhow::Error>,
+{
+    let envelope: Envelope = value.clone().into();
+    let decoded = T::try_from(envelope.clone())
+        .with_context(|| format!("{} does not decode from its own envelope", name))?;
+    if decoded != *value {
+        bail!("{} changed when decoded from its envelope", name);
+    }
+    let reencoded: Envelope = decoded.into();
+    if reencoded.to_cbor_data() != envelope.to_cbor_data() {
+        bail!(
+            "{} envelope changed when re-encoded: digest {} became {}",
+            name,
+            envelope.digest(),
+            reencoded.digest()
+        );
+    }
+    Ok(())
 }
 
 impl Default for Zewif {
@@ -118,10 +1123,11 @@ impl Default for Zewif {
 impl From<Zewif> for Envelope {
     fn from(value: Zewif) -> Self {
         let mut e = Envelope::new(value.id)
-            .add_type("Zewif");
+            .add_type("Zewif")
+            .add_optional_assertion("export_height", value.export_height);
         e = value.wallets.iter().fold(e, |e, wallet| e.add_assertion("wallet", wallet.clone()));
-        e = value.transactions.iter().fold(e, |e, (_, transaction)| e.add_assertion("transaction", transaction.clone()));
-        value.attachments.add_to_envelope(e)
+        e = value.transactions.into_values().fold(e, |e, transaction| e.add_assertion("transaction", transaction));
+        encode_attachments(&value.attachments, e)
     }
 }
 
@@ -130,39 +1136,1256 @@ impl TryFrom<Envelope> for Zewif {
     type Error = anyhow::Error;
 
     fn try_from(envelope: Envelope) -> Result<Self, Self::Error> {
+        Self::decode_envelope(envelope, false)
+    }
+}
+
+#[rustfmt::skip]
+impl Zewif {
+    fn decode_envelope(envelope: Envelope, lazy: bool) -> Result<Self> {
         envelope.check_type_envelope("Zewif")?;
+        if !envelope.assertions_with_predicate("content").is_empty() {
+            bail!("Zewif envelope is compressed or encrypted; open it with ZewifEnvelope first");
+        }
         let id = envelope.extract_subject()?;
 
         let wallets = envelope_indexed_objects_for_predicate(&envelope, "wallet")?;
 
-        let transactions = envelope
-            .try_objects_for_predicate::<Transaction>("transaction")?
-            .into_iter().map(|tx| (tx.txid(), tx)).collect();
+        let transactions = if lazy {
+            envelope
+                .try_objects_for_predicate::<LazyTransaction>("transaction")?
+                .into_iter().map(|tx| (tx.txid(), tx.into())).collect()
+        } else {
+            envelope
+                .try_objects_for_predicate::<Transaction>("transaction")?
+                .into_iter().map(|tx| (tx.txid(), tx.into())).collect()
+        };
+
+        let export_height = envelope.try_optional_object_for_predicate("export_height").context("export_height")?;
 
-        let attachments = Attachments::try_from_envelope(&envelope).context("attachments")?;
+        let attachments = decode_attachments::<Self>(&envelope).context("attachments")?;
 
         Ok(Self {
             id,
             wallets,
             transactions,
+            export_height,
             attachments,
+            content_digest: OnceLock::new(),
         })
     }
 }
 
+impl crate::EnvelopeSchema for Zewif {
+    const TYPE_NAME: &'static str = "Zewif";
+    const ATTACHMENTS: bool = true;
+
+    fn required_predicates() -> &'static [&'static str] {
+        &[]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["wallet", "transaction", "export_height"]
+    }
+
+    fn check_nested_strict(envelope: &Envelope) -> Result<()> {
+        crate::check_objects_strict::<ZewifWallet>(envelope, "wallet")?;
+        crate::check_objects_strict::<Transaction>(envelope, "transaction")
+    }
+}
+
 #[cfg(test)]
 #[rustfmt::skip]
 impl crate::RandomInstance for Zewif {
     fn random() -> Self {
-        use crate::SetIndexes;
-
         Self {
             id: ARID::new(),
             wallets: Vec::random().set_indexes(),
-            transactions: Vec::<Transaction>::random().iter().map(|tx| (tx.txid(), tx.clone())).collect(),
+            transactions: Vec::<Transaction>::random().into_iter().map(|tx| (tx.txid(), tx.into())).collect(),
+            export_height: BlockHeight::opt_random(),
             attachments: Attachments::random(),
+            content_digest: OnceLock::new(),
         }
     }
 }
 
 test_envelope_roundtrip!(Zewif);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+
+    use bc_components::{
+        ARID, ECPrivateKey, PrivateKeyBase, Signature, Signer, SigningOptions, SigningPrivateKey,
+    };
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, Amount, Anchor, Blob, BlockHash, BlockHeight, CompressionLevel, Indexed,
+        LazyTransaction, MergeReport, Network, OrchardSentOutput, ProtocolAddress, RandomInstance,
+        Seed, SeedFingerprint, SeedMaterial, StoredTransaction, Transaction, TxBlockPosition, TxId,
+        ValidationIssue, ZewifEnvelope, ZewifWallet, keys, sapling, sapling::SaplingSentOutput,
+        sha256, transparent, u256,
+    };
+
+    use super::{Zewif, check_roundtrip_identity};
+
+    fn wallet_with_transactions(txids: &[TxId]) -> ZewifWallet {
+        let mut account = Account::new();
+        for txid in txids {
+            account.add_relevant_transaction(*txid);
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        wallet
+    }
+
+    #[test]
+    fn test_wallets_referencing_shared_transaction() {
+        let shared = TxId::from_bytes([1u8; 32]);
+        let only_first = TxId::from_bytes([2u8; 32]);
+        let unreferenced = TxId::from_bytes([3u8; 32]);
+
+        let mut zewif = Zewif::new();
+        zewif.add_transaction(shared, Transaction::new(shared));
+        zewif.add_transaction(only_first, Transaction::new(only_first));
+        zewif.add_wallet(wallet_with_transactions(&[shared, only_first]));
+        zewif.add_wallet(wallet_with_transactions(&[shared]));
+        zewif.add_wallet(wallet_with_transactions(&[]));
+
+        let referencing = zewif.wallets_referencing(shared);
+        assert_eq!(referencing.len(), 2);
+        assert_eq!(referencing[0], &zewif.wallets()[0]);
+        assert_eq!(referencing[1], &zewif.wallets()[1]);
+
+        let referencing = zewif.wallets_referencing(only_first);
+        assert_eq!(referencing, vec![&zewif.wallets()[0]]);
+
+        assert!(zewif.wallets_referencing(unreferenced).is_empty());
+    }
+
+    #[test]
+    fn test_address_bloom_filter() {
+        let mut account = Account::new();
+        let mut hashes = Vec::new();
+        for i in 0..200u32 {
+            let digest = sha256(i.to_le_bytes());
+            let digest: &[u8] = digest.as_ref();
+            let hash: [u8; 20] = digest[..20].try_into().unwrap();
+            let address = if i % 2 == 0 {
+                transparent::Address::from_pubkey_hash(Network::Main, &hash)
+            } else {
+                transparent::Address::from_script_hash(Network::Main, &hash)
+            };
+            account.add_address(Address::new(ProtocolAddress::Transparent(address)));
+            hashes.push(hash);
+        }
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("undecodable"),
+        )));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+
+        let rate = 0.01;
+        let filter = zewif.address_bloom_filter(rate);
+        for hash in &hashes {
+            assert!(filter.contains(hash));
+        }
+
+        let queries = 10_000u32;
+        let false_positives = (0..queries)
+            .filter(|i| {
+                let digest = sha256([b"query".as_slice(), &i.to_le_bytes()].concat());
+                let digest: &[u8] = digest.as_ref();
+                filter.contains(&digest[..20])
+            })
+            .count();
+        assert!((false_positives as f64 / queries as f64) < rate * 3.0);
+    }
+
+    #[test]
+    fn test_ur_fragments_roundtrip() {
+        let mut zewif = Zewif::new();
+        for i in 0..4u8 {
+            let txid = TxId::from_bytes([i; 32]);
+            zewif.add_transaction(txid, Transaction::new(txid));
+        }
+        zewif.add_wallet(wallet_with_transactions(&[TxId::from_bytes([0u8; 32])]));
+
+        let mut parts = zewif.to_ur_fragments(40).unwrap();
+        assert!(parts.len() > 4);
+        assert!(parts.iter().all(|part| part.starts_with("ur:envelope/")));
+
+        // Out of order, with duplicates.
+        parts.reverse();
+        parts.insert(1, parts[0].clone());
+        parts.push(parts[2].clone());
+        assert_eq!(Zewif::from_ur_fragments(&parts).unwrap(), zewif);
+
+        // Missing a part.
+        let missing = parts[3].clone();
+        parts.retain(|part| part != &missing);
+        assert!(Zewif::from_ur_fragments(&parts).is_err());
+    }
+
+    #[test]
+    fn test_lazy_transactions_decode_on_access() {
+        let mut zewif = Zewif::new();
+        for i in 0..5u8 {
+            let txid = TxId::from_bytes([i; 32]);
+            let mut tx = Transaction::new(txid);
+            tx.set_mined_height(BlockHeight::from_u32(1000 + i as u32));
+            zewif.add_transaction(txid, tx);
+        }
+        let envelope = Envelope::from(zewif.clone());
+
+        let before = LazyTransaction::decode_count();
+        let lazy = Zewif::try_from_envelope_lazy(envelope).unwrap();
+        assert_eq!(lazy.transactions().len(), 5);
+        assert!(
+            lazy.transactions()
+                .values()
+                .all(|tx| matches!(tx, StoredTransaction::Lazy(_)))
+        );
+        assert_eq!(LazyTransaction::decode_count(), before);
+
+        let txid = TxId::from_bytes([3u8; 32]);
+        let tx = lazy.load_transaction(txid).unwrap().unwrap();
+        assert_eq!(Some(tx), zewif.load_transaction(txid).unwrap());
+        assert_eq!(LazyTransaction::decode_count(), before + 1);
+
+        // Memoized, and untouched transactions stay undecoded.
+        lazy.load_transaction(txid).unwrap();
+        assert_eq!(LazyTransaction::decode_count(), before + 1);
+        let decoded = lazy
+            .transactions()
+            .values()
+            .filter(|tx| tx.is_decoded())
+            .count();
+        assert_eq!(decoded, 1);
+
+        // A lazily loaded container equals the eager one it was encoded from.
+        assert_eq!(lazy, zewif);
+
+        // Re-encoding a lazily loaded container reproduces the original.
+        assert_eq!(Zewif::try_from(Envelope::from(lazy)).unwrap(), zewif);
+    }
+
+    #[test]
+    fn test_transaction_held_once() {
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut labelled = Transaction::new(txid);
+        labelled.set_label("label");
+
+        let mut zewif = Zewif::new();
+        zewif.add_transaction(txid, Transaction::new(txid));
+        zewif.add_lazy_transaction(LazyTransaction::new(Envelope::from(labelled.clone())).unwrap());
+        assert_eq!(zewif.transactions().len(), 1);
+        assert_eq!(zewif.load_transaction(txid).unwrap(), Some(&labelled));
+        let envelope = Envelope::from(zewif.clone());
+        assert_eq!(envelope.objects_for_predicate("transaction").len(), 1);
+
+        zewif
+            .load_transaction_mut(txid)
+            .unwrap()
+            .unwrap()
+            .set_label("changed");
+        assert!(zewif.get_transaction(txid).unwrap().is_decoded());
+        assert_eq!(
+            zewif.load_transaction(txid).unwrap().unwrap().label(),
+            Some("changed")
+        );
+    }
+
+    #[test]
+    fn test_attachment_encoding_ignores_insertion_order() {
+        let entries = [
+            ("first", "org.example.b", Some("spec-2")),
+            ("second", "org.example.a", None),
+            ("third", "org.example.a", Some("spec-1")),
+        ];
+
+        let id = Zewif::new().id();
+        let mut forward = Zewif::with_id(id);
+        for (payload, vendor, conforms_to) in entries {
+            forward.attachments_mut().add(payload, vendor, conforms_to);
+        }
+        let mut reverse = Zewif::with_id(id);
+        for (payload, vendor, conforms_to) in entries.into_iter().rev() {
+            reverse.attachments_mut().add(payload, vendor, conforms_to);
+        }
+
+        assert_eq!(
+            Envelope::from(forward).to_cbor_data(),
+            Envelope::from(reverse).to_cbor_data()
+        );
+    }
+
+    #[test]
+    fn test_statistics_histograms() {
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1first"),
+        )));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(
+            sapling::Address::new("zs1second".to_string()),
+        ))));
+        for zats in [0, 5, 1_234, 9_999] {
+            let mut output = SaplingSentOutput::new();
+            output.set_value(Amount::from_u64(zats).unwrap());
+            account.add_sapling_sent_output(output);
+        }
+        account.add_orchard_sent_output(OrchardSentOutput::new(
+            Blob::default(),
+            u256::default(),
+            Amount::from_u64(1_000).unwrap(),
+            u256::default(),
+            u256::default(),
+            u256::default(),
+        ));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        wallet.add_account(Account::new());
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+        for (byte, height) in [
+            (1u8, Some(419_200)),
+            (2, Some(419_999)),
+            (3, Some(420_000)),
+            (4, None),
+        ] {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut transaction = Transaction::new(txid);
+            if let Some(height) = height {
+                transaction.set_mined_height(BlockHeight::from_u32(height));
+            }
+            zewif.add_transaction(txid, transaction);
+        }
+
+        let stats = zewif.statistics();
+        assert_eq!(stats.wallets, 1);
+        assert_eq!(stats.accounts, 2);
+        assert_eq!(stats.transparent_addresses, 1);
+        assert_eq!(stats.sapling_addresses, 1);
+        assert_eq!(stats.unified_addresses, 0);
+        assert_eq!(stats.transactions, 4);
+        assert_eq!(stats.unmined_transactions, 1);
+        assert_eq!(
+            stats.transactions_by_height,
+            BTreeMap::from([(410_000, 2), (420_000, 1)])
+        );
+        assert_eq!(
+            stats.sent_output_values,
+            BTreeMap::from([(0, 1), (1, 1), (1_000, 3)])
+        );
+        assert_eq!(stats.sent_output_pool_shares(), Some((0.8, 0.2)));
+    }
+
+    #[test]
+    fn test_reindex_all() {
+        let mut wallet = ZewifWallet::new(Network::Main);
+        for _ in 0..3 {
+            let mut account = Account::new();
+            for name in ["t1a", "t1b", "t1c"] {
+                account.add_address(Address::new(ProtocolAddress::Transparent(
+                    transparent::Address::new(name),
+                )));
+            }
+            account.add_sapling_sent_output(SaplingSentOutput::new());
+            account.add_sapling_sent_output(SaplingSentOutput::new());
+            wallet.add_account(account);
+        }
+        let mut ufvk = keys::UnifiedFullViewingKey::new();
+        ufvk.set_orchard(keys::OrchardFullViewingKey::new([7u8; 96]));
+        wallet.set_viewing_key(5, ufvk.clone());
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet.clone());
+        zewif.add_wallet(wallet);
+
+        // Scramble the indexes: zeros, duplicates and gaps.
+        for wallet in zewif.wallets_mut() {
+            wallet.set_index(0);
+            for (i, account) in wallet.accounts_mut().iter_mut().enumerate() {
+                account.set_index([0, 5, 0][i]);
+                for address in account.addresses_mut() {
+                    address.set_index(9);
+                }
+            }
+        }
+
+        zewif.reindex_all();
+
+        for (i, wallet) in zewif.wallets().iter().enumerate() {
+            assert_eq!(wallet.index(), i);
+            for (j, account) in wallet.accounts().iter().enumerate() {
+                assert_eq!(account.index(), j);
+                let indexes: Vec<usize> = account.addresses().iter().map(Indexed::index).collect();
+                assert_eq!(indexes, [0, 1, 2]);
+                let indexes: Vec<usize> = account
+                    .sapling_sent_outputs()
+                    .iter()
+                    .map(Indexed::index)
+                    .collect();
+                assert_eq!(indexes, [0, 1]);
+            }
+            // The viewing key follows the account that had index 5.
+            assert_eq!(wallet.viewing_key(1), Some(&ufvk));
+            assert_eq!(wallet.viewing_keys().len(), 1);
+        }
+
+        let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+        assert_eq!(decoded, zewif);
+    }
+
+    #[test]
+    fn test_watch_only_export() {
+        let mut p2pkh = transparent::Address::new("t1spend");
+        p2pkh.set_spend_authority(transparent::TransparentSpendAuthority::Derived);
+        let mut shielded = sapling::Address::new("zs1spend".to_string());
+        let fvk = sapling::SaplingExtendedFullViewingKey::random();
+        shielded.set_full_viewing_key(fvk.clone());
+        shielded.set_spending_key(sapling::SaplingExtendedSpendingKey::random());
+
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(p2pkh)));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(shielded))));
+        account.add_sapling_sent_output(SaplingSentOutput::random());
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.set_seed_material(SeedMaterial::Seed(Seed::new([1u8; 32])));
+        wallet.add_seed(
+            SeedFingerprint::from_bytes([2u8; 32]),
+            SeedMaterial::Seed(Seed::new([3u8; 32])),
+        );
+        wallet.add_account(account);
+        let mut ufvk = keys::UnifiedFullViewingKey::new();
+        ufvk.set_orchard(keys::OrchardFullViewingKey::new([4u8; 96]));
+        wallet.set_viewing_key(0, ufvk.clone());
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+        let txid = TxId::from_bytes([5u8; 32]);
+        zewif.add_transaction(txid, Transaction::new(txid));
+        assert!(!zewif.is_watch_only());
+
+        let export = zewif.watch_only_export().unwrap();
+        assert!(export.is_watch_only());
+        // The original is untouched.
+        assert!(!zewif.is_watch_only());
+
+        let wallet = &export.wallets()[0];
+        assert!(wallet.seed_material().is_none());
+        assert!(wallet.seeds().is_empty());
+        let addresses = wallet.accounts()[0].addresses();
+        assert!(addresses.iter().all(|a| !a.address().has_spending_key()));
+        let ProtocolAddress::Sapling(shielded) = addresses[1].address() else {
+            panic!("expected a Sapling address");
+        };
+        assert_eq!(shielded.full_viewing_key(), Some(&fvk));
+        assert_eq!(wallet.viewing_key(0), Some(&ufvk));
+        assert_eq!(wallet.accounts()[0].sapling_sent_outputs_len(), 1);
+        assert!(export.get_transaction(txid).is_some());
+
+        let decoded = Zewif::try_from(Envelope::from(export.clone())).unwrap();
+        assert!(decoded.is_watch_only());
+        assert_eq!(decoded, export);
+    }
+
+    #[test]
+    fn test_watch_only_export_drops_attachments() {
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(
+            transparent::Address::new("t1view"),
+        )));
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+        zewif.add_transaction(txid, Transaction::new(txid));
+
+        // Vendor data and a field from a newer format at every level, with the
+        // transaction held lazily.
+        let envelope = Envelope::from(zewif)
+            .add_attachment("secret", "org.example", None::<&str>)
+            .add_assertion("future_key", "secret");
+        let mut zewif = Zewif::try_from(envelope).unwrap();
+        let wallet = &mut zewif.wallets_mut()[0];
+        wallet
+            .attachments_mut()
+            .add("secret", "org.example", None::<&str>);
+        let account = &mut wallet.accounts_mut()[0];
+        account
+            .attachments_mut()
+            .add("secret", "org.example", None::<&str>);
+        account.addresses_mut()[0]
+            .attachments_mut()
+            .add("secret", "org.example", None::<&str>);
+        let mut transaction = Transaction::new(txid);
+        transaction
+            .attachments_mut()
+            .add("secret", "org.example", None::<&str>);
+        zewif.add_lazy_transaction(LazyTransaction::from(transaction));
+
+        let export = zewif.watch_only_export().unwrap();
+        assert!(export.attachments().is_empty());
+        let wallet = &export.wallets()[0];
+        assert!(wallet.attachments().is_empty());
+        assert!(wallet.accounts()[0].attachments().is_empty());
+        assert!(wallet.accounts()[0].addresses()[0].attachments().is_empty());
+        let transaction = export.load_transaction(txid).unwrap().unwrap();
+        assert!(transaction.attachments().is_empty());
+        let envelope = Envelope::from(export);
+        assert!(envelope.assertions_with_predicate("future_key").is_empty());
+    }
+
+    #[test]
+    fn test_visit_blobs() {
+        let mut p2pkh = transparent::Address::new("t1spend");
+        p2pkh.set_spend_authority(transparent::TransparentSpendAuthority::SpendingKey(
+            transparent::TransparentSpendingKey::new([1u8; 32]),
+        ));
+        let mut shielded = sapling::Address::new("zs1view".to_string());
+        shielded.set_incoming_viewing_key(sapling::SaplingIncomingViewingKey::new([2u8; 32]));
+        shielded.set_diversifier_index(Blob::new([3u8; 11]));
+
+        let mut account = Account::new();
+        account.add_address(Address::new(ProtocolAddress::Transparent(p2pkh)));
+        account.add_address(Address::new(ProtocolAddress::Sapling(Box::new(shielded))));
+        let mut output = SaplingSentOutput::new();
+        output.set_diversifier(Blob::new([4u8; 11]));
+        account.add_sapling_sent_output(output);
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.set_seed_material(SeedMaterial::Seed(Seed::new([5u8; 32])));
+        wallet.add_account(account);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet);
+
+        let mut blobs = Vec::new();
+        zewif.visit_blobs::<32, _>(|blob| blobs.push(blob.as_slice()[0]));
+        assert_eq!(blobs, [5, 1, 2]);
+
+        let mut blobs = Vec::new();
+        zewif.visit_blobs::<11, _>(|blob| blobs.push(blob.clone()));
+        assert_eq!(blobs, [Blob::new([3u8; 11]), Blob::new([4u8; 11])]);
+
+        let mut count = 0;
+        zewif.visit_blobs::<64, _>(|_| count += 1);
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_assert_roundtrip_identity() {
+        for _ in 0..10 {
+            Zewif::random().assert_roundtrip_identity().unwrap();
+        }
+
+        let zewif = Zewif::random();
+        let lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif)).unwrap();
+        lazy.assert_roundtrip_identity().unwrap();
+    }
+
+    /// A type whose envelope encoding loses its `note` field.
+    #[derive(Debug, Clone, PartialEq)]
+    struct Lossy {
+        name: String,
+        note: Option<String>,
+    }
+
+    impl From<Lossy> for Envelope {
+        fn from(value: Lossy) -> Self {
+            Envelope::new(value.name)
+        }
+    }
+
+    impl TryFrom<Envelope> for Lossy {
+        type Error = anyhow::Error;
+
+        fn try_from(envelope: Envelope) -> anyhow::Result<Self> {
+            Ok(Lossy {
+                name: envelope.extract_subject()?,
+                note: None,
+            })
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_identity_detects_loss() {
+        let complete = Lossy {
+            name: "kept".to_string(),
+            note: None,
+        };
+        check_roundtrip_identity("Lossy", &complete).unwrap();
+
+        let lossy = Lossy {
+            name: "kept".to_string(),
+            note: Some("dropped".to_string()),
+        };
+        let error = check_roundtrip_identity("Lossy", &lossy).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Lossy changed when decoded from its envelope"
+        );
+    }
+
+    #[test]
+    fn test_verify_spend_anchors() {
+        let valid = Anchor::random();
+        let unknown = Anchor::random();
+        let known_roots = HashSet::from([valid]);
+
+        let mut zewif = Zewif::new();
+        let mut valid_tx = Transaction::new(TxId::from_bytes([1u8; 32]));
+        valid_tx.add_sapling_anchor(valid);
+        valid_tx.set_orchard_anchor(Some(valid));
+        zewif.add_transaction(valid_tx.txid(), valid_tx);
+        let mut mixed_tx = Transaction::new(TxId::from_bytes([2u8; 32]));
+        mixed_tx.add_sapling_anchor(valid);
+        mixed_tx.add_sapling_anchor(unknown);
+        zewif.add_transaction(mixed_tx.txid(), mixed_tx);
+
+        assert_eq!(
+            zewif.verify_spend_anchors(&known_roots).unwrap(),
+            vec![(TxId::from_bytes([2u8; 32]), unknown)]
+        );
+
+        // Lazily held transactions are checked as well.
+        let mut lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif)).unwrap();
+        assert_eq!(
+            lazy.verify_spend_anchors(&known_roots).unwrap(),
+            vec![(TxId::from_bytes([2u8; 32]), unknown)]
+        );
+        let all_roots = HashSet::from([valid, unknown]);
+        assert!(lazy.verify_spend_anchors(&all_roots).unwrap().is_empty());
+
+        // A transaction that fails to decode is not taken as verified.
+        let txid = TxId::from_bytes([3u8; 32]);
+        let envelope = Envelope::from(Transaction::new(txid)).add_assertion("mined_height", "high");
+        lazy.add_lazy_transaction(LazyTransaction::new(envelope).unwrap());
+        assert!(lazy.verify_spend_anchors(&all_roots).is_err());
+    }
+
+    #[test]
+    fn test_merge_disjoint() {
+        let txids = [TxId::from_bytes([1u8; 32]), TxId::from_bytes([2u8; 32])];
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet_with_transactions(&txids[..1]));
+        zewif.add_transaction(txids[0], Transaction::new(txids[0]));
+        let mut other = Zewif::new();
+        other.add_wallet(wallet_with_transactions(&txids[1..]));
+        other.add_transaction(txids[1], Transaction::new(txids[1]));
+
+        let report = zewif.merge(other).unwrap();
+        assert_eq!(
+            report,
+            MergeReport {
+                wallets_added: 1,
+                transactions_added: 1,
+                ..Default::default()
+            }
+        );
+        assert_eq!(zewif.wallets_len(), 2);
+        assert_eq!(zewif.wallets()[1].index(), 1);
+        assert_eq!(zewif.transactions().len(), 2);
+        assert_eq!(zewif.wallets_referencing(txids[1]).len(), 1);
+    }
+
+    #[test]
+    fn test_merge_keeps_higher_export_height() {
+        let merged = |ours: Option<u32>, theirs: Option<u32>| {
+            let mut zewif = Zewif::new();
+            let mut other = Zewif::new();
+            if let Some(height) = ours {
+                zewif.set_export_height(BlockHeight::from(height));
+            }
+            if let Some(height) = theirs {
+                other.set_export_height(BlockHeight::from(height));
+            }
+            zewif.merge(other).unwrap();
+            zewif.export_height().map(u32::from)
+        };
+        assert_eq!(merged(Some(100), Some(200)), Some(200));
+        assert_eq!(merged(Some(200), Some(100)), Some(200));
+        assert_eq!(merged(None, Some(100)), Some(100));
+        assert_eq!(merged(Some(100), None), Some(100));
+        assert_eq!(merged(None, None), None);
+    }
+
+    #[test]
+    fn test_merge_shared_txids() {
+        let same = TxId::from_bytes([1u8; 32]);
+        let differing = TxId::from_bytes([2u8; 32]);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet_with_transactions(&[same]));
+        zewif.add_transaction(same, Transaction::new(same));
+        let mut ours = Transaction::new(differing);
+        ours.set_label("ours");
+        zewif.add_transaction(differing, ours.clone());
+
+        let mut other = Zewif::new();
+        other.add_wallet(wallet_with_transactions(&[same]));
+        other.add_transaction(same, Transaction::new(same));
+        let mut theirs = Transaction::new(differing);
+        theirs.set_label("theirs");
+        other.add_transaction(differing, theirs);
+
+        // Held lazily on both sides, the transactions are compared decoded.
+        let mut lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif)).unwrap();
+        let other = Zewif::try_from_envelope_lazy(Envelope::from(other)).unwrap();
+        let report = lazy.merge(other).unwrap();
+        assert_eq!(
+            report,
+            MergeReport {
+                duplicate_wallets: 1,
+                duplicate_transactions: 1,
+                conflicting_transactions: vec![differing],
+                ..Default::default()
+            }
+        );
+        assert!(report.has_conflicts());
+        assert_eq!(lazy.wallets_len(), 1);
+        assert_eq!(lazy.load_transaction(differing).unwrap(), Some(&ours));
+    }
+
+    #[test]
+    fn test_validate() {
+        let present = TxId::from_bytes([1u8; 32]);
+        let missing = TxId::from_bytes([2u8; 32]);
+        let misfiled = TxId::from_bytes([3u8; 32]);
+        let early = TxId::from_bytes([4u8; 32]);
+
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(wallet_with_transactions(&[present]));
+        zewif.add_transaction(present, Transaction::new(present));
+        assert!(zewif.validate().is_empty());
+
+        zewif.add_wallet(wallet_with_transactions(&[present, missing]));
+        zewif.add_transaction(misfiled, Transaction::new(present));
+        let mut tx = Transaction::new(early);
+        tx.set_target_height(BlockHeight::from(100u32));
+        tx.set_mined_height(BlockHeight::from(99u32));
+        zewif.add_transaction(early, tx);
+        zewif.wallets_mut()[1].set_index(0);
+
+        assert_eq!(
+            zewif.validate(),
+            vec![
+                ValidationIssue::DuplicateWalletIndex(0),
+                ValidationIssue::MissingTransaction {
+                    wallet: 0,
+                    account: 0,
+                    txid: missing,
+                },
+                ValidationIssue::TransactionKeyMismatch {
+                    key: misfiled,
+                    txid: present,
+                },
+                ValidationIssue::MinedBeforeTarget {
+                    txid: early,
+                    target_height: BlockHeight::from(100u32),
+                    mined_height: BlockHeight::from(99u32),
+                },
+            ]
+        );
+
+        // A lazily held transaction that fails to decode is reported, not
+        // skipped.
+        let broken = TxId::from_bytes([5u8; 32]);
+        let envelope =
+            Envelope::from(Transaction::new(broken)).add_assertion("mined_height", "high");
+        zewif.add_lazy_transaction(LazyTransaction::new(envelope).unwrap());
+        let issues = zewif.validate();
+        assert_eq!(issues.len(), 5);
+        assert!(matches!(
+            &issues[4],
+            ValidationIssue::UndecodableTransaction(txid, _) if *txid == broken
+        ));
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = SigningPrivateKey::new_schnorr(ECPrivateKey::new());
+        let public_key = key.public_key().unwrap();
+        let zewif = Zewif::random();
+        let signature = zewif.sign(&key).unwrap();
+        assert!(zewif.verify_signature(&signature, &public_key));
+
+        // The signature still verifies once the export has been decoded.
+        let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+        assert!(decoded.verify_signature(&signature, &public_key));
+        let lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif.clone())).unwrap();
+        assert!(lazy.verify_signature(&signature, &public_key));
+
+        let other_key = SigningPrivateKey::new_schnorr(ECPrivateKey::new());
+        assert!(!zewif.verify_signature(&signature, &other_key.public_key().unwrap()));
+    }
+
+    #[test]
+    fn test_signature_detects_tampering() {
+        let key = SigningPrivateKey::new_schnorr(ECPrivateKey::new());
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut zewif = Zewif::new();
+        zewif.add_transaction(txid, Transaction::new(txid));
+        let signature = zewif.sign(&key).unwrap();
+
+        let mut tampered = zewif.clone();
+        let mut transaction = Transaction::new(txid);
+        transaction.set_label("altered");
+        tampered.add_transaction(txid, transaction);
+        assert!(!tampered.verify_signature(&signature, &key.public_key().unwrap()));
+
+        let mut extended = zewif;
+        extended.add_wallet(ZewifWallet::new(Network::Main));
+        assert!(!extended.verify_signature(&signature, &key.public_key().unwrap()));
+    }
+
+    #[test]
+    fn test_content_digest_ignores_id() {
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(ZewifWallet::new(Network::Main));
+        zewif.add_transaction(txid, Transaction::new(txid));
+        let mut copy = zewif.clone();
+        copy.id = ARID::new();
+        assert_eq!(copy.content_digest(), zewif.content_digest());
+
+        let mut transaction = Transaction::new(txid);
+        transaction.set_label("altered");
+        copy.add_transaction(txid, transaction);
+        assert_ne!(copy.content_digest(), zewif.content_digest());
+
+        // The signature, unlike the content digest, binds the identifier.
+        let key = SigningPrivateKey::new_schnorr(ECPrivateKey::new());
+        let signature = zewif.sign(&key).unwrap();
+        let relabelled = Zewif {
+            id: ARID::new(),
+            ..zewif.clone()
+        };
+        assert!(!relabelled.verify_signature(&signature, &key.public_key().unwrap()));
+    }
+
+    #[test]
+    fn test_encrypted_envelope_roundtrip() {
+        let zewif = Zewif::random();
+        let envelope = zewif.to_encrypted_envelope("passphrase").unwrap();
+        assert_eq!(envelope.extract_subject::<ARID>().unwrap(), zewif.id());
+        assert!(envelope.objects_for_predicate("wallet").is_empty());
+        assert!(envelope.objects_for_predicate("transaction").is_empty());
+        assert!(ZewifEnvelope::new(envelope.clone()).unwrap().is_locked());
+
+        // The salt and scrypt parameters are carried in the envelope, so it
+        // decrypts after a trip through its CBOR encoding.
+        let envelope = Envelope::try_from_cbor_data(envelope.to_cbor_data()).unwrap();
+        let decrypted = Zewif::from_encrypted_envelope(envelope, "passphrase").unwrap();
+        assert_eq!(decrypted, zewif);
+    }
+
+    #[test]
+    fn test_encrypted_envelope_wrong_passphrase() {
+        let zewif = Zewif::new();
+        let envelope = zewif.to_encrypted_envelope("passphrase").unwrap();
+        let error = Zewif::from_encrypted_envelope(envelope, "Passphrase").unwrap_err();
+        assert!(error.to_string().contains("passphrase may be wrong"));
+
+        // A plaintext export is not mistaken for an encrypted one.
+        assert!(Zewif::from_encrypted_envelope(Envelope::from(zewif), "passphrase").is_err());
+    }
+
+    #[test]
+    fn test_encrypted_envelope_is_not_plaintext() {
+        let envelope = Zewif::random().to_encrypted_envelope("passphrase").unwrap();
+        assert!(Zewif::try_from(envelope.clone()).is_err());
+        assert!(Zewif::try_from_envelope_lazy(envelope).is_err());
+
+        // An export encrypted with a symmetric key is rejected the same way.
+        let mut envelope = ZewifEnvelope::new(Envelope::from(Zewif::random())).unwrap();
+        envelope
+            .encrypt(&ZewifEnvelope::derive_encryption_key("password"))
+            .unwrap();
+        assert!(Zewif::try_from(envelope.envelope().clone()).is_err());
+    }
+
+    #[test]
+    fn test_signed_envelope() {
+        let key = PrivateKeyBase::new();
+        let zewif = Zewif::random();
+        let envelope = zewif.to_signed_envelope(&key).unwrap();
+        let envelope = Envelope::try_from_cbor_data(envelope.to_cbor_data()).unwrap();
+        let verified = Zewif::from_signed_envelope(envelope.clone(), &key.schnorr_public_keys());
+        assert_eq!(verified.unwrap(), zewif);
+
+        // Moving the signature onto a different body breaks it.
+        let signature = envelope.object_for_predicate(known_values::SIGNED).unwrap();
+        let mut tampered = zewif.clone();
+        let txid = TxId::from_bytes([0xAB; 32]);
+        tampered.add_transaction(txid, Transaction::new(txid));
+        let tampered = Envelope::from(tampered)
+            .wrap_envelope()
+            .add_assertion(known_values::SIGNED, signature);
+        assert!(Zewif::from_signed_envelope(tampered, &key.schnorr_public_keys()).is_err());
+
+        let other_key = PrivateKeyBase::new();
+        assert!(Zewif::from_signed_envelope(envelope, &other_key.schnorr_public_keys()).is_err());
+
+        // An unsigned export is rejected.
+        assert!(Zewif::from_signed_envelope(Envelope::from(zewif.clone()), &key).is_err());
+
+        // A signer that fails is reported as an error.
+        struct FailingSigner;
+
+        impl Signer for FailingSigner {
+            fn sign_with_options(
+                &self,
+                _message: &dyn AsRef<[u8]>,
+                _options: Option<SigningOptions>,
+            ) -> anyhow::Result<Signature> {
+                anyhow::bail!("no signing key available")
+            }
+        }
+
+        let error = zewif.to_signed_envelope(&FailingSigner).unwrap_err();
+        assert!(format!("{:#}", error).contains("no signing key available"));
+    }
+
+    #[test]
+    fn test_compressed_cbor_roundtrip() {
+        let zewif = Zewif::random();
+        let cbor = Envelope::from(zewif.clone()).to_cbor_data();
+        for level in [CompressionLevel::Fastest, CompressionLevel::Best] {
+            let bytes = zewif.to_compressed_cbor(level).unwrap();
+            assert_eq!(Zewif::from_compressed_cbor(&bytes).unwrap(), zewif);
+            assert_eq!(crate::decompress_container(&bytes).unwrap(), cbor);
+        }
+
+        // Uncompressed CBOR is not mistaken for a compressed container.
+        let error = Zewif::from_compressed_cbor(&cbor).unwrap_err();
+        assert!(error.to_string().contains("magic"));
+
+        let mut truncated = zewif.to_compressed_cbor(CompressionLevel::Default).unwrap();
+        truncated.truncate(truncated.len() / 2);
+        assert!(Zewif::from_compressed_cbor(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_transactions_by_height() {
+        let block = BlockHash::from_bytes([9u8; 32]);
+        let mut zewif = Zewif::new();
+        for (byte, height, index) in [
+            (5u8, Some(100u32), Some(1u32)),
+            (6, Some(100), Some(0)),
+            (2, Some(100), None),
+            (1, Some(100), None),
+            (7, Some(250), None),
+            (3, None, None),
+            (4, None, Some(0)),
+        ] {
+            let txid = TxId::from_bytes([byte; 32]);
+            let mut transaction = Transaction::new(txid);
+            if let Some(height) = height {
+                transaction.set_mined_height(BlockHeight::from(height));
+            }
+            transaction.set_block_position(index.map(|index| TxBlockPosition::new(block, index)));
+            zewif.add_transaction(txid, transaction);
+        }
+        let order = |zewif: &Zewif| -> Vec<TxId> {
+            zewif
+                .transactions_by_height()
+                .unwrap()
+                .map(Transaction::txid)
+                .collect()
+        };
+        let first = order(&zewif);
+        assert_eq!(
+            first,
+            [6u8, 5, 1, 2, 7, 4, 3].map(|byte| TxId::from_bytes([byte; 32]))
+        );
+        assert_eq!(order(&zewif), first);
+
+        // The same content held lazily yields the same order.
+        let lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif)).unwrap();
+        assert_eq!(order(&lazy), first);
+        assert_eq!(order(&lazy), first);
+
+        // A transaction that fails to decode is reported, not dropped.
+        let mut broken = lazy;
+        let txid = TxId::from_bytes([8u8; 32]);
+        let envelope = Envelope::from(Transaction::new(txid)).add_assertion("mined_height", "high");
+        broken.add_lazy_transaction(LazyTransaction::new(envelope).unwrap());
+        assert!(broken.transactions_by_height().is_err());
+    }
+
+    #[test]
+    fn test_content_eq_fast() {
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(ZewifWallet::new(Network::Main));
+        zewif.add_transaction(txid, Transaction::new(txid));
+
+        let lazy = Zewif::try_from_envelope_lazy(Envelope::from(zewif.clone())).unwrap();
+        assert!(zewif.content_eq_fast(&zewif.clone()));
+        assert!(zewif.content_eq_fast(&lazy));
+        assert!(lazy.content_eq_fast(&zewif));
+
+        // Same identifier and counts, different content.
+        let mut relabeled = zewif.clone();
+        let mut transaction = Transaction::new(txid);
+        transaction.set_label("altered");
+        relabeled.add_transaction(txid, transaction);
+        assert!(!zewif.content_eq_fast(&relabeled));
+
+        let mut extended = zewif.clone();
+        extended.add_wallet(ZewifWallet::new(Network::Test));
+        assert!(!zewif.content_eq_fast(&extended));
+
+        let mut other_id = Zewif::new();
+        other_id.add_wallet(ZewifWallet::new(Network::Main));
+        other_id.add_transaction(txid, Transaction::new(txid));
+        assert!(!zewif.content_eq_fast(&other_id));
+
+        let random = Zewif::random();
+        assert!(random.content_eq_fast(&random.clone()));
+    }
+
+    #[test]
+    fn test_content_digest_cache_cleared_on_change() {
+        let txid = TxId::from_bytes([1u8; 32]);
+        let mut zewif = Zewif::new();
+        zewif.add_wallet(ZewifWallet::new(Network::Main));
+        zewif.add_transaction(txid, Transaction::new(txid));
+        let copy = zewif.clone();
+        assert!(zewif.content_eq_fast(&copy));
+
+        zewif
+            .wallet_mut(0)
+            .unwrap()
+            .set_seed_material(SeedMaterial::Seed(Seed::new([7u8; 32])));
+        assert!(!zewif.content_eq_fast(&copy));
+        let mut zewif = copy.clone();
+        assert!(zewif.content_eq_fast(&copy));
+
+        zewif
+            .load_transaction_mut(txid)
+            .unwrap()
+            .unwrap()
+            .set_label("altered");
+        assert!(!zewif.content_eq_fast(&copy));
+        let mut zewif = copy.clone();
+        assert!(zewif.content_eq_fast(&copy));
+
+        zewif.add_attachment("data", "com.example", None);
+        assert!(!zewif.content_eq_fast(&copy));
+        assert_ne!(zewif.content_digest(), copy.content_digest());
+
+        let watch_only = copy.watch_only_export().unwrap();
+        assert_eq!(watch_only.content_digest(), copy.content_digest());
+        assert!(zewif.watch_only_export().unwrap().content_eq_fast(&copy));
+    }
+
+    /// Reports the time taken to compare every pair of a set of large
+    /// containers that differ in a single transaction, with `==` and with
+    /// `content_eq_fast` once the content digests are cached, along with the
+    /// time taken to compute those digests. Run with
+    /// `cargo test --release -- --ignored --nocapture content_eq_fast_speed`.
+    #[test]
+    #[ignore]
+    fn test_content_eq_fast_speed() {
+        let id = ARID::new();
+        let mut base = Zewif::with_id(id);
+        let mut wallet = ZewifWallet::new(Network::Main);
+        let mut account = Account::new();
+        for n in 0..2_000u32 {
+            let mut bytes = [0u8; 32];
+            bytes[..4].copy_from_slice(&n.to_le_bytes());
+            let txid = TxId::from_bytes(bytes);
+            let mut transaction = Transaction::new(txid);
+            transaction.set_mined_height(BlockHeight::from(1_000_000 + n / 10));
+            transaction.set_label(format!("Transaction {}", n));
+            base.add_transaction(txid, transaction);
+            account.add_relevant_transaction(txid);
+        }
+        wallet.add_account(account);
+        base.add_wallet(wallet);
+        let containers: Vec<Zewif> = (0..10u32)
+            .map(|i| {
+                let mut zewif = base.clone();
+                let mut bytes = [0u8; 32];
+                bytes[..4].copy_from_slice(&(i * 200).to_le_bytes());
+                zewif
+                    .load_transaction_mut(TxId::from_bytes(bytes))
+                    .unwrap()
+                    .unwrap()
+                    .set_label("altered");
+                zewif
+            })
+            .collect();
+
+        let compare_all = |eq: &dyn Fn(&Zewif, &Zewif) -> bool| {
+            let start = std::time::Instant::now();
+            let mut equal = 0;
+            for _ in 0..20 {
+                for a in &containers {
+                    for b in &containers {
+                        equal += usize::from(eq(a, b));
+                    }
+                }
+            }
+            assert_eq!(equal, 20 * containers.len());
+            start.elapsed()
+        };
+        let start = std::time::Instant::now();
+        for zewif in &containers {
+            zewif.content_digest();
+        }
+        let digests = start.elapsed();
+        let structural = compare_all(&|a, b| a == b);
+        let fast = compare_all(&|a, b| a.content_eq_fast(b));
+
+        println!(
+            "{} comparisons: == {:?}, content_eq_fast {:?} after {:?} computing digests",
+            20 * containers.len() * containers.len(),
+            structural,
+            fast,
+            digests
+        );
+        assert!(fast < structural);
+    }
+
+    #[test]
+    fn test_remove_wallet() {
+        let mut zewif = Zewif::new();
+        for network in [
+            Network::Main,
+            Network::Test,
+            Network::Regtest,
+            Network::Main,
+        ] {
+            zewif.add_wallet(ZewifWallet::new(network));
+        }
+        zewif.wallet_mut(3).unwrap().add_account(Account::new());
+        assert!(zewif.wallet(4).is_none());
+        assert!(zewif.remove_wallet(4).is_none());
+
+        let removed = zewif.remove_wallet(1).unwrap();
+        assert_eq!(removed.network(), Network::Test);
+        assert_eq!(removed.index(), 1);
+
+        let wallets: Vec<(usize, Network)> = zewif
+            .wallets()
+            .iter()
+            .map(|wallet| (wallet.index(), wallet.network()))
+            .collect();
+        assert_eq!(
+            wallets,
+            [
+                (0, Network::Main),
+                (1, Network::Regtest),
+                (2, Network::Main)
+            ]
+        );
+        assert_eq!(zewif.wallet(1).unwrap().network(), Network::Regtest);
+        assert_eq!(zewif.wallet(2).unwrap().accounts().len(), 1);
+        assert!(zewif.validate().is_empty());
+
+        // A wallet added afterwards takes the next contiguous index.
+        zewif.add_wallet(ZewifWallet::new(Network::Test));
+        assert_eq!(zewif.wallet(3).unwrap().index(), 3);
+
+        let decoded = Zewif::try_from(Envelope::from(zewif.clone())).unwrap();
+        assert_eq!(decoded, zewif);
+    }
+
+    #[test]
+    fn test_write_envelope() {
+        let mut large = Zewif::new();
+        for n in 0..300u16 {
+            let mut bytes = [0u8; 32];
+            bytes[..2].copy_from_slice(&n.to_le_bytes());
+            let txid = TxId::from_bytes(bytes);
+            large.add_transaction(txid, Transaction::new(txid));
+        }
+        let containers = (0..10)
+            .map(|_| Zewif::random())
+            .chain([Zewif::new(), large]);
+        for zewif in containers {
+            let expected = Envelope::from(zewif.clone()).to_cbor_data();
+            let mut bytes = Vec::new();
+            zewif.write_envelope(&mut bytes).unwrap();
+            assert_eq!(bytes, expected);
+
+            let envelope = Envelope::try_from_cbor_data(bytes).unwrap();
+            assert_eq!(Zewif::try_from(envelope.clone()).unwrap(), zewif);
+            let lazy = Zewif::try_from_envelope_lazy(envelope).unwrap();
+            let mut lazy_bytes = Vec::new();
+            lazy.write_envelope(&mut lazy_bytes).unwrap();
+            assert_eq!(lazy_bytes, expected);
+        }
+    }
+
+    /// Reports the peak resident set size of writing a 100,000-transaction
+    /// container, compared with building its envelope in memory. Run with
+    /// `cargo test --release -- --ignored --nocapture write_envelope_peak`.
+    #[test]
+    #[ignore]
+    #[cfg(target_os = "linux")]
+    fn test_write_envelope_peak_memory() {
+        fn peak_rss_kb() -> u64 {
+            let status = std::fs::read_to_string("/proc/self/status").unwrap();
+            let line = status
+                .lines()
+                .find(|line| line.starts_with("VmHWM:"))
+                .unwrap();
+            line.split_whitespace().nth(1).unwrap().parse().unwrap()
+        }
+
+        /// A writer that discards its input and counts the bytes written.
+        struct CountingSink(u64);
+
+        impl std::io::Write for CountingSink {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0 += buf.len() as u64;
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut zewif = Zewif::new();
+        let mut account = Account::new();
+        for n in 0..100_000u32 {
+            let mut bytes = [0u8; 32];
+            bytes[..4].copy_from_slice(&n.to_le_bytes());
+            let txid = TxId::from_bytes(bytes);
+            let mut transaction = Transaction::new(txid);
+            transaction.set_mined_height(BlockHeight::from(1_000_000 + n / 10));
+            transaction.set_label(format!("Transaction {}", n));
+            zewif.add_transaction(txid, transaction);
+            account.add_relevant_transaction(txid);
+        }
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(account);
+        zewif.add_wallet(wallet);
+
+        let before = peak_rss_kb();
+        let mut sink = CountingSink(0);
+        zewif.write_envelope(&mut sink).unwrap();
+        let streamed = peak_rss_kb();
+        let encoded = Envelope::from(zewif).to_cbor_data();
+        let in_memory = peak_rss_kb();
+        assert_eq!(encoded.len() as u64, sink.0);
+
+        println!(
+            "{} bytes; peak RSS growth: streaming {} KiB, in memory {} KiB",
+            sink.0,
+            streamed - before,
+            in_memory - before
+        );
+        assert!(streamed - before < in_memory - streamed);
+    }
+}
@@ -1,10 +1,12 @@
 use super::Network;
-use super::{Account, SeedMaterial};
+use super::{Account, Address, AddressSortKey, SeedFingerprint, SeedMaterial};
 use crate::{
-    Indexed, NoQuotesDebugOption, envelope_indexed_objects_for_predicate, test_envelope_roundtrip,
+    Blob, Indexed, NoQuotesDebugOption, SetIndexes, decode_attachments, encode_attachments,
+    envelope_indexed_objects_for_predicate, keys, test_envelope_roundtrip,
 };
 use anyhow::Context;
 use bc_envelope::prelude::*;
+use std::collections::BTreeMap;
 
 /// A complete Zcash wallet with multiple accounts and cryptographic key material.
 ///
@@ -30,7 +32,11 @@ use bc_envelope::prelude::*;
 ///
 /// - **Network**: The Zcash network context (mainnet, testnet, regtest)
 /// - **Seed Material**: When available, the cryptographic material used for key generation
+/// - **Additional Seeds**: Further independent seeds bundled into the same wallet file,
+///   keyed by their fingerprints so accounts can refer to the seed they derive from
 /// - **Accounts**: All accounts contained within the wallet, with their full structure
+/// - **Viewing Keys**: The unified full viewing key of each account, keyed by account
+///   index, so watch-only wallets and light clients can resume scanning
 /// - **Vendor-Specific Information**: Custom metadata stored in attachments
 ///
 /// # Examples
@@ -54,7 +60,9 @@ pub struct ZewifWallet {
     index: usize,
     network: Network,
     seed_material: Option<SeedMaterial>,
+    seeds: BTreeMap<SeedFingerprint, SeedMaterial>,
     accounts: Vec<Account>,
+    viewing_keys: BTreeMap<usize, keys::UnifiedFullViewingKey>,
     attachments: Attachments,
 }
 
@@ -74,7 +82,9 @@ impl std::fmt::Debug for ZewifWallet {
             .field("index", &self.index)
             .field("network", &self.network)
             .field("seed_material", &NoQuotesDebugOption(&self.seed_material))
+            .field("seeds", &self.seeds)
             .field("accounts", &self.accounts)
+            .field("viewing_keys", &self.viewing_keys)
             .field("attachments", &self.attachments)
             .finish()
     }
@@ -88,7 +98,9 @@ impl ZewifWallet {
             index: 0,
             network,
             seed_material: None,
+            seeds: BTreeMap::new(),
             accounts: Vec::new(),
+            viewing_keys: BTreeMap::new(),
             attachments: Attachments::new(),
         }
     }
@@ -105,14 +117,179 @@ impl ZewifWallet {
         self.seed_material = Some(seed_material);
     }
 
+    /// Returns the seeds held by this wallet, keyed by fingerprint.
+    ///
+    /// Wallet files that bundle several independent seeds keep each one here,
+    /// and each account records the fingerprint of the seed it was derived from
+    /// via [`Account::seed_fingerprint`]. This is independent of
+    /// [`seed_material`](Self::seed_material), which holds a wallet's single
+    /// seed when the source format does not identify it by fingerprint.
+    pub fn seeds(&self) -> &BTreeMap<SeedFingerprint, SeedMaterial> {
+        &self.seeds
+    }
+
+    pub fn seed(&self, fingerprint: &SeedFingerprint) -> Option<&SeedMaterial> {
+        self.seeds.get(fingerprint)
+    }
+
+    /// Adds a seed under the given fingerprint, returning any seed it replaces.
+    pub fn add_seed(
+        &mut self,
+        fingerprint: SeedFingerprint,
+        seed_material: SeedMaterial,
+    ) -> Option<SeedMaterial> {
+        self.seeds.insert(fingerprint, seed_material)
+    }
+
+    /// Returns the accounts derived from the seed with the given fingerprint.
+    pub fn accounts_for_seed(&self, fingerprint: &SeedFingerprint) -> Vec<&Account> {
+        self.accounts
+            .iter()
+            .filter(|account| account.seed_fingerprint() == Some(fingerprint))
+            .collect()
+    }
+
     pub fn accounts(&self) -> &Vec<Account> {
         &self.accounts
     }
 
+    pub fn accounts_mut(&mut self) -> &mut [Account] {
+        &mut self.accounts
+    }
+
     pub fn add_account(&mut self, mut account: Account) {
         account.set_index(self.accounts.len());
         self.accounts.push(account);
     }
+
+    /// Reassigns contiguous indexes, in storage order, to the wallet's accounts
+    /// and to everything they contain.
+    ///
+    /// A viewing key stored under an account's old index follows that account
+    /// to its new index, unless the old index was shared by several accounts,
+    /// in which case the key is left where it is.
+    pub fn reindex_all(&mut self) {
+        let old_indexes: Vec<usize> = self.accounts.iter().map(Indexed::index).collect();
+        self.accounts = std::mem::take(&mut self.accounts).set_indexes();
+        for account in &mut self.accounts {
+            account.reindex_all();
+        }
+
+        let mut viewing_keys = BTreeMap::new();
+        for (old_index, ufvk) in std::mem::take(&mut self.viewing_keys) {
+            let mut positions = old_indexes
+                .iter()
+                .enumerate()
+                .filter(|(_, index)| **index == old_index)
+                .map(|(position, _)| position);
+            let new_index = match (positions.next(), positions.next()) {
+                (Some(position), None) => position,
+                _ => old_index,
+            };
+            viewing_keys.insert(new_index, ufvk);
+        }
+        self.viewing_keys = viewing_keys;
+    }
+
+    /// Returns `true` if the wallet holds no seeds and no address spending keys.
+    ///
+    /// Viewing keys are permitted, so a watch-only wallet can still detect and
+    /// decrypt its transactions.
+    pub fn is_watch_only(&self) -> bool {
+        self.seed_material.is_none()
+            && self.seeds.is_empty()
+            && self
+                .accounts
+                .iter()
+                .flat_map(|account| account.addresses())
+                .all(|address| !address.address().has_spending_key())
+    }
+
+    /// Removes the wallet's seeds and every address spending key, keeping
+    /// viewing keys, addresses and history.
+    ///
+    /// Attachments are kept as they are; vendor data that holds key material
+    /// must be removed by the caller.
+    pub fn clear_spending_material(&mut self) {
+        self.seed_material = None;
+        self.seeds.clear();
+        for account in &mut self.accounts {
+            for address in account.addresses_mut() {
+                address.address_mut().clear_spending_key();
+            }
+        }
+    }
+
+    /// Calls `f` with every `Blob<N>` held by the wallet's seeds, accounts and
+    /// viewing keys.
+    ///
+    /// See [`Zewif::visit_blobs`](crate::Zewif::visit_blobs) for the fields
+    /// that are visited.
+    pub fn visit_blobs<const N: usize, F: FnMut(&Blob<N>)>(&self, mut f: F) {
+        let seeds = self.seed_material.iter().chain(self.seeds.values());
+        for seed_material in seeds {
+            if let SeedMaterial::Seed(seed) = seed_material {
+                seed.as_blob().visit_sized(&mut f);
+            }
+        }
+        for account in &self.accounts {
+            account.visit_blobs(&mut f);
+        }
+        for ufvk in self.viewing_keys.values() {
+            if let Some(key) = ufvk.transparent() {
+                key.as_blob().visit_sized(&mut f);
+            }
+            if let Some(key) = ufvk.sapling() {
+                key.as_blob().visit_sized(&mut f);
+            }
+            if let Some(key) = ufvk.orchard() {
+                key.as_blob().visit_sized(&mut f);
+            }
+        }
+    }
+
+    /// Returns the unified full viewing keys of the wallet's accounts, keyed by
+    /// account index.
+    pub fn viewing_keys(&self) -> &BTreeMap<usize, keys::UnifiedFullViewingKey> {
+        &self.viewing_keys
+    }
+
+    pub fn viewing_key(&self, account_index: usize) -> Option<&keys::UnifiedFullViewingKey> {
+        self.viewing_keys.get(&account_index)
+    }
+
+    /// Sets the viewing key of the account at `account_index`, returning any key
+    /// it replaces.
+    pub fn set_viewing_key(
+        &mut self,
+        account_index: usize,
+        ufvk: keys::UnifiedFullViewingKey,
+    ) -> Option<keys::UnifiedFullViewingKey> {
+        self.viewing_keys.insert(account_index, ufvk)
+    }
+
+    /// Returns all addresses across the wallet's accounts, ordered by `key`.
+    ///
+    /// The stored order is left untouched; addresses that compare equal under
+    /// `key` keep their storage order.
+    pub fn addresses_sorted_by(&self, key: AddressSortKey) -> Vec<&Address> {
+        let mut addresses: Vec<(usize, &Address)> = self
+            .accounts
+            .iter()
+            .enumerate()
+            .flat_map(|(position, account)| {
+                account
+                    .addresses()
+                    .iter()
+                    .map(move |address| (position, address))
+            })
+            .collect();
+        addresses.sort_by(|(a_position, a), (b_position, b)| match key {
+            AddressSortKey::Index => a_position.cmp(b_position).then(key.compare(a, b)),
+            _ => key.compare(a, b),
+        });
+        addresses.into_iter().map(|(_, address)| address).collect()
+    }
 }
 
 #[rustfmt::skip]
@@ -123,9 +300,17 @@ impl From<ZewifWallet> for Envelope {
             .add_assertion("network", value.network)
             .add_optional_assertion("seed_material", value.seed_material);
 
+        e = value.seeds.into_iter().fold(e, |e, (fingerprint, seed_material)| {
+            e.add_assertion("seed", Envelope::new(fingerprint).add_assertion("seed_material", seed_material))
+        });
+
         e = value.accounts.iter().fold(e, |e, account| e.add_assertion("account", account.clone()));
 
-        value.attachments.add_to_envelope(e)
+        e = value.viewing_keys.into_iter().fold(e, |e, (account_index, ufvk)| {
+            e.add_assertion("viewing_key", Envelope::new(account_index).add_assertion("ufvk", ufvk))
+        });
+
+        encode_attachments(&value.attachments, e)
     }
 }
 
@@ -139,33 +324,242 @@ impl TryFrom<Envelope> for ZewifWallet {
         let network = envelope.extract_object_for_predicate("network")?;
         let seed_material = envelope.try_optional_object_for_predicate("seed_material")?;
 
+        let mut seeds = BTreeMap::new();
+        for seed in envelope.objects_for_predicate("seed") {
+            let fingerprint = seed.extract_subject().context("seed fingerprint")?;
+            let seed_material = seed.try_object_for_predicate("seed_material").context("seed_material")?;
+            seeds.insert(fingerprint, seed_material);
+        }
+
         let accounts = envelope_indexed_objects_for_predicate(&envelope, "account").context("accounts")?;
 
-        let attachments = Attachments::try_from_envelope(&envelope).context("attachments")?;
+        let mut viewing_keys = BTreeMap::new();
+        for viewing_key in envelope.objects_for_predicate("viewing_key") {
+            let account_index = viewing_key.extract_subject().context("viewing_key account index")?;
+            let ufvk = viewing_key.try_object_for_predicate("ufvk").context("ufvk")?;
+            viewing_keys.insert(account_index, ufvk);
+        }
+
+        let attachments = decode_attachments::<Self>(&envelope).context("attachments")?;
 
         Ok(Self {
             index,
             network,
             seed_material,
+            seeds,
             accounts,
+            viewing_keys,
             attachments,
         })
     }
 }
 
+impl crate::EnvelopeSchema for ZewifWallet {
+    const TYPE_NAME: &'static str = "ZewifWallet";
+    const ATTACHMENTS: bool = true;
+
+    fn required_predicates() -> &'static [&'static str] {
+        &["network"]
+    }
+
+    fn optional_predicates() -> &'static [&'static str] {
+        &["seed_material", "seed", "account", "viewing_key"]
+    }
+
+    fn check_nested_strict(envelope: &Envelope) -> anyhow::Result<()> {
+        crate::check_objects_strict::<Account>(envelope, "account")
+    }
+}
+
 #[cfg(test)]
 impl crate::RandomInstance for ZewifWallet {
     fn random() -> Self {
-        use crate::SetIndexes;
-
         Self {
             index: 0,
             network: Network::random(),
             seed_material: SeedMaterial::opt_random(),
+            seeds: BTreeMap::random(),
             accounts: Vec::random().set_indexes(),
+            viewing_keys: BTreeMap::random(),
             attachments: Attachments::random(),
         }
     }
 }
 
 test_envelope_roundtrip!(ZewifWallet);
+
+#[cfg(test)]
+mod tests {
+    use bc_envelope::prelude::*;
+
+    use crate::{
+        Account, Address, AddressSortKey, Bip39Mnemonic, Indexed, MnemonicLanguage, Network,
+        ProtocolAddress, Seed, SeedFingerprint, SeedMaterial, UnifiedAddress, keys, sapling,
+        transparent,
+    };
+
+    use super::ZewifWallet;
+
+    fn named(address: ProtocolAddress, name: &str) -> Address {
+        let mut address = Address::new(address);
+        address.set_name(name.to_string());
+        address
+    }
+
+    /// A wallet whose addresses are in a different order under each sort key.
+    fn wallet() -> ZewifWallet {
+        let mut first = Account::new();
+        first.add_address(named(
+            ProtocolAddress::Unified(Box::new(UnifiedAddress::new("u1first".to_string()))),
+            "Alice",
+        ));
+        first.add_address(named(
+            ProtocolAddress::Transparent(transparent::Address::new("t1first")),
+            "Dave",
+        ));
+        let mut second = Account::new();
+        second.add_address(named(
+            ProtocolAddress::Sapling(Box::new(sapling::Address::new("zs1second".to_string()))),
+            "Bob",
+        ));
+        second.add_address(named(
+            ProtocolAddress::Transparent(transparent::Address::new("t1second")),
+            "Carol",
+        ));
+
+        let mut wallet = ZewifWallet::new(Network::Main);
+        wallet.add_account(first);
+        wallet.add_account(second);
+        wallet
+    }
+
+    fn address_strings(addresses: Vec<&Address>) -> Vec<String> {
+        addresses.into_iter().map(|a| a.as_string()).collect()
+    }
+
+    #[test]
+    fn test_addresses_sorted_by_name() {
+        let wallet = wallet();
+        let sorted = address_strings(wallet.addresses_sorted_by(AddressSortKey::Name));
+        assert_eq!(sorted, vec!["u1first", "zs1second", "t1second", "t1first"]);
+
+        // Storage order is untouched.
+        let stored: Vec<String> = wallet
+            .accounts()
+            .iter()
+            .flat_map(|account| account.addresses())
+            .map(|address| address.as_string())
+            .collect();
+        assert_eq!(stored, vec!["u1first", "t1first", "zs1second", "t1second"]);
+    }
+
+    #[test]
+    fn test_addresses_sorted_by_index() {
+        let mut wallet = wallet();
+        let sorted = address_strings(wallet.addresses_sorted_by(AddressSortKey::Index));
+        assert_eq!(sorted, vec!["u1first", "t1first", "zs1second", "t1second"]);
+
+        // Indexes out of storage order, as in a hand-edited container.
+        let first = &mut wallet.accounts_mut()[0];
+        first.addresses_mut()[0].set_index(1);
+        first.addresses_mut()[1].set_index(0);
+        let sorted = address_strings(wallet.addresses_sorted_by(AddressSortKey::Index));
+        assert_eq!(sorted, vec!["t1first", "u1first", "zs1second", "t1second"]);
+    }
+
+    #[test]
+    fn test_addresses_sorted_by_protocol() {
+        let wallet = wallet();
+        let sorted = address_strings(wallet.addresses_sorted_by(AddressSortKey::Protocol));
+        assert_eq!(sorted, vec!["t1first", "t1second", "zs1second", "u1first"]);
+    }
+
+    #[test]
+    fn test_addresses_sorted_by_created_at_height() {
+        let mut wallet = wallet();
+        let mut account = Account::new();
+        let mut late = named(
+            ProtocolAddress::Transparent(transparent::Address::new("t1late")),
+            "Late",
+        );
+        late.set_created_at_height(crate::BlockHeight::from_u32(200));
+        let mut early = named(
+            ProtocolAddress::Transparent(transparent::Address::new("t1early")),
+            "Early",
+        );
+        early.set_created_at_height(crate::BlockHeight::from_u32(100));
+        account.add_address(late);
+        account.add_address(early);
+        wallet.add_account(account);
+
+        let sorted = address_strings(wallet.addresses_sorted_by(AddressSortKey::CreatedAtHeight));
+        assert_eq!(
+            sorted,
+            vec![
+                "t1early",
+                "t1late",
+                "u1first",
+                "t1first",
+                "zs1second",
+                "t1second"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_two_seed_wallet_roundtrip() {
+        let mnemonic_fp = SeedFingerprint::from_bytes([1u8; 32]);
+        let raw_fp = SeedFingerprint::from_bytes([2u8; 32]);
+
+        let mut wallet = ZewifWallet::new(Network::Test);
+        wallet.add_seed(
+            mnemonic_fp,
+            SeedMaterial::Bip39Mnemonic(Bip39Mnemonic::new(
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                Some(MnemonicLanguage::English),
+            )),
+        );
+        wallet.add_seed(raw_fp, SeedMaterial::Seed(Seed::new([3u8; 32])));
+        for (fingerprint, name) in [
+            (mnemonic_fp, "first"),
+            (raw_fp, "second"),
+            (raw_fp, "third"),
+        ] {
+            let mut account = Account::new();
+            account.set_name(name);
+            account.set_zip32_account_id(0);
+            account.set_seed_fingerprint(fingerprint);
+            wallet.add_account(account);
+        }
+
+        let decoded = ZewifWallet::try_from(Envelope::from(wallet.clone())).unwrap();
+        assert_eq!(decoded, wallet);
+        assert_eq!(decoded.seeds().len(), 2);
+        assert!(matches!(decoded.seed(&raw_fp), Some(SeedMaterial::Seed(_))));
+        let names: Vec<&str> = decoded
+            .accounts_for_seed(&raw_fp)
+            .iter()
+            .map(|account| account.name())
+            .collect();
+        assert_eq!(names, ["second", "third"]);
+    }
+
+    #[test]
+    fn test_viewing_keys_roundtrip() {
+        let mut wallet = wallet();
+        let mut first = keys::UnifiedFullViewingKey::new();
+        first.set_transparent(keys::TransparentAccountPubKey::new([1u8; 65]));
+        first.set_sapling(keys::SaplingDiversifiableFullViewingKey::new([2u8; 128]));
+        first.set_orchard(keys::OrchardFullViewingKey::new([3u8; 96]));
+        let mut second = keys::UnifiedFullViewingKey::new();
+        second.set_sapling(keys::SaplingDiversifiableFullViewingKey::new([4u8; 128]));
+        wallet.set_viewing_key(0, first.clone());
+        wallet.set_viewing_key(1, second.clone());
+
+        let decoded = ZewifWallet::try_from(Envelope::from(wallet.clone())).unwrap();
+        assert_eq!(decoded, wallet);
+        assert_eq!(decoded.viewing_key(0), Some(&first));
+        assert_eq!(decoded.viewing_key(1), Some(&second));
+        assert!(decoded.viewing_key(2).is_none());
+    }
+}